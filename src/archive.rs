@@ -0,0 +1,273 @@
+//! Templates, that are distributed as archive files (`.tar.gz`, `.tar.zst`, `.zip`, ...)
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use fs_err as fs;
+use log::warn;
+use std::{
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::emoji;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Tar,
+    TarGz,
+    TarZst,
+    Zip,
+}
+
+impl ArchiveKind {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        [
+            (".tar.gz", Self::TarGz),
+            (".tgz", Self::TarGz),
+            (".tar.zst", Self::TarZst),
+            (".tzst", Self::TarZst),
+            (".tar", Self::Tar),
+            (".zip", Self::Zip),
+        ]
+        .into_iter()
+        .find(|(extension, _)| name.ends_with(extension))
+        .map(|(_, kind)| kind)
+    }
+}
+
+/// Does `path` name an archive, judging by its extension?
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveKind::of(path).is_some()
+}
+
+/// Unpack `archive` into the directory `into`, and return the root of the template.
+///
+/// Entries, that would end up outside of `into`, are rejected. Links are skipped, just like
+/// for templates in a local directory. If the archive holds nothing but a single directory,
+/// as most release tarballs do, that directory is the root of the template.
+pub fn unpack(archive: &Path, into: &Path) -> Result<PathBuf> {
+    let kind = ArchiveKind::of(archive).with_context(|| {
+        format!(
+            "{} {} `{}`",
+            emoji::ERROR,
+            style("Unsupported archive format:").bold().red(),
+            style(archive.display()).bold().red(),
+        )
+    })?;
+    let file = fs::File::open(archive)?;
+    match kind {
+        ArchiveKind::Tar => unpack_tar(file, into),
+        ArchiveKind::TarGz => unpack_tar(flate2::read::GzDecoder::new(file), into),
+        ArchiveKind::TarZst => unpack_tar(zstd::Decoder::new(file)?, into),
+        ArchiveKind::Zip => unpack_zip(file.into_parts().0, into),
+    }
+    .with_context(|| format!("Failed to unpack the archive `{}`", archive.display()))?;
+
+    single_top_level_dir(into).map(|dir| dir.unwrap_or_else(|| into.to_path_buf()))
+}
+
+fn unpack_tar(reader: impl Read, into: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            fs::create_dir_all(into.join(safe_path(&path)?))?;
+        } else if entry_type.is_file() {
+            let destination = into.join(safe_path(&path)?);
+            create_parent(&destination)?;
+            entry.unpack(&destination)?;
+        } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+            skip_link(&path);
+        }
+        // other entries, like global pax headers, don't make it into the template
+    }
+    Ok(())
+}
+
+fn unpack_zip(file: std::fs::File, into: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let path = PathBuf::from(entry.name());
+        let destination = into.join(safe_path(&path)?);
+        if entry.is_symlink() {
+            skip_link(&path);
+        } else if entry.is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else {
+            create_parent(&destination)?;
+            io::copy(&mut entry, &mut fs::File::create(&destination)?)?;
+            #[cfg(unix)]
+            if let Some(mode) = entry.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                // no setuid, setgid or sticky bits, like for tar archives
+                let mode = mode & 0o777;
+                fs::set_permissions(&destination, std::fs::Permissions::from_mode(mode))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `path` of an archive entry, or an error if it would point outside of the archive
+fn safe_path(path: &Path) -> Result<PathBuf> {
+    let mut safe = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => safe.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => bail!(
+                "{} {} `{}`",
+                emoji::ERROR,
+                style("Archive entry points outside of the archive:")
+                    .bold()
+                    .red(),
+                style(path.display()).bold().red(),
+            ),
+        }
+    }
+    Ok(safe)
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+fn skip_link(path: &Path) {
+    warn!(
+        "{} {} `{}`",
+        emoji::WARN,
+        style("[Skipping] Links in archives are not supported")
+            .bold()
+            .red(),
+        style(path.display()).bold(),
+    );
+}
+
+/// The only entry of `dir`, if that is a directory
+fn single_top_level_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    let entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    Ok(match entries.as_slice() {
+        [only] if only.file_type()?.is_dir() => Some(only.path()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_gz(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            // `append_data` refuses `..`, so the name is set on the raw header
+            header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_cksum();
+            builder.append(&header, contents.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn archives_are_recognized_by_their_extension() {
+        for name in ["t.tar.gz", "t.TGZ", "t.tar.zst", "t.tzst", "t.tar", "t.zip"] {
+            assert!(is_archive(Path::new(name)), "{name}");
+        }
+        for name in ["t.gz", "t.toml", "template"] {
+            assert!(!is_archive(Path::new(name)), "{name}");
+        }
+    }
+
+    #[test]
+    fn a_single_top_level_dir_becomes_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("template.tar.gz");
+        std::fs::write(
+            &archive,
+            tar_gz(&[
+                ("template-1.0/Cargo.toml", "[package]"),
+                ("template-1.0/src/main.rs", "fn main() {}"),
+            ]),
+        )
+        .unwrap();
+        let into = dir.path().join("unpacked");
+
+        let root = unpack(&archive, &into).unwrap();
+
+        assert_eq!(root, into.join("template-1.0"));
+        assert!(root.join("src/main.rs").is_file());
+    }
+
+    #[test]
+    fn entries_outside_of_the_archive_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evil.tar.gz");
+        std::fs::write(
+            &archive,
+            tar_gz(&[("ok.txt", "fine"), ("../escaped.txt", "evil")]),
+        )
+        .unwrap();
+        let into = dir.path().join("unpacked");
+
+        let error = unpack(&archive, &into).unwrap_err();
+
+        assert!(format!("{error:#}").contains("points outside of the archive"));
+        assert!(!dir.path().join("escaped.txt").exists());
+    }
+
+    #[test]
+    fn zip_entries_are_unpacked() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("template.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("Cargo.toml", options).unwrap();
+        io::Write::write_all(&mut zip, b"[package]").unwrap();
+        zip.start_file("src/lib.rs", options).unwrap();
+        zip.finish().unwrap();
+        let into = dir.path().join("unpacked");
+
+        let root = unpack(&archive, &into).unwrap();
+
+        assert_eq!(root, into);
+        assert_eq!(
+            std::fs::read_to_string(into.join("Cargo.toml")).unwrap(),
+            "[package]"
+        );
+        assert!(into.join("src/lib.rs").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn zip_entries_lose_special_permission_bits() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("template.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default().unix_permissions(0o4755);
+        zip.start_file("run.sh", options).unwrap();
+        zip.finish().unwrap();
+        let into = dir.path().join("unpacked");
+
+        unpack(&archive, &into).unwrap();
+
+        let mode = std::fs::metadata(into.join("run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o755);
+    }
+}
@@ -4,7 +4,7 @@ use std::{
 };
 
 use anyhow::{anyhow, Result};
-use clap::{Args, Parser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::Deserialize;
 
 use crate::git;
@@ -57,8 +57,17 @@ pub enum Cli {
 }
 
 #[derive(Clone, Debug, Args)]
-#[command(arg_required_else_help(true), version, about)]
+#[command(
+    arg_required_else_help(true),
+    version,
+    about,
+    args_conflicts_with_subcommands(true),
+    subcommand_negates_reqs(true)
+)]
 pub struct GenerateArgs {
+    #[command(subcommand)]
+    pub command: Option<GenerateCommand>,
+
     #[command(flatten)]
     pub template_path: TemplatePath,
 
@@ -118,6 +127,11 @@ pub struct GenerateArgs {
     #[arg(long="values-file", value_parser, alias="template-values-file", value_name="FILE", help_heading = heading::OUTPUT_PARAMETERS)]
     pub template_values_file: Option<String>,
 
+    /// Write the values of all placeholders, including those set by hooks, to FILE after
+    /// expansion. Passing it to `--values-file` together with `--silent` reproduces the project.
+    #[arg(long, value_parser, value_name="FILE", help_heading = heading::OUTPUT_PARAMETERS)]
+    pub save_values: Option<PathBuf>,
+
     /// If silent mode is set all variables will be extracted from the template_values_file. If a
     /// value is missing the project generation will fail
     #[arg(long, short, requires("name"), action)]
@@ -184,6 +198,32 @@ pub struct GenerateArgs {
     #[arg(long, action, help_heading = heading::OUTPUT_PARAMETERS)]
     pub no_workspace: bool,
 
+    /// Record the template source, the resolved revision and all placeholder values in a
+    /// `.cargo-generate-provenance.toml` file in the generated project. This enables
+    /// `cargo generate update` later on.
+    #[arg(long, action, help_heading = heading::OUTPUT_PARAMETERS)]
+    pub provenance: bool,
+
+    /// Run the whole template expansion, but don't write anything into the destination. Instead,
+    /// report the files that would be generated. Hooks are only run if `--allow-commands` is given.
+    #[arg(long, action, help_heading = heading::OUTPUT_PARAMETERS)]
+    pub dry_run: bool,
+
+    /// Fail if the `cargo-generate.toml` of the template contains unknown keys, instead of
+    /// warning about them.
+    #[arg(long, action, help_heading = heading::TEMPLATE_SELECTION)]
+    pub strict: bool,
+
+    /// The format of the output. `json` prints one JSON object per event on stdout, the human
+    /// readable messages go to stderr then.
+    #[arg(long, value_enum, default_value_t, value_name = "FMT", help_heading = heading::OUTPUT_PARAMETERS)]
+    pub message_format: MessageFormat,
+
+    /// Don't access the network, use only templates from the git cache. Fails if the
+    /// requested template, branch, tag or revision is not cached.
+    #[arg(long, action, help_heading = heading::GIT_PARAMETERS)]
+    pub offline: bool,
+
     /// All args after "--" on the command line.
     #[arg(skip)]
     pub other_args: Option<Vec<String>>,
@@ -192,6 +232,7 @@ pub struct GenerateArgs {
 impl Default for GenerateArgs {
     fn default() -> Self {
         Self {
+            command: None,
             template_path: TemplatePath::default(),
             list_favorites: false,
             name: None,
@@ -200,6 +241,7 @@ impl Default for GenerateArgs {
             quiet: false,
             continue_on_error: false,
             template_values_file: None,
+            save_values: None,
             silent: false,
             config: None,
             vcs: None,
@@ -215,11 +257,237 @@ impl Default for GenerateArgs {
             overwrite: false,
             skip_submodules: false,
             no_workspace: false,
+            provenance: false,
+            dry_run: false,
+            strict: false,
+            message_format: MessageFormat::Human,
+            offline: false,
             other_args: None,
         }
     }
 }
 
+#[derive(Clone, Debug, Subcommand)]
+pub enum GenerateCommand {
+    /// Update a project, generated with `--provenance`, to a newer revision of its template.
+    /// Changes of the template are merged into the files of the project, conflicts are marked
+    /// like `git merge` does.
+    Update(UpdateArgs),
+    /// Describe the placeholders, conditionals, sub-templates and hooks of a template, without
+    /// expanding it.
+    Inspect(InspectArgs),
+    /// Check a local template for mistakes: unknown or invalid configuration, broken Liquid
+    /// syntax, unused placeholders, invalid conditionals and missing hook files.
+    Lint(LintArgs),
+    /// Manage the cache of git templates
+    Cache(CacheArgs),
+    /// Manage the favorites in the application config, keeping its comments and formatting
+    Favorite(FavoriteArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct FavoriteArgs {
+    #[command(subcommand)]
+    pub command: FavoriteCommand,
+
+    /// Use specific configuration file. Defaults to $CARGO_HOME/cargo-generate or
+    /// $HOME/.cargo/cargo-generate
+    #[arg(short, long, value_parser, global = true)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum FavoriteCommand {
+    /// Add a favorite
+    Add(FavoriteAddArgs),
+    /// Remove a favorite
+    Remove {
+        /// Name of the favorite
+        name: String,
+    },
+    /// Show the settings of a favorite
+    Show {
+        /// Name of the favorite
+        name: String,
+    },
+    /// Give a favorite a new name
+    Rename {
+        /// Current name of the favorite
+        from: String,
+        /// New name of the favorite
+        to: String,
+    },
+}
+
+#[derive(Default, Clone, Debug, Args)]
+#[command(group(clap::ArgGroup::new("source").required(true).args(["git", "path"])))]
+pub struct FavoriteAddArgs {
+    /// Name of the favorite
+    pub name: String,
+
+    /// Git repository of the template
+    #[arg(long, help_heading = heading::TEMPLATE_SELECTION)]
+    pub git: Option<String>,
+
+    /// Local path of the template
+    #[arg(long, help_heading = heading::TEMPLATE_SELECTION)]
+    pub path: Option<PathBuf>,
+
+    /// Subfolder within the template
+    #[arg(long, help_heading = heading::TEMPLATE_SELECTION)]
+    pub subfolder: Option<String>,
+
+    /// Branch of the template
+    #[arg(short, long, conflicts_with_all = ["revision", "tag"], help_heading = heading::GIT_PARAMETERS)]
+    pub branch: Option<String>,
+
+    /// Tag of the template
+    #[arg(short, long, conflicts_with_all = ["revision", "branch"], help_heading = heading::GIT_PARAMETERS)]
+    pub tag: Option<String>,
+
+    /// Git revision of the template (e.g. a commit hash)
+    #[arg(short, long, conflicts_with_all = ["tag", "branch"], alias = "rev", help_heading = heading::GIT_PARAMETERS)]
+    pub revision: Option<String>,
+
+    /// Description, shown by `--list-favorites`
+    #[arg(long)]
+    pub description: Option<String>,
+
+    /// Define a value for the template. E.g `--define foo=bar`
+    #[arg(long, short, number_of_values = 1, value_parser)]
+    pub define: Vec<String>,
+
+    /// Replace an existing favorite of the same name
+    #[arg(long, action)]
+    pub force: bool,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum CacheCommand {
+    /// List the cached templates, with their commits and references
+    List,
+    /// Remove the cached commits, that were not used for a while
+    Prune {
+        /// Keep commits, that were used within this many days
+        #[arg(long, value_name = "DAYS", default_value_t = 30)]
+        max_age: u64,
+    },
+    /// Remove the whole cache
+    Clear,
+}
+
+#[derive(Default, Clone, Debug, Args)]
+pub struct LintArgs {
+    /// Directory of the template, defaults to the current directory
+    #[arg(value_parser, value_name = "PATH")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Default, Clone, Debug, Args)]
+pub struct InspectArgs {
+    #[command(flatten)]
+    pub template_path: TemplatePath,
+
+    /// Output format of the description
+    #[arg(long, value_enum, default_value_t = InspectFormat::Human)]
+    pub format: InspectFormat,
+
+    /// Use a different ssh identity
+    #[arg(short = 'i', long = "identity", value_parser, value_name="IDENTITY", help_heading = heading::GIT_PARAMETERS)]
+    pub ssh_identity: Option<PathBuf>,
+
+    /// Use a different gitconfig file, if omitted the usual $HOME/.gitconfig will be used
+    #[arg(long = "gitconfig", value_parser, value_name="GITCONFIG_FILE", help_heading = heading::GIT_PARAMETERS)]
+    pub gitconfig: Option<PathBuf>,
+
+    /// Skip downloading git submodules (if there are any)
+    #[arg(long, action, help_heading = heading::GIT_PARAMETERS)]
+    pub skip_submodules: bool,
+
+    /// Don't access the network, use only templates from the git cache
+    #[arg(long, action, help_heading = heading::GIT_PARAMETERS)]
+    pub offline: bool,
+
+    /// Use specific configuration file. Defaults to $CARGO_HOME/cargo-generate or
+    /// $HOME/.cargo/cargo-generate
+    #[arg(short, long, value_parser)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+    /// Styled messages, meant to be read by humans
+    #[default]
+    Human,
+    /// One JSON object per event, meant to be read by other tools
+    Json,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InspectFormat {
+    /// Tables, meant to be read by humans
+    #[default]
+    Human,
+    /// JSON, meant to be read by scripts
+    Json,
+}
+
+#[derive(Default, Clone, Debug, Args)]
+pub struct UpdateArgs {
+    /// Directory of the generated project, defaults to the current directory
+    #[arg(value_parser, value_name = "PROJECT_DIR")]
+    pub project_dir: Option<PathBuf>,
+
+    /// Branch of the template to update to
+    #[arg(short, long, conflicts_with_all = ["revision", "tag"], help_heading = heading::GIT_PARAMETERS)]
+    pub branch: Option<String>,
+
+    /// Tag of the template to update to
+    #[arg(short, long, conflicts_with_all = ["revision", "branch"], help_heading = heading::GIT_PARAMETERS)]
+    pub tag: Option<String>,
+
+    /// Git revision of the template to update to (e.g. a commit hash)
+    #[arg(short, long, conflicts_with_all = ["tag", "branch"], alias = "rev", help_heading = heading::GIT_PARAMETERS)]
+    pub revision: Option<String>,
+
+    /// Use a different ssh identity
+    #[arg(short = 'i', long = "identity", value_parser, value_name="IDENTITY", help_heading = heading::GIT_PARAMETERS)]
+    pub ssh_identity: Option<PathBuf>,
+
+    /// Use a different gitconfig file, if omitted the usual $HOME/.gitconfig will be used
+    #[arg(long = "gitconfig", value_parser, value_name="GITCONFIG_FILE", help_heading = heading::GIT_PARAMETERS)]
+    pub gitconfig: Option<PathBuf>,
+
+    /// Skip downloading git submodules (if there are any)
+    #[arg(long, action, help_heading = heading::GIT_PARAMETERS)]
+    pub skip_submodules: bool,
+
+    /// Don't access the network, use only templates from the git cache
+    #[arg(long, action, help_heading = heading::GIT_PARAMETERS)]
+    pub offline: bool,
+
+    /// Use specific configuration file. Defaults to $CARGO_HOME/cargo-generate or
+    /// $HOME/.cargo/cargo-generate
+    #[arg(short, long, value_parser)]
+    pub config: Option<PathBuf>,
+
+    /// Allows running system commands without being prompted. Warning: Setting this flag will
+    /// enable the template to run arbitrary system commands without user confirmation. Use at your
+    /// own risk and be sure to review the template code beforehand.
+    #[arg(short, long, action)]
+    pub allow_commands: bool,
+
+    /// Enables more verbose output.
+    #[arg(long, short, action)]
+    pub verbose: bool,
+}
+
 #[derive(Default, Debug, Clone, Args)]
 pub struct TemplatePath {
     /// Auto attempt to use as either `--git` or `--favorite`. If either is specified explicitly,
@@ -322,6 +590,7 @@ impl TemplatePath {
 }
 
 #[derive(Debug, Parser, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub enum Vcs {
     None,
     Git,
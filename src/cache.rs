@@ -0,0 +1,136 @@
+//! Implementation of `cargo generate cache`
+
+use anyhow::Result;
+use console::style;
+use log::info;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    emoji,
+    git::cache::{GitCache, RepoIndex, Snapshot},
+    CacheArgs, CacheCommand,
+};
+
+/// List, prune or clear the git template cache
+pub fn cache(args: CacheArgs) -> Result<()> {
+    let cache = GitCache::open()?;
+    match args.command {
+        CacheCommand::List => list(&cache),
+        CacheCommand::Prune { max_age } => {
+            let removed = cache.prune(max_age)?;
+            info!(
+                "{} {}",
+                emoji::SPARKLE,
+                style(format!(
+                    "Removed {removed} cached commit(s), unused for more than {max_age} day(s)"
+                ))
+                .bold()
+                .green()
+            );
+            Ok(())
+        }
+        CacheCommand::Clear => {
+            cache.clear()?;
+            info!(
+                "{} {} `{}`",
+                emoji::SPARKLE,
+                style("Cleared the git cache in").bold().green(),
+                style(cache.root().display()).bold().yellow(),
+            );
+            Ok(())
+        }
+    }
+}
+
+fn list(cache: &GitCache) -> Result<()> {
+    let repos = cache.list()?;
+    info!(
+        "{} {} `{}`",
+        emoji::WRENCH,
+        style("Git cache in").bold(),
+        style(cache.root().display()).bold().yellow(),
+    );
+    if repos.is_empty() {
+        info!("  (empty)");
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    for repo in &repos {
+        info!("{}", style(&repo.url).bold());
+        for snapshot in repo.snapshots.values() {
+            let refs = refs_of(repo, snapshot);
+            info!(
+                "  {}  {:>9}  used {}{}{}",
+                style(&snapshot.commit[..snapshot.commit.len().min(12)]).yellow(),
+                human_size(cache.snapshot_size(&repo.url, snapshot)),
+                days_ago(now.saturating_sub(snapshot.last_used)),
+                if snapshot.submodules {
+                    ""
+                } else {
+                    ", without submodules"
+                },
+                if refs.is_empty() {
+                    String::new()
+                } else {
+                    format!("  ({})", refs.join(", "))
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The short names of the references, that resolved to the commit of `snapshot`
+fn refs_of(repo: &RepoIndex, snapshot: &Snapshot) -> Vec<String> {
+    repo.refs
+        .iter()
+        .filter(|(_, commit)| **commit == snapshot.commit)
+        .map(|(name, _)| {
+            name.strip_prefix("refs/heads/")
+                .or_else(|| name.strip_prefix("refs/tags/"))
+                .unwrap_or(name)
+                .to_owned()
+        })
+        .collect()
+}
+
+fn days_ago(seconds: u64) -> String {
+    match seconds / (24 * 60 * 60) {
+        0 => "today".into(),
+        1 => "yesterday".into(),
+        days => format!("{days} days ago"),
+    }
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_and_ages_are_human_readable() {
+        assert_eq!(human_size(100), "100 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(days_ago(60), "today");
+        assert_eq!(days_ago(3 * 24 * 60 * 60), "3 days ago");
+    }
+}
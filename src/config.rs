@@ -1,43 +1,86 @@
 use anyhow::Result;
+use console::style;
 use indexmap::IndexMap;
+use log::warn;
 use semver::VersionReq;
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
 use std::{collections::HashMap, fs};
 use std::{convert::TryFrom, io::ErrorKind};
 
-use crate::Vcs;
+use crate::{emoji, Vcs};
 
 pub const CONFIG_FILE_NAME: &str = "cargo-generate.toml";
 
+/// The configuration of a template, read from its `cargo-generate.toml`
 #[derive(Deserialize, Debug, PartialEq, Default, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema), schemars(deny_unknown_fields))]
 pub struct Config {
     pub template: Option<TemplateConfig>,
+    /// Values to prompt the user for, by name
     pub placeholders: Option<TemplateSlotsTable>,
     pub hooks: Option<HooksConfig>,
+    /// Configuration that only applies if the Rhai expression used as key evaluates to `true`
     pub conditional: Option<HashMap<String, ConditionalConfig>>,
+    /// Liquid filters backed by a Rhai script, the script file by filter name
+    pub filters: Option<HashMap<String, String>>,
 }
 
+/// Rhai scripts run while expanding the template
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema), schemars(deny_unknown_fields))]
 pub struct HooksConfig {
+    /// Run before any placeholder is prompted for
     pub init: Option<Vec<String>>,
+    /// Run after the placeholders, before the files are rendered
     pub pre: Option<Vec<String>>,
+    /// Run after the files are rendered
     pub post: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema), schemars(deny_unknown_fields))]
 pub struct TemplateConfig {
+    /// Folders of the template, that are templates on their own, the user picks one of them
     pub sub_templates: Option<Vec<String>>,
 
+    /// The versions of cargo-generate the template works with
+    #[cfg_attr(test, schemars(with = "Option<String>"))]
     pub cargo_generate_version: Option<VersionReq>,
+    /// Only render these files, all others are copied verbatim
     pub include: Option<Vec<String>>,
+    /// Copy these files verbatim, without rendering them
     pub exclude: Option<Vec<String>>,
+    /// Never copy these files into the generated project
     pub ignore: Option<Vec<String>>,
     pub vcs: Option<Vcs>,
+    /// Always generate into the current directory, like `--init`
     pub init: Option<bool>,
+    /// A template, that this template builds upon. Its files, placeholders, conditionals, hooks
+    /// and file lists are inherited, this template takes precedence.
+    pub extends: Option<ExtendsConfig>,
+    /// Folder with liquid partials for `{% include %}` and `{% render %}`, it is never part of
+    /// the generated project
+    pub partials: Option<String>,
+}
+
+/// Where the parent of a template comes from, either `git` or `path`
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema), schemars(deny_unknown_fields))]
+pub struct ExtendsConfig {
+    /// Git repository of the parent template
+    pub git: Option<String>,
+    /// Local path of the parent template, relative to the folder of this template
+    pub path: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub revision: Option<String>,
+    /// Folder of the parent template within its repository or path
+    pub subfolder: Option<String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema), schemars(deny_unknown_fields))]
 pub struct ConditionalConfig {
     pub include: Option<Vec<String>>,
     pub exclude: Option<Vec<String>>,
@@ -48,6 +91,299 @@ pub struct ConditionalConfig {
 #[derive(Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct TemplateSlotsTable(pub IndexMap<String, toml::Value>);
 
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "template",
+    "placeholders",
+    "hooks",
+    "conditional",
+    "filters",
+];
+const TEMPLATE_KEYS: &[&str] = &[
+    "sub_templates",
+    "cargo_generate_version",
+    "include",
+    "exclude",
+    "ignore",
+    "vcs",
+    "init",
+    "extends",
+    "partials",
+];
+const EXTENDS_KEYS: &[&str] = &["git", "path", "branch", "tag", "revision", "subfolder"];
+const HOOKS_KEYS: &[&str] = &["init", "pre", "post"];
+const CONDITIONAL_KEYS: &[&str] = &["include", "exclude", "ignore", "placeholders"];
+const PLACEHOLDER_KEYS: &[&str] = &[
+    "type",
+    "prompt",
+    "default",
+    "choices",
+    "regex",
+    "validate",
+    "min",
+    "max",
+    "step",
+    "min_items",
+    "max_items",
+    "when",
+    "computed",
+];
+
+/// The schema of a single placeholder, as [`TemplateSlotsTable`] keeps them as plain TOML
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+#[allow(dead_code)]
+struct PlaceholderSchema {
+    #[serde(rename = "type")]
+    var_type: Option<PlaceholderTypeSchema>,
+    /// The question asked to the user, required unless the placeholder is `computed`
+    prompt: Option<String>,
+    default: Option<PlaceholderValueSchema>,
+    /// The only values the user may pick from
+    choices: Option<Vec<PlaceholderChoiceSchema>>,
+    /// A regular expression the value has to match
+    regex: Option<String>,
+    /// A semantic check the value has to pass
+    validate: Option<PlaceholderValidatorSchema>,
+    /// The smallest value of a number
+    min: Option<f64>,
+    /// The largest value of a number
+    max: Option<f64>,
+    /// A number has to be `min` plus a multiple of `step`
+    step: Option<f64>,
+    /// The least number of items of an array
+    min_items: Option<u32>,
+    /// The largest number of items of an array
+    max_items: Option<u32>,
+    /// A Rhai expression, the placeholder is only asked for if it's true
+    when: Option<String>,
+    /// The expression the value is derived from, instead of asking for it
+    computed: Option<PlaceholderComputedSchema>,
+}
+
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum PlaceholderComputedSchema {
+    /// A Rhai expression, like the ones of conditionals
+    Rhai(String),
+    /// A Liquid template
+    Liquid(String),
+}
+
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum PlaceholderTypeSchema {
+    String,
+    Text,
+    Editor,
+    Secret,
+    Bool,
+    Array,
+    Integer,
+    Float,
+}
+
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum PlaceholderChoiceSchema {
+    Value(String),
+    Described {
+        /// The value the placeholder gets
+        value: String,
+        /// Shown instead of the value
+        label: Option<String>,
+        /// Shown next to the label
+        help: Option<String>,
+    },
+}
+
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+enum PlaceholderValidatorSchema {
+    Semver,
+    Url,
+    Email,
+    RustIdent,
+    CrateName,
+    Spdx,
+    RelativePath,
+}
+
+#[cfg(test)]
+#[derive(schemars::JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum PlaceholderValueSchema {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<String>),
+}
+
+#[cfg(test)]
+impl schemars::JsonSchema for TemplateSlotsTable {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Placeholders".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "object",
+            "additionalProperties": generator.subschema_for::<PlaceholderSchema>(),
+        })
+    }
+}
+
+/// A key in a `cargo-generate.toml`, that is not known to cargo-generate
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnknownKey {
+    /// the table the key was found in, empty for the top level
+    pub table: String,
+    pub key: String,
+    /// the keys that are valid in `table`
+    pub known: &'static [&'static str],
+}
+
+impl UnknownKey {
+    /// The known key, that is most similar to the unknown one
+    pub fn suggestion(&self) -> Option<&'static str> {
+        self.known
+            .iter()
+            .map(|known| (strsim::jaro(&self.key, known), *known))
+            .filter(|(confidence, _)| *confidence > 0.7)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, known)| known)
+    }
+}
+
+impl std::fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.table.is_empty() {
+            write!(f, "`{}`", self.key)?;
+        } else {
+            write!(f, "`{}.{}`", self.table, self.key)?;
+        }
+        if let Some(suggestion) = self.suggestion() {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+/// Warn about unknown keys in the configuration file at `path`, or fail if `strict`
+pub fn check_unknown_keys(path: &Option<impl AsRef<Path>>, strict: bool) -> Result<()> {
+    let Some(Ok(contents)) = path.as_ref().map(fs::read_to_string) else {
+        return Ok(());
+    };
+    // invalid TOML is reported when the configuration is read
+    let Ok(unknown_keys) = find_unknown_keys(&contents) else {
+        return Ok(());
+    };
+
+    if strict && !unknown_keys.is_empty() {
+        anyhow::bail!(
+            "{} {}\n{}",
+            emoji::ERROR,
+            style(format!("Unknown keys in {CONFIG_FILE_NAME}:"))
+                .bold()
+                .red(),
+            unknown_keys
+                .iter()
+                .map(|key| format!("  {key}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+    for key in unknown_keys {
+        warn!(
+            "{} {key}",
+            style(format!("Unknown key in {CONFIG_FILE_NAME}, it is ignored:"))
+                .bold()
+                .yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Find all keys of a `cargo-generate.toml` that would be silently ignored
+pub fn find_unknown_keys(contents: &str) -> Result<Vec<UnknownKey>, toml::de::Error> {
+    fn check(
+        table_name: &str,
+        table: &toml::Table,
+        known: &'static [&'static str],
+        unknown: &mut Vec<UnknownKey>,
+    ) {
+        unknown.extend(
+            table
+                .keys()
+                .filter(|key| !known.contains(&key.as_str()))
+                .map(|key| UnknownKey {
+                    table: table_name.to_string(),
+                    key: key.clone(),
+                    known,
+                }),
+        );
+    }
+
+    fn check_placeholders(table_name: &str, table: &toml::Table, unknown: &mut Vec<UnknownKey>) {
+        for (name, placeholder) in table {
+            if let Some(placeholder) = placeholder.as_table() {
+                check(
+                    &format!("{table_name}.{name}"),
+                    placeholder,
+                    PLACEHOLDER_KEYS,
+                    unknown,
+                );
+            }
+        }
+    }
+
+    let config: toml::Table = toml::from_str(contents)?;
+    let table = |name: &str| config.get(name).and_then(toml::Value::as_table);
+    let mut unknown = vec![];
+
+    check("", &config, TOP_LEVEL_KEYS, &mut unknown);
+    if let Some(template) = table("template") {
+        check("template", template, TEMPLATE_KEYS, &mut unknown);
+        if let Some(extends) = template.get("extends").and_then(toml::Value::as_table) {
+            check("template.extends", extends, EXTENDS_KEYS, &mut unknown);
+        }
+    }
+    if let Some(hooks) = table("hooks") {
+        check("hooks", hooks, HOOKS_KEYS, &mut unknown);
+    }
+    if let Some(placeholders) = table("placeholders") {
+        check_placeholders("placeholders", placeholders, &mut unknown);
+    }
+    for (expression, conditional) in table("conditional").into_iter().flatten() {
+        if let Some(conditional) = conditional.as_table() {
+            let table_name = format!("conditional.'{expression}'");
+            check(&table_name, conditional, CONDITIONAL_KEYS, &mut unknown);
+            if let Some(placeholders) = conditional
+                .get("placeholders")
+                .and_then(toml::Value::as_table)
+            {
+                check_placeholders(
+                    &format!("{table_name}.placeholders"),
+                    placeholders,
+                    &mut unknown,
+                );
+            }
+        }
+    }
+
+    Ok(unknown)
+}
+
 impl TryFrom<String> for Config {
     type Error = toml::de::Error;
 
@@ -100,6 +436,16 @@ impl Config {
         pre.append(&mut self.get_post_hooks());
         pre
     }
+
+    /// The Rhai scripts of the custom `filters`, sorted by their name
+    pub fn get_filter_files(&self) -> Vec<String> {
+        let mut filters = self.filters.iter().flatten().collect::<Vec<_>>();
+        filters.sort();
+        filters
+            .into_iter()
+            .map(|(_, script)| script.clone())
+            .collect()
+    }
 }
 
 /// Search through a folder structure for template configuration files, but look no deeper than
@@ -211,6 +557,8 @@ mod tests {
                 ignore: None,
                 vcs: None,
                 init: None,
+                extends: None,
+                partials: None,
             })
         );
         assert!(config.placeholders.is_some());
@@ -228,6 +576,7 @@ mod tests {
                 hooks: None,
                 placeholders: None,
                 conditional: Default::default(),
+                filters: None,
             }
         )
     }
@@ -246,6 +595,137 @@ mod tests {
         assert!(result.is_err(), "Config should not have parsed");
     }
 
+    #[test]
+    fn find_unknown_keys_reports_keys_of_all_tables() {
+        let unknown = find_unknown_keys(
+            r#"
+            [template]
+            exlude = ["a"]
+            [hook]
+            pre = ["pre.rhai"]
+            [placeholders]
+            a = { type = "bool", prompt = "foo", defualt = false }
+            [conditional.'a']
+            includes = ["b"]
+            placeholders = { b = { prompt = "bar", choice = ["x"] } }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            unknown.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "`hook`, did you mean `hooks`?",
+                "`template.exlude`, did you mean `exclude`?",
+                "`placeholders.a.defualt`, did you mean `default`?",
+                "`conditional.'a'.includes`, did you mean `include`?",
+                "`conditional.'a'.placeholders.b.choice`, did you mean `choices`?",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_without_similar_known_key_have_no_suggestion() {
+        let unknown = find_unknown_keys("[zzz]\n").unwrap();
+        assert_eq!(unknown[0].suggestion(), None);
+        assert_eq!(unknown[0].to_string(), "`zzz`");
+    }
+
+    #[test]
+    fn check_unknown_keys_fails_only_if_strict() -> anyhow::Result<()> {
+        let tmp = tmp_dir().unwrap();
+        create_file(&tmp, CONFIG_FILE_NAME, "[template]\nexlude = []\n")?;
+        let path = Some(tmp.path().join(CONFIG_FILE_NAME));
+
+        assert!(check_unknown_keys(&path, false).is_ok());
+        let err = check_unknown_keys(&path, true).unwrap_err().to_string();
+        assert!(err.contains("`template.exlude`, did you mean `exclude`?"));
+        Ok(())
+    }
+
+    /// The JSON schema is published for editors, regenerate it with
+    /// `UPDATE_SCHEMA=1 cargo test json_schema`
+    #[test]
+    fn json_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&schemars::schema_for!(Config)).unwrap() + "\n";
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("cargo-generate.schema.json");
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            fs::write(&path, &schema).unwrap();
+        }
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap_or_default(),
+            schema,
+            "the JSON schema is outdated, run `UPDATE_SCHEMA=1 cargo test json_schema`"
+        );
+    }
+
+    #[test]
+    fn json_schema_knows_the_same_keys() {
+        let schema = schemars::schema_for!(Config).to_value();
+        let keys = |schema: &serde_json::Value| {
+            let mut keys = schema["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+        let known = |known: &[&str]| {
+            let mut known = known.iter().map(ToString::to_string).collect::<Vec<_>>();
+            known.sort();
+            known
+        };
+        let definition = |name: &str| &schema["$defs"][name];
+
+        assert_eq!(keys(&schema), known(TOP_LEVEL_KEYS));
+        assert_eq!(keys(definition("TemplateConfig")), known(TEMPLATE_KEYS));
+        assert_eq!(keys(definition("ExtendsConfig")), known(EXTENDS_KEYS));
+        assert_eq!(keys(definition("HooksConfig")), known(HOOKS_KEYS));
+        assert_eq!(
+            keys(definition("ConditionalConfig")),
+            known(CONDITIONAL_KEYS)
+        );
+        assert_eq!(
+            keys(definition("PlaceholderSchema")),
+            known(PLACEHOLDER_KEYS)
+        );
+    }
+
+    #[test]
+    fn find_unknown_keys_accepts_all_known_keys() {
+        let unknown = find_unknown_keys(
+            r#"
+            [template]
+            sub_templates = ["a"]
+            cargo_generate_version = ">=0.8.0"
+            include = ["a"]
+            ignore = ["b"]
+            vcs = "none"
+            init = true
+            extends = { git = "https://example.com/base", tag = "v1", subfolder = "base" }
+            [hooks]
+            init = ["init.rhai"]
+            pre = ["pre.rhai"]
+            post = ["post.rhai"]
+            [placeholders]
+            a = { type = "string", prompt = "foo", default = "x", choices = ["x"], regex = ".*", validate = "rust_ident", when = "true" }
+            c = { computed = { rhai = "b + 1" } }
+            b = { type = "integer", prompt = "bar", default = 2, min = 0, max = 10, step = 2 }
+            d = { type = "array", prompt = "baz", default = ["x"], min_items = 1, max_items = 3 }
+            [conditional.'a == "x"']
+            exclude = ["c"]
+            [filters]
+            semver_major = "filters/semver_major.rhai"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(unknown, vec![]);
+    }
+
     #[test]
     fn config_try_from_handles_placeholders() {
         let result = Config::try_from(
@@ -0,0 +1,107 @@
+//! Reporting for `--dry-run`
+
+use console::style;
+use log::{error, info, warn};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{copy::LIQUID_SUFFIX, emoji, include_exclude::ShouldInclude};
+
+/// Print the files that would have been generated into `project_dir`
+pub fn report(project_dir: &Path, outcomes: &[(PathBuf, ShouldInclude)], overwrite: bool) {
+    // copying a `.liquid` file onto an existing one fails without `--overwrite`, see `safe_copy`
+    let liquid_outputs = outcomes
+        .iter()
+        .map(|(path, _)| (output_path(path), path))
+        .filter(|(output, path)| output != *path)
+        .map(|(output, _)| output)
+        .collect::<HashSet<_>>();
+    let mut outcomes = outcomes
+        .iter()
+        .map(|(path, outcome)| (output_path(path), *outcome))
+        .collect::<Vec<_>>();
+    outcomes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    outcomes.dedup();
+
+    info!(
+        "{} {} `{}`",
+        emoji::WRENCH,
+        style("Dry run, the following files would be generated into").bold(),
+        style(project_dir.display()).bold().yellow(),
+    );
+    for (path, outcome) in &outcomes {
+        let outcome = match outcome {
+            ShouldInclude::Include => style("rendered").green(),
+            ShouldInclude::Exclude => style("copied").cyan(),
+            ShouldInclude::Ignore => style("ignored").dim(),
+        };
+        info!("  {outcome:<10} {}", path.display());
+    }
+
+    let (failing, existing): (Vec<_>, Vec<_>) = outcomes
+        .iter()
+        .filter(|(path, outcome)| {
+            *outcome != ShouldInclude::Ignore && project_dir.join(path).is_file()
+        })
+        .partition(|(path, _)| !overwrite && liquid_outputs.contains(path));
+    if !failing.is_empty() {
+        error!(
+            "{}",
+            style("These files already exist, and generating would fail without `--overwrite`:")
+                .bold()
+                .red(),
+        );
+        for (path, _) in failing {
+            info!("  {}", path.display());
+        }
+    }
+    if !existing.is_empty() {
+        warn!(
+            "{}",
+            style(if overwrite {
+                "These files already exist and would be overwritten:"
+            } else {
+                "These files already exist and would be kept, unless `--overwrite` is given:"
+            })
+            .bold()
+            .yellow(),
+        );
+        for (path, _) in existing {
+            info!("  {}", path.display());
+        }
+    }
+
+    info!(
+        "{} {}",
+        emoji::SPARKLE,
+        style("Dry run done, nothing has been written")
+            .bold()
+            .green(),
+    );
+}
+
+/// The path of a template file in the generated project, without any `.liquid` suffix
+pub fn output_path(path: &Path) -> PathBuf {
+    path.to_str()
+        .and_then(|p| p.strip_suffix(LIQUID_SUFFIX))
+        .map_or_else(|| path.to_path_buf(), PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_path_strips_the_liquid_suffix() {
+        assert_eq!(
+            output_path(Path::new("src/main.rs.liquid")),
+            PathBuf::from("src/main.rs")
+        );
+        assert_eq!(
+            output_path(Path::new("README.md")),
+            PathBuf::from("README.md")
+        );
+    }
+}
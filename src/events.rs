@@ -0,0 +1,266 @@
+//! Machine readable events of a generation run, printed with `--message-format json`.
+//!
+//! Each event is a single line of JSON on stdout, the `event` field tells the kind of event.
+//! The human readable logging is moved to stderr in that case.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    dry_run::output_path, include_exclude::ShouldInclude, project_variables::SECRET_MASK,
+    MessageFormat,
+};
+
+/// Where the events of a single run go, passed along to everything that emits them
+#[derive(Debug, Default, Clone)]
+pub struct Events {
+    json: bool,
+    /// the secret placeholders of the template, for emitters that only know a name
+    secrets: Vec<String>,
+}
+
+impl Events {
+    pub fn new(format: MessageFormat) -> Self {
+        Self {
+            json: format == MessageFormat::Json,
+            secrets: vec![],
+        }
+    }
+
+    /// Remember the secret placeholders of the template
+    pub fn set_secrets(&mut self, names: Vec<String>) {
+        self.secrets = names;
+    }
+
+    /// Are events printed?
+    pub const fn enabled(&self) -> bool {
+        self.json
+    }
+
+    /// Is `name` a secret placeholder, whose value must never be emitted?
+    pub fn is_secret(&self, name: &str) -> bool {
+        self.secrets.iter().any(|secret| secret == name)
+    }
+
+    /// Print `event` as a line of JSON, if enabled
+    pub fn emit(&self, event: &Event) {
+        if self.enabled() {
+            // serializing these events can't fail, there are no maps with non-string keys
+            if let Ok(line) = serde_json::to_string(event) {
+                println!("{line}");
+            }
+        }
+    }
+
+    /// Emit the final [`Event::Result`] of a run
+    pub fn emit_result(&self, result: &anyhow::Result<PathBuf>) {
+        self.emit(&match result {
+            Ok(path) => Event::Result {
+                success: true,
+                path: Some(path),
+                error: None,
+            },
+            Err(e) => Event::Result {
+                success: false,
+                path: None,
+                error: Some(ErrorDescription::from(e)),
+            },
+        });
+    }
+
+    /// Emit an [`Event::File`] for each file of the template, in the order they were processed
+    pub fn emit_file_outcomes(
+        &self,
+        project_dir: &Path,
+        outcomes: &[(PathBuf, ShouldInclude)],
+        overwrite: bool,
+    ) {
+        if !self.enabled() {
+            return;
+        }
+        for (path, outcome) in outcomes {
+            let path = output_path(path);
+            let action = match outcome {
+                ShouldInclude::Ignore => FileAction::Ignored,
+                _ if !overwrite && project_dir.join(&path).is_file() => FileAction::Skipped,
+                ShouldInclude::Include => FileAction::Rendered,
+                ShouldInclude::Exclude => FileAction::Copied,
+            };
+            self.emit(&Event::File {
+                path: &path,
+                action,
+            });
+        }
+    }
+
+    /// The value of placeholder `name` as emitted, secrets are masked
+    pub fn placeholder_value(&self, name: &str, value: &liquid_core::Value) -> serde_json::Value {
+        if self.is_secret(name) {
+            SECRET_MASK.into()
+        } else {
+            json_value(value)
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// The template was fetched, from git or a local path
+    SourceResolved {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        git: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a Path>,
+        branch: Option<&'a str>,
+        commit: Option<&'a str>,
+    },
+    /// The folder of the template that gets expanded, relative to the template root
+    TemplateDirSelected {
+        subfolder: Option<&'a str>,
+    },
+    PlaceholderValue {
+        name: &'a str,
+        value: serde_json::Value,
+        source: ValueSource,
+    },
+    HookStarted {
+        script: &'a Path,
+    },
+    HookFinished {
+        script: &'a Path,
+        /// everything the script printed, and the string it returned
+        output: String,
+    },
+    File {
+        /// relative to the generated project
+        path: &'a Path,
+        action: FileAction,
+    },
+    WorkspaceMemberAdded {
+        workspace: &'a Path,
+    },
+    GitInitialized {
+        path: &'a Path,
+        branch: Option<&'a str>,
+    },
+    /// The last event of a run
+    Result {
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a Path>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<ErrorDescription>,
+    },
+}
+
+/// Where the value of a placeholder came from
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    /// `--name`, `--define`, a values file or an environment variable
+    Provided,
+    /// the default of the template, in `--silent` mode or when the `when` condition is false
+    Default,
+    /// answered by the user
+    Prompt,
+    /// set by a Rhai hook
+    Hook,
+    /// derived by cargo-generate
+    Builtin,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    Rendered,
+    Copied,
+    Ignored,
+    /// exists already, and is kept as `--overwrite` was not given
+    Skipped,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct ErrorDescription {
+    message: String,
+    /// the chain of underlying errors, outermost first
+    causes: Vec<String>,
+}
+
+impl From<&anyhow::Error> for ErrorDescription {
+    fn from(error: &anyhow::Error) -> Self {
+        let plain = |e: &dyn std::fmt::Display| {
+            console::strip_ansi_codes(&e.to_string()).trim().to_string()
+        };
+        Self {
+            message: plain(error),
+            causes: error.chain().skip(1).map(|e| plain(e)).collect(),
+        }
+    }
+}
+
+/// Convert a liquid value, for [`Event::PlaceholderValue`]
+pub fn json_value(value: &liquid_core::Value) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn events_are_tagged_with_their_kind() {
+        let event = Event::File {
+            path: Path::new("src/main.rs"),
+            action: FileAction::Rendered,
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "event": "file", "path": "src/main.rs", "action": "rendered" })
+        );
+
+        let event = Event::PlaceholderValue {
+            name: "ci",
+            value: json_value(&liquid_core::Value::Scalar(true.into())),
+            source: ValueSource::Default,
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "event": "placeholder_value", "name": "ci", "value": true, "source": "default" })
+        );
+    }
+
+    #[test]
+    fn secrets_are_masked_only_in_the_run_that_knows_them() {
+        let mut events = Events::new(MessageFormat::Json);
+        events.set_secrets(vec!["token".into()]);
+        let value = liquid_core::Value::scalar("hunter2");
+
+        assert_eq!(events.placeholder_value("token", &value), SECRET_MASK);
+        assert_eq!(
+            Events::new(MessageFormat::Json).placeholder_value("token", &value),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    fn errors_are_described_with_their_causes() {
+        let error = Err::<(), _>(anyhow::anyhow!("inner"))
+            .context(
+                console::style("outer")
+                    .red()
+                    .force_styling(true)
+                    .to_string(),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            ErrorDescription::from(&error),
+            ErrorDescription {
+                message: "outer".into(),
+                causes: vec!["inner".into()],
+            }
+        );
+    }
+}
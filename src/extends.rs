@@ -0,0 +1,482 @@
+//! Templates, that build upon another template with `[template] extends = { ... }`
+
+use anyhow::{bail, Result};
+use console::style;
+use fs_err as fs;
+use indexmap::IndexSet;
+use log::info;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    app_config::AppConfig,
+    check_cargo_generate_version,
+    config::{
+        check_unknown_keys, ConditionalConfig, Config, ExtendsConfig, HooksConfig, TemplateConfig,
+        CONFIG_FILE_NAME,
+    },
+    emoji, get_source_template_into_temp,
+    ignore_me::IGNORE_FILE_NAME,
+    locate_template_file, resolve_template_dir_subfolder,
+    template_source::{CloneOptions, TemplateSource},
+    user_parsed_input::TemplateLocation,
+    GenerateArgs,
+};
+
+/// Stops a chain of templates, that is too long to be sensible
+const MAX_DEPTH: usize = 8;
+
+/// Inherit from the parent templates of `config`, if it `extends` one.
+///
+/// The files of the parents are copied into `template_dir`, unless the template has them
+/// already, and the configuration of the parents is merged into `config`.
+/// `location` and `subfolder` tell where the template itself came from.
+pub fn inherit(
+    config: Config,
+    template_dir: &Path,
+    location: &TemplateLocation,
+    subfolder: Option<&str>,
+    args: &GenerateArgs,
+) -> Result<Config> {
+    if config
+        .template
+        .as_ref()
+        .and_then(|t| t.extends.as_ref())
+        .is_none()
+    {
+        return Ok(config);
+    }
+
+    let clone_options = match location {
+        TemplateLocation::Git(git) => CloneOptions {
+            ssh_identity: git.identity().map(Path::to_path_buf),
+            gitconfig: git.gitconfig().map(Path::to_path_buf),
+            skip_submodules: git.skip_submodules,
+            offline: git.offline,
+            ..CloneOptions::default()
+        },
+        _ => CloneOptions {
+            ssh_identity: args.ssh_identity.clone(),
+            gitconfig: args.gitconfig.clone(),
+            skip_submodules: args.skip_submodules,
+            offline: args.offline,
+            ..CloneOptions::default()
+        },
+    };
+    let mut chain = vec![identity(location, subfolder)];
+    inherit_from_parent(
+        config,
+        template_dir,
+        &origin_dir(location, subfolder, template_dir),
+        &Inheritance {
+            clone_options,
+            strict: args.strict,
+        },
+        &mut chain,
+    )
+}
+
+struct Inheritance {
+    clone_options: CloneOptions,
+    strict: bool,
+}
+
+fn inherit_from_parent(
+    mut config: Config,
+    template_dir: &Path,
+    origin: &Path,
+    inheritance: &Inheritance,
+    chain: &mut Vec<String>,
+) -> Result<Config> {
+    let Some(extends) = config.template.as_mut().and_then(|t| t.extends.take()) else {
+        return Ok(config);
+    };
+
+    let location = parent_location(&extends, origin, &inheritance.clone_options)?;
+    let subfolder = extends.subfolder.as_deref();
+    let parent = identity(&location, subfolder);
+    if chain.contains(&parent) {
+        bail!(
+            "{} {} {}",
+            emoji::ERROR,
+            style("The templates extend each other in a cycle:")
+                .bold()
+                .red(),
+            style(format!("{} → {parent}", chain.join(" → ")))
+                .bold()
+                .red(),
+        );
+    }
+    if chain.len() > MAX_DEPTH {
+        bail!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!(
+                "More than {MAX_DEPTH} templates extend each other, is there a cycle?"
+            ))
+            .bold()
+            .red(),
+        );
+    }
+    info!(
+        "{} {} `{}`",
+        emoji::WRENCH,
+        style("Extending the template").bold(),
+        style(&parent).bold().yellow(),
+    );
+    chain.push(parent);
+
+    let (parent_base_dir, _) = get_source_template_into_temp(&location)?;
+    let parent_dir = resolve_template_dir_subfolder(parent_base_dir.path(), subfolder)?;
+    let parent_config_path =
+        locate_template_file(CONFIG_FILE_NAME, &parent_base_dir, &parent_dir).ok();
+    check_unknown_keys(&parent_config_path, inheritance.strict)?;
+    let parent_config = Config::from_path(&parent_config_path)?;
+    check_cargo_generate_version(&parent_config)?;
+
+    let parent_config = inherit_from_parent(
+        parent_config,
+        &parent_dir,
+        &origin_dir(&location, subfolder, &parent_dir),
+        inheritance,
+        chain,
+    )?;
+    check_hook_files(&parent_config, &parent_dir, template_dir)?;
+    overlay_files(&parent_dir, template_dir)?;
+    Ok(merge(config, parent_config))
+}
+
+fn parent_location(
+    extends: &ExtendsConfig,
+    origin: &Path,
+    clone_options: &CloneOptions,
+) -> Result<TemplateLocation> {
+    match (&extends.git, &extends.path) {
+        (Some(git), None) => Ok(TemplateSource::classify(git, &AppConfig::default(), origin)
+            .into_git_template_location(&CloneOptions {
+                branch: extends.branch.clone(),
+                tag: extends.tag.clone(),
+                revision: extends.revision.clone(),
+                ..clone_options.clone()
+            })),
+        (None, Some(path)) => Ok(TemplateLocation::from(origin.join(path))),
+        _ => bail!(
+            "{} {}",
+            emoji::ERROR,
+            style("`template.extends` needs either `git` or `path`")
+                .bold()
+                .red(),
+        ),
+    }
+}
+
+/// The folder, relative `extends.path`s of a template start at.
+/// That is the original folder for local templates, and the fetched copy otherwise.
+fn origin_dir(
+    location: &TemplateLocation,
+    subfolder: Option<&str>,
+    template_dir: &Path,
+) -> PathBuf {
+    match location {
+        TemplateLocation::Path(path) => path.join(subfolder.unwrap_or_default()),
+        _ => template_dir.to_path_buf(),
+    }
+}
+
+/// Identifies a template in the chain of `extends`, to detect cycles
+fn identity(location: &TemplateLocation, subfolder: Option<&str>) -> String {
+    let source = match location {
+        TemplateLocation::Git(git) => format!(
+            "{}#{}",
+            git.url(),
+            git.revision()
+                .or_else(|| git.tag())
+                .or_else(|| git.branch())
+                .unwrap_or("HEAD")
+        ),
+        TemplateLocation::Path(path) | TemplateLocation::Archive(path) => path
+            .canonicalize()
+            .unwrap_or_else(|_| path.clone())
+            .display()
+            .to_string(),
+    };
+    match subfolder
+        .map(|s| s.trim_matches('/'))
+        .filter(|s| !s.is_empty())
+    {
+        Some(subfolder) => format!("{source}:{subfolder}"),
+        None => source,
+    }
+}
+
+/// Fail if a hook script of the parent would be replaced by a different file of the template,
+/// the hook would run the template's script instead.
+fn check_hook_files(parent_config: &Config, parent_dir: &Path, template_dir: &Path) -> Result<()> {
+    for script in parent_config.get_hook_files() {
+        let (parent_script, own_script) = (parent_dir.join(&script), template_dir.join(&script));
+        if own_script.is_file()
+            && parent_script.is_file()
+            && fs::read(&own_script)? != fs::read(&parent_script)?
+        {
+            bail!(
+                "{} {} `{}`",
+                emoji::ERROR,
+                style(
+                    "The template has a file of the same name as a hook of its parent, rename it:"
+                )
+                .bold()
+                .red(),
+                style(script).bold().red(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Copy the files of `parent_dir` into `template_dir`, unless the template has them already.
+/// The `.genignore` files of both are joined.
+fn overlay_files(parent_dir: &Path, template_dir: &Path) -> Result<()> {
+    for entry in WalkDir::new(parent_dir).min_depth(1) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(parent_dir)?;
+        let target = template_dir.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if !entry.file_type().is_file() || relative == Path::new(CONFIG_FILE_NAME) {
+            continue;
+        } else if !target.exists() {
+            fs::copy(entry.path(), &target)?;
+        } else if relative.file_name() == Some(IGNORE_FILE_NAME.as_ref()) {
+            let mut ignore_file = fs::OpenOptions::new().append(true).open(&target)?;
+            writeln!(ignore_file)?;
+            ignore_file.write_all(&fs::read(entry.path())?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merge the configuration of a template with the one of its parent, the template wins
+fn merge(config: Config, parent: Config) -> Config {
+    let template = config.template.unwrap_or_default();
+    let parent_template = parent.template.unwrap_or_default();
+    let hooks = config.hooks.unwrap_or_default();
+    let parent_hooks = parent.hooks.unwrap_or_default();
+
+    Config {
+        template: Some(TemplateConfig {
+            sub_templates: template.sub_templates,
+            cargo_generate_version: template.cargo_generate_version,
+            include: union(parent_template.include, template.include),
+            exclude: union(parent_template.exclude, template.exclude),
+            ignore: union(parent_template.ignore, template.ignore),
+            vcs: template.vcs.or(parent_template.vcs),
+            init: template.init.or(parent_template.init),
+            extends: None,
+            partials: template.partials.or(parent_template.partials),
+        }),
+        placeholders: match (parent.placeholders, config.placeholders) {
+            (Some(mut placeholders), Some(own)) => {
+                placeholders.0.extend(own.0);
+                Some(placeholders)
+            }
+            (parent, own) => own.or(parent),
+        },
+        hooks: (config_has_hooks(&hooks) || config_has_hooks(&parent_hooks)).then(|| HooksConfig {
+            init: union(parent_hooks.init, hooks.init),
+            pre: union(parent_hooks.pre, hooks.pre),
+            post: union(parent_hooks.post, hooks.post),
+        }),
+        conditional: match (parent.conditional, config.conditional) {
+            (Some(mut conditional), Some(own)) => {
+                for (condition, own) in own {
+                    let merged = match conditional.remove(&condition) {
+                        Some(parent) => merge_conditional(own, parent),
+                        None => own,
+                    };
+                    conditional.insert(condition, merged);
+                }
+                Some(conditional)
+            }
+            (parent, own) => own.or(parent),
+        },
+        filters: match (parent.filters, config.filters) {
+            (Some(mut filters), Some(own)) => {
+                filters.extend(own);
+                Some(filters)
+            }
+            (parent, own) => own.or(parent),
+        },
+    }
+}
+
+/// Merge the blocks of a template and its parent for the same condition, the template wins
+fn merge_conditional(own: ConditionalConfig, parent: ConditionalConfig) -> ConditionalConfig {
+    ConditionalConfig {
+        include: union(parent.include, own.include),
+        exclude: union(parent.exclude, own.exclude),
+        ignore: union(parent.ignore, own.ignore),
+        placeholders: match (parent.placeholders, own.placeholders) {
+            (Some(mut placeholders), Some(own)) => {
+                placeholders.0.extend(own.0);
+                Some(placeholders)
+            }
+            (parent, own) => own.or(parent),
+        },
+    }
+}
+
+const fn config_has_hooks(hooks: &HooksConfig) -> bool {
+    hooks.init.is_some() || hooks.pre.is_some() || hooks.post.is_some()
+}
+
+/// The entries of both lists, the parent's first and without duplicates
+fn union(parent: Option<Vec<String>>, own: Option<Vec<String>>) -> Option<Vec<String>> {
+    if parent.is_none() && own.is_none() {
+        return None;
+    }
+    Some(
+        parent
+            .into_iter()
+            .chain(own)
+            .flatten()
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn config(contents: &str) -> Config {
+        Config::try_from(contents.to_string()).unwrap()
+    }
+
+    #[test]
+    fn the_template_takes_precedence_over_its_parent() {
+        let parent = config(indoc! {r#"
+            [template]
+            ignore = ["target"]
+            vcs = "None"
+            [placeholders]
+            license = { type = "string", prompt = "License?", default = "MIT" }
+            ci = { type = "bool", prompt = "CI?", default = true }
+            [hooks]
+            pre = ["base.rhai"]
+            [conditional.'ci']
+            ignore = [".github"]
+        "#});
+        let child = config(indoc! {r#"
+            [template]
+            ignore = ["target", "tmp"]
+            extends = { path = "../base" }
+            [placeholders]
+            ci = { type = "bool", prompt = "Use CI?", default = false }
+            port = { type = "string", prompt = "Port?" }
+            [hooks]
+            pre = ["service.rhai"]
+        "#});
+
+        let merged = merge(child, parent);
+
+        let template = merged.template.unwrap();
+        assert_eq!(template.ignore, Some(vec!["target".into(), "tmp".into()]));
+        assert_eq!(template.vcs, Some(crate::Vcs::None));
+        assert_eq!(template.extends, None);
+        let placeholders = merged.placeholders.unwrap().0;
+        assert_eq!(
+            placeholders.keys().collect::<Vec<_>>(),
+            vec!["license", "ci", "port"]
+        );
+        assert_eq!(placeholders["ci"]["prompt"].as_str(), Some("Use CI?"));
+        assert_eq!(
+            merged.hooks.unwrap().pre,
+            Some(vec!["base.rhai".into(), "service.rhai".into()])
+        );
+        assert!(merged.conditional.unwrap().contains_key("ci"));
+    }
+
+    #[test]
+    fn files_of_the_template_are_kept() {
+        let parent = tempfile::tempdir().unwrap();
+        let template = tempfile::tempdir().unwrap();
+        for (dir, file, contents) in [
+            (parent.path(), "LICENSE", "parent"),
+            (parent.path(), "ci/build.yml", "parent"),
+            (parent.path(), "README.md", "parent"),
+            (parent.path(), CONFIG_FILE_NAME, "[template]"),
+            (parent.path(), IGNORE_FILE_NAME, "LICENSE"),
+            (template.path(), "README.md", "template"),
+            (template.path(), IGNORE_FILE_NAME, "tmp"),
+        ] {
+            std::fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+
+        overlay_files(parent.path(), template.path()).unwrap();
+
+        let read = |file: &str| std::fs::read_to_string(template.path().join(file)).unwrap();
+        assert_eq!(read("LICENSE"), "parent");
+        assert_eq!(read("ci/build.yml"), "parent");
+        assert_eq!(read("README.md"), "template");
+        assert_eq!(read(IGNORE_FILE_NAME), "tmp\nLICENSE");
+        assert!(!template.path().join(CONFIG_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn conditionals_of_the_same_name_are_merged() {
+        let parent = config(indoc! {r#"
+            [conditional.'ci']
+            ignore = [".github"]
+            placeholders = { runner = { type = "string", prompt = "Runner?", default = "ubuntu" }, cache = { type = "bool", prompt = "Cache?" } }
+        "#});
+        let child = config(indoc! {r#"
+            [conditional.'ci']
+            ignore = ["ci"]
+            placeholders = { cache = { type = "bool", prompt = "Use a cache?" }, badge = { type = "bool", prompt = "Badge?" } }
+        "#});
+
+        let merged = merge(child, parent);
+
+        let ci = &merged.conditional.unwrap()["ci"];
+        assert_eq!(ci.ignore, Some(vec![".github".into(), "ci".into()]));
+        let placeholders = &ci.placeholders.as_ref().unwrap().0;
+        assert_eq!(
+            placeholders.keys().collect::<Vec<_>>(),
+            vec!["runner", "cache", "badge"]
+        );
+        assert_eq!(
+            placeholders["cache"]["prompt"].as_str(),
+            Some("Use a cache?")
+        );
+    }
+
+    #[test]
+    fn hooks_of_the_parent_must_not_be_shadowed() {
+        let parent = tempfile::tempdir().unwrap();
+        let template = tempfile::tempdir().unwrap();
+        let parent_config = config(indoc! {r#"
+            [hooks]
+            pre = ["pre.rhai"]
+            post = ["post.rhai"]
+        "#});
+        for (dir, file, contents) in [
+            (parent.path(), "pre.rhai", "parent"),
+            (parent.path(), "post.rhai", "shared"),
+            (template.path(), "post.rhai", "shared"),
+        ] {
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+
+        check_hook_files(&parent_config, parent.path(), template.path()).unwrap();
+
+        std::fs::write(template.path().join("pre.rhai"), "template").unwrap();
+        let error = check_hook_files(&parent_config, parent.path(), template.path()).unwrap_err();
+        assert!(error.to_string().contains("pre.rhai"));
+    }
+}
@@ -2,11 +2,17 @@
 
 use crate::{
     app_config::{app_config_path, AppConfig, FavoriteConfig},
-    emoji, GenerateArgs,
+    emoji,
+    template_variables::{
+        log_template_values_from_definitions, read_template_values_from_definitions,
+    },
+    FavoriteAddArgs, FavoriteArgs, FavoriteCommand, GenerateArgs,
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use console::style;
 use log::info;
+use std::{fs, path::Path};
+use toml_edit::{DocumentMut, Item, Table, TableLike};
 
 pub fn list_favorites(args: &GenerateArgs) -> Result<()> {
     let app_config: AppConfig = app_config_path(&args.config)?.as_path().try_into()?;
@@ -57,3 +63,308 @@ pub fn list_favorites(args: &GenerateArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Add, remove, show or rename a favorite in the application config
+pub fn favorite(args: FavoriteArgs) -> Result<()> {
+    let path = match &args.config {
+        // `add` may create a new config file
+        Some(path) if !path.exists() => path.clone(),
+        config => app_config_path(config)?,
+    };
+
+    let mut document = read_document(&path)?;
+    let message = match args.command {
+        FavoriteCommand::Show { name } => {
+            let app_config: AppConfig = path.as_path().try_into()?;
+            let favorite = app_config
+                .get_favorite_cfg(&name)
+                .ok_or_else(|| not_found(&name, &path))?;
+            show(&name, favorite);
+            return Ok(());
+        }
+        FavoriteCommand::Add(add) => {
+            add_favorite(&mut document, &add)?;
+            format!("Favorite `{}` added", add.name)
+        }
+        FavoriteCommand::Remove { name } => {
+            remove_favorite(&mut document, &name).ok_or_else(|| not_found(&name, &path))?;
+            format!("Favorite `{name}` removed")
+        }
+        FavoriteCommand::Rename { from, to } => {
+            rename_favorite(&mut document, &from, &to)?.ok_or_else(|| not_found(&from, &path))?;
+            format!("Favorite `{from}` renamed to `{to}`")
+        }
+    };
+    write_document(&path, &document)?;
+
+    info!(
+        "{} {} `{}`",
+        emoji::SPARKLE,
+        style(format!("{message} in")).bold().green(),
+        style(path.display()).bold().yellow(),
+    );
+    Ok(())
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    fs::read_to_string(path)?
+        .parse()
+        .with_context(|| format!("Failed to parse the config file `{}`", path.display()))
+}
+
+/// Write `document`, after making sure it is still a valid config
+fn write_document(path: &Path, document: &DocumentMut) -> Result<()> {
+    let contents = document.to_string();
+    toml::from_str::<AppConfig>(&contents).context("The changed config file would be invalid")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+fn not_found(name: &str, path: &Path) -> anyhow::Error {
+    anyhow!(
+        "{} {} `{}`",
+        emoji::ERROR,
+        style(format!("Favorite `{name}` is not defined in"))
+            .bold()
+            .red(),
+        style(path.display()).bold().red(),
+    )
+}
+
+fn favorites_table(document: &mut DocumentMut) -> Result<&mut dyn TableLike> {
+    document
+        .entry("favorites")
+        .or_insert_with(|| {
+            let mut favorites = Table::new();
+            favorites.set_implicit(true);
+            Item::Table(favorites)
+        })
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            anyhow!(
+                "{} {}",
+                emoji::ERROR,
+                style("`favorites` in the config file is not a table")
+                    .bold()
+                    .red(),
+            )
+        })
+}
+
+fn add_favorite(document: &mut DocumentMut, args: &FavoriteAddArgs) -> Result<()> {
+    let favorites = favorites_table(document)?;
+    if favorites.contains_key(&args.name) && !args.force {
+        bail!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!(
+                "Favorite `{}` exists already, pass `--force` to replace it",
+                args.name
+            ))
+            .bold()
+            .red(),
+        );
+    }
+
+    let mut favorite = Table::new();
+    let path = args.path.as_ref().map(|p| p.display().to_string());
+    for (key, value) in [
+        ("description", args.description.as_ref()),
+        ("git", args.git.as_ref()),
+        ("path", path.as_ref()),
+        ("subfolder", args.subfolder.as_ref()),
+        ("branch", args.branch.as_ref()),
+        ("tag", args.tag.as_ref()),
+        ("revision", args.revision.as_ref()),
+    ] {
+        if let Some(value) = value {
+            favorite.insert(key, toml_edit::value(value.as_str()));
+        }
+    }
+
+    log_template_values_from_definitions(&args.define, &[])?;
+    let mut values = read_template_values_from_definitions(&args.define)?
+        .into_iter()
+        .collect::<Vec<_>>();
+    values.sort_by(|(a, _), (b, _)| a.cmp(b));
+    if !values.is_empty() {
+        let mut table = Table::new();
+        for (key, value) in values {
+            table.insert(&key, Item::Value(value.to_string().parse()?));
+        }
+        favorite.insert("values", Item::Table(table));
+    }
+
+    favorites.insert(&args.name, Item::Table(favorite));
+    Ok(())
+}
+
+/// `None` if there is no such favorite
+fn remove_favorite(document: &mut DocumentMut, name: &str) -> Option<Item> {
+    document
+        .get_mut("favorites")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|favorites| favorites.remove(name))
+}
+
+/// `Ok(None)` if there is no favorite `from`
+fn rename_favorite(document: &mut DocumentMut, from: &str, to: &str) -> Result<Option<()>> {
+    let Some(favorites) = document
+        .get_mut("favorites")
+        .and_then(Item::as_table_like_mut)
+    else {
+        return Ok(None);
+    };
+    if favorites.contains_key(to) {
+        bail!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!("Favorite `{to}` exists already"))
+                .bold()
+                .red(),
+        );
+    }
+    // the table keeps its position, so the file order doesn't change
+    Ok(favorites.remove(from).map(|favorite| {
+        favorites.insert(to, favorite);
+    }))
+}
+
+fn show(name: &str, favorite: &FavoriteConfig) {
+    info!(
+        "{} {}",
+        emoji::WRENCH,
+        style(format!("Favorite `{name}`")).bold()
+    );
+    let path = favorite.path.as_ref().map(|p| p.display().to_string());
+    let vcs = favorite.vcs.map(|vcs| format!("{vcs:?}"));
+    let init = favorite.init.map(|init| init.to_string());
+    let overwrite = favorite.overwrite.map(|overwrite| overwrite.to_string());
+    for (key, value) in [
+        ("description", favorite.description.as_ref()),
+        ("git", favorite.git.as_ref()),
+        ("path", path.as_ref()),
+        ("subfolder", favorite.subfolder.as_ref()),
+        ("branch", favorite.branch.as_ref()),
+        ("tag", favorite.tag.as_ref()),
+        ("revision", favorite.revision.as_ref()),
+        ("vcs", vcs.as_ref()),
+        ("init", init.as_ref()),
+        ("overwrite", overwrite.as_ref()),
+    ] {
+        if let Some(value) = value {
+            info!("    {}: {value}", style(key).bold());
+        }
+    }
+
+    let mut values = favorite.values.iter().flatten().collect::<Vec<_>>();
+    values.sort_by_key(|(key, _)| key.as_str());
+    if !values.is_empty() {
+        info!("    {}:", style("values").bold());
+        for (key, value) in values {
+            info!("        {key} = {value}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn document(contents: &str) -> DocumentMut {
+        contents.parse().unwrap()
+    }
+
+    #[test]
+    fn adding_keeps_comments_and_formatting() {
+        let mut doc = document(indoc! {r#"
+            # my favorites
+            [favorites.web]
+            git   = "https://example.com/web"  # the web one
+
+            [values]
+            license = "MIT"
+        "#});
+
+        add_favorite(
+            &mut doc,
+            &FavoriteAddArgs {
+                name: "cli".into(),
+                git: Some("https://example.com/cli".into()),
+                branch: Some("main".into()),
+                description: Some("A CLI".into()),
+                define: vec!["ci=true".into()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            doc.to_string(),
+            indoc! {r#"
+                # my favorites
+                [favorites.web]
+                git   = "https://example.com/web"  # the web one
+
+                [favorites.cli]
+                description = "A CLI"
+                git = "https://example.com/cli"
+                branch = "main"
+
+                [favorites.cli.values]
+                ci = "true"
+
+                [values]
+                license = "MIT"
+            "#}
+        );
+    }
+
+    #[test]
+    fn adding_an_existing_favorite_needs_force() {
+        let mut doc = document("[favorites.web]\ngit = \"a\"\n");
+        let mut args = FavoriteAddArgs {
+            name: "web".into(),
+            git: Some("b".into()),
+            ..Default::default()
+        };
+
+        assert!(add_favorite(&mut doc, &args).is_err());
+
+        args.force = true;
+        add_favorite(&mut doc, &args).unwrap();
+        assert_eq!(doc["favorites"]["web"]["git"].as_str(), Some("b"));
+    }
+
+    #[test]
+    fn removing_and_renaming_keep_the_rest() {
+        let mut doc = document(indoc! {r#"
+            [favorites.one]
+            git = "a" # first
+
+            # the second
+            [favorites.two]
+            git = "b"
+        "#});
+
+        assert!(remove_favorite(&mut doc, "three").is_none());
+        assert!(rename_favorite(&mut doc, "one", "two").is_err());
+        assert_eq!(rename_favorite(&mut doc, "one", "uno").unwrap(), Some(()));
+        assert!(remove_favorite(&mut doc, "two").is_some());
+
+        assert_eq!(
+            doc.to_string(),
+            indoc! {r#"
+                [favorites.uno]
+                git = "a" # first
+            "#}
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! A persistent cache of git templates, so that a template is only cloned once per commit.
+//!
+//! The cache lives in `$CARGO_HOME/cargo-generate-cache/git`, or wherever
+//! `$CARGO_GENERATE_CACHE_DIR` points to. Each repository has its own folder, named after its
+//! normalized url, with one snapshot folder per commit and an `index.toml`, that records the
+//! commit each requested branch or tag resolved to the last time.
+
+use anyhow::{bail, Context, Result};
+use console::style;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{copy::copy_files_recursively, emoji, user_parsed_input::GitUserInput};
+
+pub const CACHE_DIR_ENV: &str = "CARGO_GENERATE_CACHE_DIR";
+const INDEX_FILE_NAME: &str = "index.toml";
+
+/// The reference of a repository a template is requested at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WantedRef {
+    Head,
+    Branch(String),
+    Tag(String),
+    Revision(String),
+}
+
+impl From<&GitUserInput> for WantedRef {
+    fn from(git: &GitUserInput) -> Self {
+        // the same precedence as cloning: a revision overrides a tag, which overrides a branch
+        match (git.revision(), git.tag(), git.branch()) {
+            (Some(revision), _, _) => Self::Revision(revision.to_owned()),
+            (None, Some(tag), _) => Self::Tag(tag.to_owned()),
+            (None, None, Some(branch)) => Self::Branch(branch.to_owned()),
+            (None, None, None) => Self::Head,
+        }
+    }
+}
+
+impl WantedRef {
+    /// The full name of the reference, as listed by `git ls-remote`
+    pub fn ref_name(&self) -> Option<String> {
+        match self {
+            Self::Head => Some("HEAD".into()),
+            Self::Branch(branch) => Some(format!("refs/heads/{branch}")),
+            Self::Tag(tag) => Some(format!("refs/tags/{tag}")),
+            Self::Revision(_) => None,
+        }
+    }
+
+    /// The branch a checkout of this reference is on
+    pub fn branch(&self, head_branch: Option<&str>) -> Option<String> {
+        match self {
+            Self::Head => head_branch.map(str::to_owned),
+            Self::Branch(branch) => Some(branch.clone()),
+            Self::Tag(_) | Self::Revision(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for WantedRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Head => write!(f, "the default branch"),
+            Self::Branch(branch) => write!(f, "branch `{branch}`"),
+            Self::Tag(tag) => write!(f, "tag `{tag}`"),
+            Self::Revision(revision) => write!(f, "revision `{revision}`"),
+        }
+    }
+}
+
+/// The `index.toml` of a cached repository
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct RepoIndex {
+    pub url: String,
+    /// the branch `HEAD` pointed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_branch: Option<String>,
+    /// the commit each reference resolved to, by its full name, e.g. `refs/heads/main`
+    #[serde(default)]
+    pub refs: BTreeMap<String, String>,
+    /// the cached snapshots, by their folder name
+    #[serde(default)]
+    pub snapshots: BTreeMap<String, Snapshot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub commit: String,
+    pub submodules: bool,
+    /// seconds since the unix epoch
+    pub last_used: u64,
+}
+
+impl Snapshot {
+    fn dir_name(&self) -> String {
+        snapshot_dir_name(&self.commit, self.submodules)
+    }
+}
+
+fn snapshot_dir_name(commit: &str, submodules: bool) -> String {
+    if submodules {
+        commit.to_owned()
+    } else {
+        format!("{commit}-no-submodules")
+    }
+}
+
+/// A template found in the cache
+#[derive(Debug)]
+pub struct CacheHit {
+    pub path: PathBuf,
+    pub commit: String,
+    pub branch: Option<String>,
+}
+
+pub struct GitCache {
+    root: PathBuf,
+}
+
+impl GitCache {
+    /// The cache in its default location
+    pub fn open() -> Result<Self> {
+        let root = match std::env::var_os(CACHE_DIR_ENV) {
+            Some(dir) => PathBuf::from(dir),
+            None => home::cargo_home()
+                .context("$CARGO_HOME could not be determined")?
+                .join("cargo-generate-cache")
+                .join("git"),
+        };
+        Ok(Self { root })
+    }
+
+    #[cfg(test)]
+    pub const fn at(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Templates from local folders are cheap to clone, so they are never cached
+    pub fn is_cacheable(url: &str) -> bool {
+        !Path::new(url).exists()
+    }
+
+    fn repo_dir(&self, url: &str) -> PathBuf {
+        self.root.join(repo_dir_name(url))
+    }
+
+    pub fn read_index(&self, url: &str) -> Result<RepoIndex> {
+        read_index(&self.repo_dir(url))
+    }
+
+    fn write_index(&self, index: &RepoIndex) -> Result<()> {
+        let dir = self.repo_dir(&index.url);
+        fs::create_dir_all(&dir)?;
+        // write and rename, so that concurrent runs never see half an index
+        let tmp = dir.join(format!("{INDEX_FILE_NAME}.{}", std::process::id()));
+        fs::write(&tmp, toml::to_string(index)?)?;
+        fs::rename(tmp, dir.join(INDEX_FILE_NAME))?;
+        Ok(())
+    }
+
+    /// Look up the snapshot of `url` at `commit`, if `commit` is known.
+    /// Without a commit, the reference is resolved with what the index recorded last time.
+    pub fn lookup(
+        &self,
+        url: &str,
+        wanted: &WantedRef,
+        commit: Option<&str>,
+        submodules: bool,
+    ) -> Result<Option<CacheHit>> {
+        let mut index = self.read_index(url)?;
+        let commit = match (commit, wanted) {
+            (Some(commit), _) => Some(commit.to_owned()),
+            (None, WantedRef::Revision(revision)) => index
+                .snapshots
+                .values()
+                .find(|s| s.submodules == submodules && s.commit.starts_with(revision.as_str()))
+                .map(|s| s.commit.clone()),
+            (None, wanted) => wanted
+                .ref_name()
+                .and_then(|name| index.refs.get(&name).cloned()),
+        };
+        let Some(commit) = commit else {
+            return Ok(None);
+        };
+
+        let dir_name = snapshot_dir_name(&commit, submodules);
+        let path = self.repo_dir(url).join(&dir_name);
+        let Some(snapshot) = index.snapshots.get_mut(&dir_name).filter(|_| path.is_dir()) else {
+            return Ok(None);
+        };
+        snapshot.last_used = now();
+        let branch = wanted.branch(index.head_branch.as_deref());
+        self.write_index(&index)?;
+
+        Ok(Some(CacheHit {
+            path,
+            commit,
+            branch,
+        }))
+    }
+
+    /// Remember the commit `wanted` resolved to, and the branch `HEAD` points to
+    pub fn record_ref(
+        &self,
+        url: &str,
+        wanted: &WantedRef,
+        commit: &str,
+        head_branch: Option<&str>,
+    ) -> Result<()> {
+        let Some(name) = wanted.ref_name() else {
+            return Ok(());
+        };
+        let mut index = self.read_index(url)?;
+        index.url = url.to_owned();
+        index.refs.insert(name, commit.to_owned());
+        if *wanted == WantedRef::Head && head_branch.is_some() {
+            index.head_branch = head_branch.map(str::to_owned);
+        }
+        self.write_index(&index)
+    }
+
+    /// Store a copy of the cloned template at `checkout`, without its git history
+    pub fn store(&self, url: &str, checkout: &Path, commit: &str, submodules: bool) -> Result<()> {
+        let repo_dir = self.repo_dir(url);
+        let snapshot = Snapshot {
+            commit: commit.to_owned(),
+            submodules,
+            last_used: now(),
+        };
+        let path = repo_dir.join(snapshot.dir_name());
+        if !path.is_dir() {
+            fs::create_dir_all(&repo_dir)?;
+            // copy and rename, so that concurrent runs never see half a snapshot
+            let tmp = tempfile::Builder::new()
+                .prefix(".incomplete-")
+                .tempdir_in(&repo_dir)?;
+            copy_files_recursively(checkout, tmp.path(), false)?;
+            if fs::rename(tmp.path(), &path).is_err() && !path.is_dir() {
+                bail!("Failed to store the template in the cache");
+            }
+        }
+
+        let mut index = self.read_index(url)?;
+        index.url = url.to_owned();
+        index.snapshots.insert(snapshot.dir_name(), snapshot);
+        self.write_index(&index)
+    }
+
+    /// All cached repositories
+    pub fn list(&self) -> Result<Vec<RepoIndex>> {
+        if !self.root.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut repos = vec![];
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.join(INDEX_FILE_NAME).is_file() {
+                repos.push(read_index(&path)?);
+            }
+        }
+        repos.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(repos)
+    }
+
+    /// The size of a snapshot on disk, in bytes
+    pub fn snapshot_size(&self, url: &str, snapshot: &Snapshot) -> u64 {
+        walkdir::WalkDir::new(self.repo_dir(url).join(snapshot.dir_name()))
+            .into_iter()
+            .filter_map(|e| e.ok()?.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    /// Remove all snapshots, that were not used within `max_age_days`.
+    /// Returns the number of removed snapshots.
+    pub fn prune(&self, max_age_days: u64) -> Result<usize> {
+        let oldest = now().saturating_sub(max_age_days * 24 * 60 * 60);
+        let mut removed = 0;
+        for mut index in self.list()? {
+            let repo_dir = self.repo_dir(&index.url);
+            let (outdated, kept) = std::mem::take(&mut index.snapshots)
+                .into_iter()
+                .partition::<BTreeMap<_, _>, _>(|(_, s)| s.last_used < oldest);
+            for dir_name in outdated.keys() {
+                let path = repo_dir.join(dir_name);
+                if path.is_dir() {
+                    fs::remove_dir_all(path)?;
+                }
+                removed += 1;
+            }
+            index.snapshots = kept;
+            index
+                .refs
+                .retain(|_, commit| index.snapshots.values().any(|s| s.commit == *commit));
+
+            if index.snapshots.is_empty() {
+                fs::remove_dir_all(repo_dir)?;
+            } else {
+                self.write_index(&index)?;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Remove the whole cache
+    pub fn clear(&self) -> Result<()> {
+        if self.root.is_dir() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// The error for a template, that is not cached, in `--offline` mode
+pub fn offline_miss(url: &str, wanted: &WantedRef) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{} {} {}",
+        emoji::ERROR,
+        style(format!(
+            "`{url}` at {wanted} is not in the git cache, and `--offline` forbids cloning it."
+        ))
+        .bold()
+        .red(),
+        style("Run once without `--offline` to cache it.").bold(),
+    )
+}
+
+fn read_index(repo_dir: &Path) -> Result<RepoIndex> {
+    let path = repo_dir.join(INDEX_FILE_NAME);
+    if !path.exists() {
+        return Ok(RepoIndex::default());
+    }
+    toml::from_str(&fs::read_to_string(&path)?)
+        .with_context(|| format!("Invalid git cache index `{}`", path.display()))
+}
+
+/// Normalize a git url, so that the different spellings of the same repository match
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+            format!("{}://{}/{path}", scheme.to_lowercase(), host.to_lowercase())
+        }
+        None => url.to_owned(),
+    }
+}
+
+/// A readable folder name, made unique by a hash of the normalized url
+fn repo_dir_name(url: &str) -> String {
+    let url = normalize_url(url);
+    let hash = git2::Oid::hash_object(git2::ObjectType::Blob, url.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+    let readable = url
+        .split_once("://")
+        .map_or(url.as_str(), |(_, rest)| rest)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let readable = readable.trim_matches('-');
+    let readable = &readable[readable.len().saturating_sub(48)..];
+    format!("{readable}-{}", &hash[..hash.len().min(12)])
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_normalized() {
+        assert_eq!(
+            normalize_url("HTTPS://GitHub.com/Owner/Repo.git/"),
+            "https://github.com/Owner/Repo"
+        );
+        assert_eq!(
+            repo_dir_name("https://github.com/owner/repo"),
+            repo_dir_name("https://github.com/owner/repo.git")
+        );
+        assert_ne!(
+            repo_dir_name("https://github.com/owner/repo"),
+            repo_dir_name("https://github.com/owner/other")
+        );
+        assert!(
+            repo_dir_name("https://github.com/owner/repo").starts_with("github-com-owner-repo-")
+        );
+    }
+
+    #[test]
+    fn snapshots_are_found_by_ref_and_revision() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let checkout = tempfile::tempdir()?;
+        fs::write(checkout.path().join("Cargo.toml"), "")?;
+        let cache = GitCache::at(root.path().to_path_buf());
+        let url = "https://example.com/repo.git";
+        let branch = WantedRef::Branch("main".into());
+
+        assert!(cache.lookup(url, &branch, None, true)?.is_none());
+        cache.store(url, checkout.path(), "0123abcd", true)?;
+        cache.record_ref(url, &branch, "0123abcd", None)?;
+
+        let hit = cache.lookup(url, &branch, None, true)?.unwrap();
+        assert_eq!(hit.commit, "0123abcd");
+        assert_eq!(hit.branch.as_deref(), Some("main"));
+        assert!(hit.path.join("Cargo.toml").is_file());
+
+        let revision = WantedRef::Revision("0123".into());
+        assert!(cache.lookup(url, &revision, None, true)?.is_some());
+        // a snapshot with submodules is not the same as one without
+        assert!(cache.lookup(url, &revision, None, false)?.is_none());
+        assert!(cache
+            .lookup(url, &WantedRef::Tag("v1".into()), None, true)?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_unused_snapshots() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let checkout = tempfile::tempdir()?;
+        let cache = GitCache::at(root.path().to_path_buf());
+        let url = "https://example.com/repo.git";
+        cache.store(url, checkout.path(), "aaaa", true)?;
+        cache.store(url, checkout.path(), "bbbb", true)?;
+
+        let mut index = cache.read_index(url)?;
+        index.snapshots.get_mut("aaaa").unwrap().last_used = 0;
+        cache.write_index(&index)?;
+
+        assert_eq!(cache.prune(30)?, 1);
+        let index = cache.read_index(url)?;
+        assert_eq!(index.snapshots.keys().collect::<Vec<_>>(), vec!["bbbb"]);
+
+        cache.clear()?;
+        assert!(cache.list()?.is_empty());
+        Ok(())
+    }
+}
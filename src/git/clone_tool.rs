@@ -147,6 +147,45 @@ impl<'cb> RepoCloneBuilder<'cb> {
         Ok(self)
     }
 
+    /// List the references of the remote, like `git ls-remote` does
+    pub fn list_remote(self) -> Result<RemoteRefs> {
+        let gitconfig = self
+            .gitconfig
+            .map(Result::Ok)
+            .unwrap_or_else(Config::open_default)?;
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(self.authenticator.credentials(&gitconfig));
+        let mut proxy_options = ProxyOptions::new();
+        proxy_options.auto();
+
+        let mut remote = git2::Remote::create_detached(self.url.as_str())?;
+        let connection =
+            remote.connect_auth(git2::Direction::Fetch, Some(callbacks), Some(proxy_options))?;
+        let head_branch = connection.default_branch().ok().and_then(|b| {
+            b.as_str()
+                .ok()
+                .map(|b| b.trim_start_matches("refs/heads/").to_owned())
+        });
+
+        let mut refs = RemoteRefs {
+            head_branch,
+            ..RemoteRefs::default()
+        };
+        for head in connection.list()? {
+            let commit = head.oid().to_string();
+            match head.name().strip_suffix("^{}") {
+                // annotated tags are listed twice, prefer the commit they point to
+                Some(tag) => {
+                    refs.commits.insert(tag.to_owned(), commit);
+                }
+                None => {
+                    refs.commits.entry(head.name().to_owned()).or_insert(commit);
+                }
+            }
+        }
+        Ok(refs)
+    }
+
     /// creates a Result to the final GitCloneCmd wrapper
     pub fn build(self) -> Result<GitCloneCmd<'cb>> {
         if self.destination_path.is_none() {
@@ -157,6 +196,14 @@ impl<'cb> RepoCloneBuilder<'cb> {
     }
 }
 
+/// The references of a remote repository, by their full name like `refs/heads/main` or `HEAD`
+#[derive(Debug, Default)]
+pub struct RemoteRefs {
+    /// the branch `HEAD` points to
+    pub head_branch: Option<String>,
+    pub commits: std::collections::HashMap<String, String>,
+}
+
 pub struct GitCloneCmd<'cb> {
     builder: RepoCloneBuilder<'cb>,
 }
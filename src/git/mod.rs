@@ -9,11 +9,14 @@ use log::warn;
 use remove_dir_all::remove_dir_all;
 pub use utils::clone_git_template_into_temp;
 
+pub mod cache;
 mod clone_tool;
 mod gitconfig;
 mod utils;
 
-pub use utils::{tmp_dir, try_get_branch_from_path};
+pub use utils::{
+    has_uncommitted_changes, tmp_dir, try_get_branch_from_path, try_get_commit_from_path,
+};
 
 // cargo-generate (as application) want from git module:
 // 1. cloning remote
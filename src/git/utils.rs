@@ -1,11 +1,17 @@
 use anyhow::Context;
 use anyhow::Result;
+use console::style;
+use log::{info, warn};
 use std::path::{Path, PathBuf};
 
-use git2::Repository;
+use git2::{Repository, StatusOptions};
 use tempfile::TempDir;
 
+use super::cache::{offline_miss, GitCache, WantedRef};
 use super::clone_tool::RepoCloneBuilder;
+use crate::{
+    copy::copy_files_recursively, emoji, user_parsed_input::GitUserInput, TemplateRevision,
+};
 
 pub fn tmp_dir() -> std::io::Result<tempfile::TempDir> {
     tempfile::Builder::new().prefix("cargo-generate").tempdir()
@@ -32,31 +38,106 @@ pub fn home() -> Result<PathBuf> {
 }
 
 // clone git repository into temp using libgit2
-pub fn clone_git_template_into_temp(
-    git_url: &str,
-    branch: Option<&str>,
-    tag: Option<&str>,
-    revision: Option<&str>,
-    identity: Option<&Path>,
-    gitconfig: Option<&Path>,
-    skip_submodules: bool,
-) -> anyhow::Result<(TempDir, Option<String>)> {
+fn clone_into_temp(git: &GitUserInput) -> Result<(TempDir, TemplateRevision)> {
     let git_clone_dir = tmp_dir()?;
 
-    let repo = RepoCloneBuilder::new(git_url)
-        .with_branch(branch)
-        .with_ssh_identity(identity)?
-        .with_submodules(!skip_submodules)
-        .with_gitconfig(gitconfig)?
+    let repo = clone_builder(git)?
         .with_destination(git_clone_dir.path())?
-        .with_tag(tag)
-        .with_revision(revision)
+        .with_tag(git.tag())
+        .with_revision(git.revision())
         .build()?
         .do_clone()?;
 
-    let branch = get_branch_name_repo(&repo).ok();
+    let revision = TemplateRevision {
+        branch: get_branch_name_repo(&repo).ok(),
+        commit: try_get_commit_from_path(git_clone_dir.path()),
+    };
+
+    Ok((git_clone_dir, revision))
+}
+
+fn clone_builder(git: &GitUserInput) -> Result<RepoCloneBuilder<'static>> {
+    RepoCloneBuilder::new(git.url())
+        .with_branch(git.branch())
+        .with_ssh_identity(git.identity())?
+        .with_submodules(!git.skip_submodules)
+        .with_gitconfig(git.gitconfig())
+}
+
+/// Get the git template into a temp folder, from the git cache if possible.
+///
+/// The requested branch, tag or revision is resolved against the remote first, a template is
+/// only cloned, if the resolved commit is not cached yet. With `--offline` the cache is the
+/// only source.
+pub fn clone_git_template_into_temp(git: &GitUserInput) -> Result<(TempDir, TemplateRevision)> {
+    if !GitCache::is_cacheable(git.url()) {
+        return clone_into_temp(git);
+    }
+
+    let cache = GitCache::open()?;
+    let url = git.url();
+    let wanted = WantedRef::from(git);
+    let submodules = !git.skip_submodules;
+
+    let resolved = if git.offline || matches!(wanted, WantedRef::Revision(_)) {
+        None
+    } else {
+        match clone_builder(git)?.list_remote() {
+            Ok(refs) => wanted
+                .ref_name()
+                .and_then(|name| refs.commits.get(&name).cloned())
+                .map(|commit| (commit, refs.head_branch)),
+            Err(e) => {
+                warn!(
+                    "{} `{url}`, {}: {e}",
+                    style("Failed to list the references of").bold().yellow(),
+                    style("a cached template might be outdated").bold().yellow(),
+                );
+                None
+            }
+        }
+    };
+    if let Some((commit, head_branch)) = &resolved {
+        cache.record_ref(url, &wanted, commit, head_branch.as_deref())?;
+    }
+
+    if let Some(hit) = cache.lookup(
+        url,
+        &wanted,
+        resolved.as_ref().map(|(commit, _)| commit.as_str()),
+        submodules,
+    )? {
+        info!(
+            "{} {} `{}`",
+            emoji::WRENCH,
+            style("Using the cached template at commit").bold(),
+            style(&hit.commit).bold().yellow(),
+        );
+        let temp_dir = tmp_dir()?;
+        copy_files_recursively(&hit.path, temp_dir.path(), false)?;
+        return Ok((
+            temp_dir,
+            TemplateRevision {
+                branch: hit.branch,
+                commit: Some(hit.commit),
+            },
+        ));
+    }
+    if git.offline {
+        return Err(offline_miss(url, &wanted));
+    }
 
-    Ok((git_clone_dir, branch))
+    let (temp_dir, revision) = clone_into_temp(git)?;
+    if let Some(commit) = &revision.commit {
+        let head_branch = revision.branch.as_deref();
+        if let Err(e) = cache
+            .store(url, temp_dir.path(), commit, submodules)
+            .and_then(|()| cache.record_ref(url, &wanted, commit, head_branch))
+        {
+            warn!("Failed to store the template in the git cache: {e}");
+        }
+    }
+    Ok((temp_dir, revision))
 }
 
 pub fn try_get_branch_from_path(git: impl AsRef<Path>) -> Option<String> {
@@ -65,6 +146,26 @@ pub fn try_get_branch_from_path(git: impl AsRef<Path>) -> Option<String> {
         .and_then(|repo| get_branch_name_repo(&repo).ok())
 }
 
+/// The commit id `HEAD` of the repository at `git` points to, if it is a git repository
+pub fn try_get_commit_from_path(git: impl AsRef<Path>) -> Option<String> {
+    Repository::open(git).ok().and_then(|repo| {
+        repo.head()
+            .and_then(|head| head.peel_to_commit())
+            .map(|commit| commit.id().to_string())
+            .ok()
+    })
+}
+
+/// Does the git repository at `git` have changes that are not committed, including untracked files?
+pub fn has_uncommitted_changes(git: impl AsRef<Path>) -> bool {
+    Repository::open(git).is_ok_and(|repo| {
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).include_ignored(false);
+        repo.statuses(Some(&mut options))
+            .is_ok_and(|statuses| !statuses.is_empty())
+    })
+}
+
 /// thanks to @extrawurst for pointing this out
 /// <https://github.com/extrawurst/gitui/blob/master/asyncgit/src/sync/branch/mod.rs#L38>
 fn get_branch_name_repo(repo: &Repository) -> anyhow::Result<String> {
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::template::LiquidObjectResource;
+use crate::{events::Events, template::LiquidObjectResource};
 
 #[derive(Debug)]
 pub struct RhaiHooksContext {
@@ -9,4 +9,5 @@ pub struct RhaiHooksContext {
     pub silent: bool,
     pub working_directory: PathBuf,
     pub destination_directory: PathBuf,
+    pub events: Events,
 }
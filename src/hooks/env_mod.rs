@@ -29,6 +29,7 @@ pub fn create_module(env: Environment) -> Module {
 #[cfg(test)]
 mod tests {
     use crate::{
+        events::Events,
         hooks::{create_rhai_engine, RhaiHooksContext},
         template::LiquidObjectResource,
     };
@@ -40,6 +41,7 @@ mod tests {
         let context = RhaiHooksContext {
             working_directory: tmp_dir.path().to_path_buf(),
             destination_directory: tmp_dir.path().join("destination").to_path_buf(),
+            events: Events::default(),
             liquid_object: LiquidObjectResource::default(),
             allow_commands: true,
             silent: true,
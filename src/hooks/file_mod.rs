@@ -115,6 +115,7 @@ mod tests {
     use std::io::Write;
 
     use crate::{
+        events::Events,
         hooks::{create_rhai_engine, RhaiHooksContext},
         template::LiquidObjectResource,
     };
@@ -220,6 +221,7 @@ mod tests {
         RhaiHooksContext {
             working_directory: tmp_dir.path().to_path_buf(),
             destination_directory: tmp_dir.path().join("destination").to_path_buf(),
+            events: Events::default(),
             liquid_object: LiquidObjectResource::default(),
             allow_commands: true,
             silent: true,
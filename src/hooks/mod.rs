@@ -10,8 +10,10 @@ use rhai::module_resolvers::FileModuleResolver;
 use rhai::EvalAltResult;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use crate::emoji;
+use crate::events::{Event, Events};
 use crate::template::LiquidObjectResource;
 
 mod context;
@@ -28,13 +30,41 @@ pub fn execute_hooks(context: &RhaiHooksContext, scripts: &[String]) -> Result<(
     debug!("executing rhai with context: {context:?}");
 
     let engine = create_rhai_engine(context);
-    evaluate_scripts(&context.working_directory, scripts, engine)?;
+    evaluate_scripts(&context.working_directory, scripts, engine, &context.events)?;
     Ok(())
 }
 
-fn evaluate_scripts(template_dir: &Path, scripts: &[String], engine: rhai::Engine) -> Result<()> {
+fn evaluate_scripts(
+    template_dir: &Path,
+    scripts: &[String],
+    mut engine: rhai::Engine,
+    events: &Events,
+) -> Result<()> {
+    // with machine readable output, whatever the scripts print is part of the events
+    let printed = Arc::new(Mutex::new(String::new()));
+    if events.enabled() {
+        let print = Arc::clone(&printed);
+        engine.on_print(move |text| {
+            if let Ok(mut printed) = print.lock() {
+                printed.push_str(text);
+                printed.push('\n');
+            }
+        });
+        let debug = Arc::clone(&printed);
+        engine.on_debug(move |text, _, _| {
+            if let Ok(mut printed) = debug.lock() {
+                printed.push_str(text);
+                printed.push('\n');
+            }
+        });
+    }
+
     for script in scripts {
         let script: PathBuf = template_dir.join(script);
+        let relative_script = Path::new(script.strip_prefix(template_dir).unwrap_or(&script));
+        events.emit(&Event::HookStarted {
+            script: relative_script,
+        });
 
         let result = engine
             .eval_file::<rhai::Dynamic>(script.clone())
@@ -47,6 +77,18 @@ fn evaluate_scripts(template_dir: &Path, scripts: &[String], engine: rhai::Engin
                     style(script.display()).yellow(),
                 )
             })?;
+        if events.enabled() {
+            let mut output = std::mem::take(&mut *printed.lock().map_err(|_| PoisonError)?);
+            if let Ok(returned) = result.into_string() {
+                output.push_str(&returned);
+            }
+            events.emit(&Event::HookFinished {
+                script: relative_script,
+                output,
+            });
+            continue;
+        }
+
         match result.into_string() {
             Ok(output) => {
                 if !output.is_empty() {
@@ -115,7 +157,7 @@ pub fn evaluate_script<T: Clone + 'static>(
 pub fn create_rhai_engine(context: &RhaiHooksContext) -> rhai::Engine {
     let mut engine = rhai::Engine::new();
 
-    let var_mod = variable_mod::create_module(&context.liquid_object);
+    let var_mod = variable_mod::create_module(&context.liquid_object, &context.events);
     let file_mod = file_mod::create_module(&context.working_directory);
     let system_mod = system_mod::create_module(
         context.working_directory.clone(),
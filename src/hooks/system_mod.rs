@@ -67,11 +67,14 @@ fn run_command(
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: Some("no".into()),
                             kind: StringKind::Choices(vec!["yes".into(), "no".into()]),
                             regex: None,
+                            validate: None,
                         }),
                     },
                 },
@@ -156,6 +159,7 @@ mod tests {
     use std::io::Write;
 
     use crate::{
+        events::Events,
         hooks::{create_rhai_engine, RhaiHooksContext},
         template::LiquidObjectResource,
     };
@@ -170,6 +174,7 @@ mod tests {
         let context = RhaiHooksContext {
             working_directory: tmp_dir.path().to_path_buf(),
             destination_directory: tmp_dir.path().join("destination").to_path_buf(),
+            events: Events::default(),
             liquid_object: LiquidObjectResource::default(),
             allow_commands: true,
             silent: true,
@@ -219,6 +224,7 @@ mod tests {
         let context = RhaiHooksContext {
             working_directory: tmp_dir.path().to_path_buf(),
             destination_directory: tmp_dir.path().join("destination").to_path_buf(),
+            events: Events::default(),
             liquid_object: LiquidObjectResource::default(),
             allow_commands: true,
             silent: true,
@@ -239,6 +245,7 @@ mod tests {
         let context = RhaiHooksContext {
             working_directory: tmp_dir.path().to_path_buf(),
             destination_directory: tmp_dir.path().join("destination").to_path_buf(),
+            events: Events::default(),
             liquid_object: LiquidObjectResource::default(),
             allow_commands: false,
             silent: true,
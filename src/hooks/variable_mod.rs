@@ -1,15 +1,16 @@
 use liquid::ValueView;
 use liquid_core::Value;
 use regex::Regex;
-use rhai::{Array, Dynamic, Module};
+use rhai::{Array, Dynamic, Map, Module};
 
+use crate::events::{Event, Events, ValueSource};
 use crate::interactive::prompt_and_check_variable;
 use crate::project_variables::{StringEntry, StringKind, TemplateSlots, VarInfo};
-use crate::template::LiquidObjectResource;
+use crate::template::{scalar_to_toml_value, LiquidObjectResource};
 
 use super::{HookResult, PoisonError};
 
-pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
+pub fn create_module(liquid_object: &LiquidObjectResource, events: &Events) -> Module {
     let mut module = Module::new();
 
     module.set_native_fn("is_set", {
@@ -29,6 +30,8 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                 NamedValue::NonExistent => Ok(Dynamic::from(String::from(""))),
                 NamedValue::Bool(v) => Ok(Dynamic::from(v)),
                 NamedValue::String(v) => Ok(Dynamic::from(v)),
+                NamedValue::Integer(v) => Ok(Dynamic::from(v)),
+                NamedValue::Float(v) => Ok(Dynamic::from(v)),
                 NamedValue::Array(arr) => {
                     let rhai_array: Array = arr
                         .into_iter()
@@ -36,25 +39,22 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                         .collect::<HookResult<_>>()?;
                     Ok(Dynamic::from(rhai_array))
                 }
+                NamedValue::Object(obj) => liquid_to_rhai_value(obj),
             }
         }
     });
 
     module.set_native_fn("set", {
         let liquid_object = liquid_object.clone();
+        let events = events.clone();
         move |name: &str, value: &str| -> HookResult<()> {
             match liquid_object.get_value(name)? {
-                NamedValue::NonExistent | NamedValue::String(_) => {
-                    liquid_object
-                        .lock()
-                        .map_err(|_| PoisonError::new_eval_alt_result())?
-                        .borrow_mut()
-                        .insert(
-                            name.to_string().into(),
-                            Value::Scalar(value.to_string().into()),
-                        );
-                    Ok(())
-                }
+                NamedValue::NonExistent | NamedValue::String(_) => set_value(
+                    &liquid_object,
+                    &events,
+                    name,
+                    Value::Scalar(value.to_string().into()),
+                ),
                 _ => Err(format!("Variable {name} not a String").into()),
             }
         }
@@ -62,15 +62,11 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
 
     module.set_native_fn("set", {
         let liquid_object = liquid_object.clone();
+        let events = events.clone();
         move |name: &str, value: bool| -> HookResult<()> {
             match liquid_object.get_value(name)? {
                 NamedValue::NonExistent | NamedValue::Bool(_) => {
-                    liquid_object
-                        .lock()
-                        .map_err(|_| PoisonError::new_eval_alt_result())?
-                        .borrow_mut()
-                        .insert(name.to_string().into(), Value::Scalar(value.into()));
-                    Ok(())
+                    set_value(&liquid_object, &events, name, Value::Scalar(value.into()))
                 }
                 _ => Err(format!("Variable {name} not a bool").into()),
             }
@@ -79,28 +75,66 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
 
     module.set_native_fn("set", {
         let liquid_object = liquid_object.clone();
+        let events = events.clone();
+        move |name: &str, value: rhai::INT| -> HookResult<()> {
+            match liquid_object.get_value(name)? {
+                NamedValue::NonExistent | NamedValue::Integer(_) => {
+                    set_value(&liquid_object, &events, name, Value::Scalar(value.into()))
+                }
+                _ => Err(format!("Variable {name} not an integer").into()),
+            }
+        }
+    });
+
+    module.set_native_fn("set", {
+        let liquid_object = liquid_object.clone();
+        let events = events.clone();
+        move |name: &str, value: rhai::FLOAT| -> HookResult<()> {
+            match liquid_object.get_value(name)? {
+                NamedValue::NonExistent | NamedValue::Float(_) => {
+                    set_value(&liquid_object, &events, name, Value::Scalar(value.into()))
+                }
+                _ => Err(format!("Variable {name} not a float").into()),
+            }
+        }
+    });
+
+    module.set_native_fn("set", {
+        let liquid_object = liquid_object.clone();
+        let events = events.clone();
         move |name: &str, value: Array| -> HookResult<()> {
             match liquid_object.get_value(name)? {
                 NamedValue::NonExistent | NamedValue::Array(_) => {
                     let val = rhai_to_liquid_value(Dynamic::from(value))?;
-                    liquid_object
-                        .lock()
-                        .map_err(|_| PoisonError::new_eval_alt_result())?
-                        .borrow_mut()
-                        .insert(name.to_string().into(), val);
-                    Ok(())
+                    set_value(&liquid_object, &events, name, val)
                 }
                 _ => Err(format!("Variable {name} not an array").into()),
             }
         }
     });
 
+    module.set_native_fn("set", {
+        let liquid_object = liquid_object.clone();
+        let events = events.clone();
+        move |name: &str, value: Map| -> HookResult<()> {
+            match liquid_object.get_value(name)? {
+                NamedValue::NonExistent | NamedValue::Object(_) => {
+                    let val = rhai_to_liquid_value(Dynamic::from_map(value))?;
+                    set_value(&liquid_object, &events, name, val)
+                }
+                _ => Err(format!("Variable {name} not an object").into()),
+            }
+        }
+    });
+
     module.set_native_fn("prompt", {
         move |prompt: &str, default_value: bool| -> HookResult<bool> {
             let value = prompt_and_check_variable(
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::Bool {
                         default: Some(default_value),
                     },
@@ -121,11 +155,14 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: None,
                             kind: StringKind::String,
                             regex: None,
+                            validate: None,
                         }),
                     },
                 },
@@ -145,11 +182,14 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: Some(default_value.into()),
                             kind: StringKind::String,
                             regex: None,
+                            validate: None,
                         }),
                     },
                 },
@@ -169,11 +209,14 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: Some(default_value.into()),
                             kind: StringKind::String,
                             regex: Some(Regex::new(regex).map_err(|_| "Invalid regex")?),
+                            validate: None,
                         }),
                     },
                 },
@@ -193,16 +236,19 @@ pub fn create_module(liquid_object: &LiquidObjectResource) -> Module {
                 &TemplateSlots {
                     prompt: prompt.into(),
                     var_name: "".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: Some(default_value.into()),
                             kind: StringKind::Choices(
                                 choices
                                     .iter()
-                                    .map(|d| d.to_owned().into_string().unwrap())
+                                    .map(|d| d.to_owned().into_string().unwrap().into())
                                     .collect(),
                             ),
                             regex: None,
+                            validate: None,
                         }),
                     },
                 },
@@ -223,7 +269,10 @@ enum NamedValue {
     NonExistent,
     Bool(bool),
     String(String),
+    Integer(i64),
+    Float(f64),
     Array(Vec<Value>),
+    Object(Value),
 }
 
 trait GetNamedValue {
@@ -243,58 +292,89 @@ impl GetNamedValue for LiquidObjectResource {
                     let values: Vec<Value> = arr.values().map(|v| v.to_value()).collect();
                     return NamedValue::Array(values);
                 }
+                if value.as_object().is_some() {
+                    return NamedValue::Object(value.to_value());
+                }
 
                 // Then check if it's a scalar
                 value
                     .as_scalar()
-                    .map(|scalar| {
-                        scalar.to_bool().map_or_else(
-                            || {
-                                let v = scalar.to_kstr();
-                                NamedValue::String(String::from(v.as_str()))
-                            },
-                            NamedValue::Bool,
-                        )
+                    .and_then(|scalar| scalar_to_toml_value(&scalar))
+                    .map_or(NamedValue::NonExistent, |scalar| match scalar {
+                        toml::Value::Boolean(b) => NamedValue::Bool(b),
+                        toml::Value::Integer(i) => NamedValue::Integer(i),
+                        toml::Value::Float(f) => NamedValue::Float(f),
+                        other => NamedValue::String(other.as_str().unwrap_or_default().into()),
                     })
-                    .unwrap_or_else(|| NamedValue::NonExistent)
             });
         Ok(value)
     }
 }
 
+/// Set a variable on behalf of a hook
+fn set_value(
+    liquid_object: &LiquidObjectResource,
+    events: &Events,
+    name: &str,
+    value: Value,
+) -> HookResult<()> {
+    events.emit(&Event::PlaceholderValue {
+        name,
+        value: events.placeholder_value(name, &value),
+        source: ValueSource::Hook,
+    });
+    liquid_object
+        .lock()
+        .map_err(|_| PoisonError::new_eval_alt_result())?
+        .borrow_mut()
+        .insert(name.to_string().into(), value);
+    Ok(())
+}
+
 fn rhai_to_liquid_value(val: Dynamic) -> HookResult<Value> {
-    val.as_bool()
-        .map(Into::into)
-        .map(Value::Scalar)
-        .or_else(|_| val.clone().into_string().map(Into::into).map(Value::Scalar))
-        .or_else(|_| {
-            val.clone()
-                .try_cast::<Array>()
-                .ok_or_else(|| {
-                    format!(
-                        "expecting type to be string, bool or array but found a '{}' instead",
-                        val.type_name()
-                    )
-                    .into()
-                })
-                .and_then(|arr| {
-                    arr.into_iter()
-                        .map(rhai_to_liquid_value)
-                        .collect::<HookResult<_>>()
-                        .map(Value::Array)
-                })
-        })
+    if let Ok(b) = val.as_bool() {
+        return Ok(Value::Scalar(b.into()));
+    }
+    if let Ok(i) = val.as_int() {
+        return Ok(Value::Scalar(i.into()));
+    }
+    if let Ok(f) = val.as_float() {
+        return Ok(Value::Scalar(f.into()));
+    }
+    if val.is_string() {
+        return Ok(Value::Scalar(val.into_string()?.into()));
+    }
+    if val.is_array() {
+        return val
+            .cast::<Array>()
+            .into_iter()
+            .map(rhai_to_liquid_value)
+            .collect::<HookResult<_>>()
+            .map(Value::Array);
+    }
+    if val.is_map() {
+        return val
+            .cast::<Map>()
+            .into_iter()
+            .map(|(k, v)| rhai_to_liquid_value(v).map(|v| (k.to_string().into(), v)))
+            .collect::<HookResult<_>>()
+            .map(Value::Object);
+    }
+    Err(format!(
+        "expecting type to be string, bool, number, array or map but found a '{}' instead",
+        val.type_name()
+    )
+    .into())
 }
 
 pub(super) fn liquid_to_rhai_value(val: Value) -> HookResult<Dynamic> {
     match val {
-        Value::Scalar(scalar) => {
-            // Try to convert to bool first, then to string
-            scalar.to_bool().map_or_else(
-                || Ok(Dynamic::from(String::from(scalar.to_kstr().as_str()))),
-                |b| Ok(Dynamic::from(b)),
-            )
-        }
+        Value::Scalar(scalar) => Ok(match scalar_to_toml_value(&scalar) {
+            Some(toml::Value::Boolean(b)) => Dynamic::from(b),
+            Some(toml::Value::Integer(i)) => Dynamic::from(i),
+            Some(toml::Value::Float(f)) => Dynamic::from(f),
+            _ => Dynamic::from(String::from(scalar.to_kstr().as_str())),
+        }),
         Value::Array(arr) => {
             let rhai_array: Array = arr
                 .into_iter()
@@ -302,6 +382,13 @@ pub(super) fn liquid_to_rhai_value(val: Value) -> HookResult<Dynamic> {
                 .collect::<HookResult<_>>()?;
             Ok(Dynamic::from(rhai_array))
         }
+        Value::Object(obj) => {
+            let rhai_map: Map = obj
+                .into_iter()
+                .map(|(k, v)| liquid_to_rhai_value(v).map(|v| (k.as_str().into(), v)))
+                .collect::<HookResult<_>>()?;
+            Ok(Dynamic::from_map(rhai_map))
+        }
         _ => Err(format!(
             "unsupported liquid value type for conversion to rhai: {:?}",
             val
@@ -326,7 +413,7 @@ mod tests {
         let mut engine = rhai::Engine::new();
         let liquid_object = Arc::new(Mutex::new(RefCell::new(Object::new())));
 
-        let module = create_module(&liquid_object);
+        let module = create_module(&liquid_object, &Events::default());
         engine.register_static_module("variable", module.into());
 
         engine
@@ -351,6 +438,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rhai_numbers_keep_their_type() {
+        let mut engine = rhai::Engine::new();
+        let liquid_object = Arc::new(Mutex::new(RefCell::new(Object::new())));
+        liquid_object
+            .lock()
+            .unwrap()
+            .borrow_mut()
+            .insert("port".into(), Value::Scalar(8080.into()));
+        liquid_object
+            .lock()
+            .unwrap()
+            .borrow_mut()
+            .insert("version".into(), Value::Scalar("42".into()));
+
+        let module = create_module(&liquid_object, &Events::default());
+        engine.register_static_module("variable", module.into());
+
+        engine
+            .eval::<()>(
+                r#"
+            variable::set("next_port", variable::get("port") + 1);
+            variable::set("ratio", 0.5);
+            variable::set("tag", "v" + variable::get("version"));
+        "#,
+            )
+            .unwrap();
+
+        let ref_cell = liquid_object.lock().unwrap();
+        let liquid_object = ref_cell.borrow();
+
+        assert_eq!(
+            liquid_object.get("next_port"),
+            Some(&Value::Scalar(8081.into()))
+        );
+        assert_eq!(liquid_object.get("ratio"), Some(&Value::Scalar(0.5.into())));
+        assert_eq!(liquid_object.get("tag"), Some(&Value::Scalar("v42".into())));
+    }
+
     #[test]
     fn test_rhai_get_array() {
         let mut engine = rhai::Engine::new();
@@ -368,7 +494,7 @@ mod tests {
         );
 
         let liquid_object = Arc::new(Mutex::new(RefCell::new(obj)));
-        let module = create_module(&liquid_object);
+        let module = create_module(&liquid_object, &Events::default());
         engine.register_static_module("variable", module.into());
 
         // Test is_set() on array variable
@@ -397,12 +523,51 @@ mod tests {
         assert_eq!(result, "aaa,bbb,ccc,ddd,");
     }
 
+    #[test]
+    fn test_rhai_get_and_set_object() {
+        let mut engine = rhai::Engine::new();
+        let mut database = Object::new();
+        database.insert("host".into(), Value::Scalar("localhost".into()));
+        database.insert("port".into(), Value::Scalar(5432.into()));
+        let mut obj = Object::new();
+        obj.insert("database".into(), Value::Object(database));
+
+        let liquid_object = Arc::new(Mutex::new(RefCell::new(obj)));
+        let module = create_module(&liquid_object, &Events::default());
+        engine.register_static_module("variable", module.into());
+
+        engine
+            .eval::<()>(
+                r#"
+            let database = variable::get("database");
+            variable::set("url", `${database.host}:${database.port}`);
+            variable::set("services", [#{ name: "api", replicas: 2 }]);
+        "#,
+            )
+            .unwrap();
+
+        let ref_cell = liquid_object.lock().unwrap();
+        let liquid_object = ref_cell.borrow();
+
+        assert_eq!(
+            liquid_object.get("url"),
+            Some(&Value::Scalar("localhost:5432".into()))
+        );
+        let mut service = Object::new();
+        service.insert("name".into(), Value::Scalar("api".into()));
+        service.insert("replicas".into(), Value::Scalar(2.into()));
+        assert_eq!(
+            liquid_object.get("services"),
+            Some(&Value::Array(vec![Value::Object(service)]))
+        );
+    }
+
     #[test]
     fn test_rhai_get_nonexistent_array() {
         let mut engine = rhai::Engine::new();
         let liquid_object = Arc::new(Mutex::new(RefCell::new(Object::new())));
 
-        let module = create_module(&liquid_object);
+        let module = create_module(&liquid_object, &Events::default());
         engine.register_static_module("variable", module.into());
 
         // Test is_set() on non-existent variable
@@ -21,13 +21,13 @@ pub const IGNORE_FILE_NAME: &str = ".genignore";
 const CARGO_OK_FILE_NAME: &str = ".cargo-ok";
 
 /// Takes the directory path and removes the files/directories specified in the
-/// `.genignore` file
+/// `.genignore` file, returns the removed paths
 /// It handles all errors internally
 pub fn remove_unneeded_files(
     dir: &Path,
     ignored_files: &Option<Vec<String>>,
     verbose: bool,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<PathBuf>> {
     let mut items = get_ignored(dir);
     if let Some(ignored_files) = ignored_files {
         for f in ignored_files {
@@ -37,8 +37,9 @@ pub fn remove_unneeded_files(
             items.push(p);
         }
     }
+    items.retain(|item| item.exists());
     remove_dir_files(&items, verbose);
-    Ok(())
+    Ok(items)
 }
 
 fn check_if_genignore_exists(location: &Path) -> bool {
@@ -48,7 +49,8 @@ fn check_if_genignore_exists(location: &Path) -> bool {
     ignore_path.exists()
 }
 
-fn get_ignored(location: &Path) -> Vec<PathBuf> {
+/// All paths below `location`, that are never part of the expanded template
+pub fn get_ignored(location: &Path) -> Vec<PathBuf> {
     let default_ignored = [IGNORE_FILE_NAME, CONFIG_FILE_NAME, CARGO_OK_FILE_NAME]
         .iter()
         .map(|&file_name| location.join(file_name));
@@ -7,6 +7,7 @@ use std::path::Path;
 #[derive(Default)]
 pub struct Matcher(Option<MatcherKind>, Vec<String>);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShouldInclude {
     Include,
     Exclude,
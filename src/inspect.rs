@@ -0,0 +1,431 @@
+//! Implementation of `cargo generate inspect`
+
+use anyhow::Result;
+use console::style;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::{
+    app_config::{app_config_path, AppConfig},
+    config::{ConditionalConfig, Config, TemplateSlotsTable},
+    prepare_local_template,
+    project_variables::{
+        try_into_template_slots, Choice, Computed, Number, NumberKind, StringKind, TemplateSlots,
+        VarInfo,
+    },
+    read_template_config,
+    user_parsed_input::UserParsedInput,
+    validators::Validator,
+    GenerateArgs, InspectArgs, InspectFormat,
+};
+
+/// Describe a template, without expanding it
+pub fn inspect(args: InspectArgs) -> Result<()> {
+    let generate_args = GenerateArgs {
+        template_path: args.template_path,
+        ssh_identity: args.ssh_identity,
+        gitconfig: args.gitconfig,
+        skip_submodules: args.skip_submodules,
+        offline: args.offline,
+        config: args.config,
+        silent: true,
+        ..GenerateArgs::default()
+    };
+    let app_config = AppConfig::try_from(app_config_path(&generate_args.config)?.as_path())?;
+    let user_parsed_input = UserParsedInput::try_from_args_and_config(app_config, &generate_args);
+
+    let (template_base_dir, template_dir, _) = prepare_local_template(&user_parsed_input)?;
+    let config = read_template_config(
+        &template_base_dir,
+        &template_dir,
+        user_parsed_input.location(),
+        &generate_args,
+    )?;
+
+    let description = TemplateDescription::try_from(&config)?;
+    match args.format {
+        InspectFormat::Human => print!("{}", description.to_table()),
+        InspectFormat::Json => println!("{}", serde_json::to_string_pretty(&description)?),
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Default)]
+struct TemplateDescription {
+    placeholders: Vec<PlaceholderDescription>,
+    conditionals: Vec<ConditionalDescription>,
+    sub_templates: Vec<String>,
+    hooks: HooksDescription,
+}
+
+#[derive(Serialize, Debug)]
+struct PlaceholderDescription {
+    name: String,
+    #[serde(rename = "type")]
+    var_type: &'static str,
+    prompt: String,
+    default: Option<Value>,
+    choices: Option<Vec<String>>,
+    regex: Option<String>,
+    validate: Option<&'static str>,
+    when: Option<String>,
+    computed: Option<Computed>,
+    /// The bounds of a number, by `min`, `max` and `step`, or of a list by `min_items` and `max_items`
+    #[serde(flatten)]
+    bounds: BTreeMap<&'static str, Value>,
+}
+
+#[derive(Serialize, Debug)]
+struct ConditionalDescription {
+    condition: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    ignore: Vec<String>,
+    placeholders: Vec<PlaceholderDescription>,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct HooksDescription {
+    init: Vec<String>,
+    pre: Vec<String>,
+    post: Vec<String>,
+}
+
+impl TryFrom<&Config> for TemplateDescription {
+    type Error = anyhow::Error;
+
+    fn try_from(config: &Config) -> Result<Self> {
+        let conditionals = config
+            .conditional
+            .iter()
+            .flatten()
+            // the order of the conditionals is not preserved, so at least make it stable
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(condition, conditional)| {
+                ConditionalDescription::try_from((condition, conditional))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            placeholders: describe_placeholders(config.placeholders.as_ref())?,
+            conditionals,
+            sub_templates: config
+                .template
+                .as_ref()
+                .and_then(|t| t.sub_templates.clone())
+                .unwrap_or_default(),
+            hooks: HooksDescription {
+                init: config.get_init_hooks(),
+                pre: config.get_pre_hooks(),
+                post: config.get_post_hooks(),
+            },
+        })
+    }
+}
+
+impl TryFrom<(&String, &ConditionalConfig)> for ConditionalDescription {
+    type Error = anyhow::Error;
+
+    fn try_from((condition, conditional): (&String, &ConditionalConfig)) -> Result<Self> {
+        Ok(Self {
+            condition: condition.clone(),
+            include: conditional.include.clone().unwrap_or_default(),
+            exclude: conditional.exclude.clone().unwrap_or_default(),
+            ignore: conditional.ignore.clone().unwrap_or_default(),
+            placeholders: describe_placeholders(conditional.placeholders.as_ref())?,
+        })
+    }
+}
+
+fn describe_placeholders(
+    placeholders: Option<&TemplateSlotsTable>,
+) -> Result<Vec<PlaceholderDescription>> {
+    Ok(placeholders
+        .map(try_into_template_slots)
+        .transpose()?
+        .unwrap_or_default()
+        .into_values()
+        .map(PlaceholderDescription::from)
+        .collect())
+}
+
+impl From<TemplateSlots> for PlaceholderDescription {
+    fn from(slot: TemplateSlots) -> Self {
+        let mut bounds = BTreeMap::new();
+        let mut validate = None;
+        let (var_type, default, choices, regex) = match slot.var_info {
+            VarInfo::Bool { default } => ("bool", default.map(Value::from), None, None),
+            VarInfo::Number { entry } => {
+                let number = |n: Number| match n {
+                    Number::Integer(i) => Value::from(i),
+                    Number::Float(f) => Value::from(f),
+                };
+                for (name, bound) in [("min", entry.min), ("max", entry.max), ("step", entry.step)]
+                {
+                    if let Some(bound) = bound {
+                        bounds.insert(name, number(bound));
+                    }
+                }
+                let var_type = match entry.kind {
+                    NumberKind::Integer => "integer",
+                    NumberKind::Float => "float",
+                };
+                (var_type, entry.default.map(number), None, None)
+            }
+            VarInfo::Array { entry } => {
+                validate = entry.validate.map(Validator::name);
+                for (name, bound) in [
+                    ("min_items", entry.min_items),
+                    ("max_items", entry.max_items),
+                ] {
+                    if let Some(bound) = bound {
+                        bounds.insert(name, Value::from(bound));
+                    }
+                }
+                (
+                    "array",
+                    entry.default.map(Value::from),
+                    entry.choices.map(choice_values),
+                    entry.regex.map(|r| r.to_string()),
+                )
+            }
+            VarInfo::String { entry } => {
+                validate = entry.validate.map(Validator::name);
+                let (var_type, choices) = match entry.kind {
+                    StringKind::Choices(choices) => ("string", Some(choice_values(choices))),
+                    StringKind::String => ("string", None),
+                    StringKind::Editor => ("editor", None),
+                    StringKind::Text => ("text", None),
+                    StringKind::Secret => ("secret", None),
+                };
+                (
+                    var_type,
+                    entry.default.map(Value::from),
+                    choices,
+                    entry.regex.map(|r| r.to_string()),
+                )
+            }
+        };
+
+        Self {
+            name: slot.var_name,
+            var_type,
+            prompt: slot.prompt.raw,
+            default,
+            choices,
+            regex,
+            validate,
+            when: slot.when,
+            computed: slot.computed,
+            bounds,
+        }
+    }
+}
+
+impl TemplateDescription {
+    fn to_table(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(&format!("{}\n", style("Placeholders").bold()));
+        out.push_str(&placeholders_table(&self.placeholders, "  "));
+
+        if !self.conditionals.is_empty() {
+            out.push_str(&format!("\n{}\n", style("Conditionals").bold()));
+            for conditional in &self.conditionals {
+                out.push_str(&format!("  if `{}`\n", conditional.condition));
+                for (label, files) in [
+                    ("include", &conditional.include),
+                    ("exclude", &conditional.exclude),
+                    ("ignore", &conditional.ignore),
+                ] {
+                    if !files.is_empty() {
+                        out.push_str(&format!("    {label}: {}\n", files.join(", ")));
+                    }
+                }
+                if !conditional.placeholders.is_empty() {
+                    out.push_str("    placeholders:\n");
+                    out.push_str(&placeholders_table(&conditional.placeholders, "      "));
+                }
+            }
+        }
+
+        if !self.sub_templates.is_empty() {
+            out.push_str(&format!(
+                "\n{}\n  {}\n",
+                style("Sub-templates").bold(),
+                self.sub_templates.join(", ")
+            ));
+        }
+
+        let hooks = [
+            ("init", &self.hooks.init),
+            ("pre", &self.hooks.pre),
+            ("post", &self.hooks.post),
+        ];
+        if hooks.iter().any(|(_, scripts)| !scripts.is_empty()) {
+            out.push_str(&format!("\n{}\n", style("Hooks").bold()));
+            for (label, scripts) in hooks.iter().filter(|(_, s)| !s.is_empty()) {
+                out.push_str(&format!("  {label}: {}\n", scripts.join(", ")));
+            }
+        }
+
+        out
+    }
+}
+
+/// Describe the placeholders without a value, and a values file to fill in for them
+pub fn describe_missing_values(slots: Vec<TemplateSlots>) -> String {
+    let placeholders = slots
+        .into_iter()
+        .map(PlaceholderDescription::from)
+        .collect::<Vec<_>>();
+
+    let mut out = placeholders_table(&placeholders, "  ");
+    out.push_str("\nFill in and pass with `--values-file`:\n\n[values]\n");
+    for placeholder in &placeholders {
+        let value = match placeholder.var_type {
+            "bool" => "false",
+            "integer" => "0",
+            "float" => "0.0",
+            "array" => "[]",
+            _ => "\"\"",
+        };
+        out.push_str(&format!("{} = {value}", placeholder.name));
+        if let Some(choices) = &placeholder.choices {
+            out.push_str(&format!("  # {}", choices.join(", ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Only the values of the choices are described, they're what a user can pass
+fn choice_values(choices: Vec<Choice>) -> Vec<String> {
+    choices.into_iter().map(|choice| choice.value).collect()
+}
+
+fn placeholders_table(placeholders: &[PlaceholderDescription], indent: &str) -> String {
+    if placeholders.is_empty() {
+        return format!("{indent}(none)\n");
+    }
+
+    let header = ["NAME", "TYPE", "PROMPT", "DEFAULT", "CHOICES", "REGEX"].map(String::from);
+    let rows = placeholders
+        .iter()
+        .map(|p| {
+            [
+                p.name.clone(),
+                std::iter::once(p.var_type.to_string())
+                    .chain(
+                        ["min", "max", "step", "min_items", "max_items"]
+                            .into_iter()
+                            .filter_map(|name| {
+                                p.bounds
+                                    .get(name)
+                                    .map(|bound| format!("{name} {}", display_value(bound)))
+                            }),
+                    )
+                    .chain(p.validate.map(|validator| format!("validate {validator}")))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                p.prompt.clone(),
+                p.default.as_ref().map(display_value).unwrap_or_default(),
+                p.choices.as_ref().map(|c| c.join(", ")).unwrap_or_default(),
+                p.regex.clone().unwrap_or_default(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let widths = std::iter::once(&header)
+        .chain(&rows)
+        .fold([0; 6], |mut widths, row| {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+            widths
+        });
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{indent}{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn description(config: &str) -> TemplateDescription {
+        let config = Config::try_from(config.to_string()).unwrap();
+        TemplateDescription::try_from(&config).unwrap()
+    }
+
+    #[test]
+    fn it_describes_placeholders_in_json() {
+        let description = description(indoc::indoc! {r#"
+            [placeholders]
+            license = { type = "string", prompt = "License?", choices = ["MIT", "Apache-2.0"], default = "MIT" }
+            ci = { type = "bool", prompt = "CI?", default = true, when = 'license == "MIT"' }
+            version = { prompt = "Version?", regex = "^[0-9.]+$", validate = "semver" }
+        "#});
+
+        let json = serde_json::to_value(&description).unwrap();
+        assert_eq!(
+            json["placeholders"],
+            serde_json::json!([
+                { "name": "license", "type": "string", "prompt": "License?", "default": "MIT", "choices": ["MIT", "Apache-2.0"], "regex": null, "validate": null, "when": null, "computed": null },
+                { "name": "ci", "type": "bool", "prompt": "CI?", "default": true, "choices": null, "regex": null, "validate": null, "when": "license == \"MIT\"", "computed": null },
+                { "name": "version", "type": "string", "prompt": "Version?", "default": null, "choices": null, "regex": "^[0-9.]+$", "validate": "semver", "when": null, "computed": null },
+            ])
+        );
+    }
+
+    #[test]
+    fn it_describes_conditionals_and_hooks() {
+        let description = description(indoc::indoc! {r#"
+            [template]
+            sub_templates = ["a", "b"]
+
+            [hooks]
+            pre = ["pre.rhai"]
+
+            [conditional.'ci']
+            ignore = [".github"]
+            placeholders = { runner = { prompt = "Runner?" } }
+        "#});
+
+        assert_eq!(description.sub_templates, vec!["a", "b"]);
+        assert_eq!(description.hooks.pre, vec!["pre.rhai"]);
+        assert_eq!(description.conditionals.len(), 1);
+        assert_eq!(description.conditionals[0].condition, "ci");
+        assert_eq!(description.conditionals[0].ignore, vec![".github"]);
+        assert_eq!(description.conditionals[0].placeholders[0].name, "runner");
+
+        let table = description.to_table();
+        assert!(table.contains("if `ci`"));
+        assert!(table.contains("runner"));
+        assert!(table.contains("pre: pre.rhai"));
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
     emoji,
-    project_variables::{ArrayEntry, Prompt, StringEntry, StringKind, TemplateSlots, VarInfo},
+    project_variables::{
+        ArrayEntry, Choice, NumberEntry, Prompt, StringEntry, StringKind, TemplateSlots, VarInfo,
+        SECRET_MASK,
+    },
 };
 use anyhow::{anyhow, bail, Result};
 use console::style;
 use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
-use dialoguer::{Editor, Input};
+use dialoguer::{Editor, Input, Password};
 use liquid_core::Value;
 use log::warn;
 use std::{
@@ -21,12 +24,15 @@ pub fn name() -> Result<String> {
     let valid_ident = regex::Regex::new(r"^([a-zA-Z][a-zA-Z0-9_-]+)$")?;
     let project_var = TemplateSlots {
         var_name: "crate_name".into(),
+        when: None,
+        computed: None,
         prompt: "Project Name".into(),
         var_info: VarInfo::String {
             entry: Box::new(StringEntry {
                 default: None,
                 kind: StringKind::String,
                 regex: Some(valid_ident),
+                validate: None,
             }),
         },
     };
@@ -62,6 +68,10 @@ pub fn user_question(
             stdin().read_to_string(&mut buffer)?;
             Ok(buffer)
         }
+        StringKind::Secret => Password::new()
+            .with_prompt(&prompt.styled_with_default)
+            .interact()
+            .map_err(Into::<anyhow::Error>::into),
         StringKind::Choices(_) => {
             unreachable!("StringKind::Choices should be handled in the parent")
         }
@@ -82,12 +92,22 @@ pub fn prompt_and_check_variable(
                 entry,
                 &variable.prompt,
             ),
-            StringKind::String | StringKind::Text | StringKind::Editor => {
+            StringKind::String | StringKind::Text | StringKind::Editor | StringKind::Secret => {
                 handle_string_input(provided_value, &variable.var_name, entry, &variable.prompt)
             }
         },
-        VarInfo::Array { entry } => {
-            handle_multi_select_input(provided_value, &variable.var_name, entry, &variable.prompt)
+        VarInfo::Array { entry } => match &entry.choices {
+            Some(choices) => handle_multi_select_input(
+                provided_value,
+                &variable.var_name,
+                choices,
+                entry,
+                &variable.prompt,
+            ),
+            None => handle_list_input(provided_value, &variable.var_name, entry, &variable.prompt),
+        },
+        VarInfo::Number { entry } => {
+            handle_number_input(provided_value, &variable.var_name, entry, &variable.prompt)
         }
     }
 }
@@ -100,6 +120,10 @@ pub fn variable(variable: &TemplateSlots, provided_value: Option<&impl ToString>
             Ok(Value::Scalar(as_bool.into()))
         }
         VarInfo::String { .. } => Ok(Value::Scalar(user_entry.into())),
+        VarInfo::Number { entry } => {
+            let number = entry.parse(&user_entry).map_err(|reason| anyhow!(reason))?;
+            Ok(number.to_value())
+        }
         VarInfo::Array { .. } => {
             let items = if user_entry.is_empty() {
                 Vec::new()
@@ -115,77 +139,146 @@ pub fn variable(variable: &TemplateSlots, provided_value: Option<&impl ToString>
     }
 }
 
+/// What the user chose in the review summary
+pub enum Review {
+    /// generate the project with the listed values
+    Generate,
+    /// answer the entry at this index again
+    Answer(usize),
+    /// stop without generating anything
+    Abort,
+}
+
+/// List all resolved values, letting the user pick one to answer again, generate or abort
+pub fn review(entries: &[String]) -> Result<Review> {
+    let generate = style("Generate the project").bold().green().to_string();
+    let abort = style("Abort").bold().red().to_string();
+    let items = entries
+        .iter()
+        .map(String::as_str)
+        .chain([generate.as_str(), abort.as_str()])
+        .collect::<Vec<_>>();
+    let chosen = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Review the values, choose one to answer it again")
+        .items(&items)
+        .default(entries.len())
+        .interact()?;
+
+    Ok(match chosen {
+        index if index < entries.len() => Review::Answer(index),
+        index if index == entries.len() => Review::Generate,
+        _ => Review::Abort,
+    })
+}
+
 fn handle_string_input(
     provided_value: Option<String>,
     var_name: &str,
     entry: &StringEntry,
     prompt: &Prompt,
 ) -> Result<String> {
+    // a secret is never echoed back
+    let shown = |value: &str| match entry.kind {
+        StringKind::Secret => SECRET_MASK.to_string(),
+        _ => value.to_string(),
+    };
     if let Some(value) = provided_value {
-        if entry
-            .regex
-            .as_ref()
-            .map(|ex| ex.is_match(&value))
-            .unwrap_or(true)
-        {
-            return Ok(value);
-        }
-        bail!(
-            "{} {} \"{}\" {}",
-            emoji::WARN,
-            style("Sorry,").bold().red(),
-            style(&value).bold().yellow(),
-            style(format!("is not a valid value for {var_name}"))
-                .bold()
-                .red()
-        )
+        return match entry.check(&value) {
+            Ok(()) => Ok(value),
+            Err(reason) => bail!(
+                "{} {} \"{}\" {}",
+                emoji::WARN,
+                style("Sorry,").bold().red(),
+                style(shown(&value)).bold().yellow(),
+                style(format!("is not a valid value for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        };
     };
     let mut prompt: Cow<'_, Prompt> = Cow::Borrowed(prompt);
-    match &entry.regex {
-        Some(regex) => loop {
-            let user_entry = user_question(&prompt, &entry.default, &entry.kind)?;
-            if regex.is_match(&user_entry) {
-                break Ok(user_entry);
+    loop {
+        let user_entry = user_question(&prompt, &entry.default, &entry.kind)?;
+        let Err(reason) = entry.check(&user_entry) else {
+            break Ok(user_entry);
+        };
+        // the user won't see the error in stdout if in a editor
+        match entry.kind {
+            StringKind::Editor => {
+                // Editor use with_default
+                prompt.to_mut().with_default = format!(
+                    "{}: \"{user_entry}\" is not a valid value for `{var_name}`, it {reason}",
+                    prompt
+                        .with_default
+                        .split_once(':')
+                        .map(|t| t.0)
+                        .unwrap_or(&prompt.with_default)
+                );
             }
-            // the user won't see the error in stdout if in a editor
-            match entry.kind {
-                StringKind::Editor => {
-                    // Editor use with_default
-                    prompt.to_mut().with_default = format!(
-                        "{}: \"{user_entry}\" is not a valid value for `{var_name}`",
-                        prompt
-                            .with_default
-                            .split_once(':')
-                            .map(|t| t.0)
-                            .unwrap_or(&prompt.with_default)
-                    );
-                }
-                _ => {
-                    warn!(
-                        "{} \"{}\" {}",
-                        style("Sorry,").bold().red(),
-                        style(&user_entry).bold().yellow(),
-                        style(format!("is not a valid value for {var_name}"))
-                            .bold()
-                            .red()
-                    );
-                }
-            };
-        },
-        None => Ok(user_question(&prompt, &entry.default, &entry.kind)?),
+            _ => {
+                warn!(
+                    "{} \"{}\" {}",
+                    style("Sorry,").bold().red(),
+                    style(shown(&user_entry)).bold().yellow(),
+                    style(format!("is not a valid value for {var_name}, it {reason}"))
+                        .bold()
+                        .red()
+                );
+            }
+        };
+    }
+}
+
+fn handle_number_input(
+    provided_value: Option<String>,
+    var_name: &str,
+    entry: &NumberEntry,
+    prompt: &Prompt,
+) -> Result<String> {
+    if let Some(value) = provided_value {
+        return match entry.parse(&value) {
+            Ok(number) => Ok(number.to_string()),
+            Err(reason) => bail!(
+                "{} {} \"{}\" {}",
+                emoji::WARN,
+                style("Sorry,").bold().red(),
+                style(&value).bold().yellow(),
+                style(format!("is not a valid value for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        };
+    }
+    loop {
+        let mut input = Input::<String>::new().with_prompt(&prompt.styled_with_default);
+        if let Some(default) = entry.default {
+            input = input.default(default.to_string());
+        }
+        let user_entry = input.interact()?;
+        match entry.parse(&user_entry) {
+            Ok(number) => break Ok(number.to_string()),
+            Err(reason) => warn!(
+                "{} \"{}\" {}",
+                style("Sorry,").bold().red(),
+                style(&user_entry).bold().yellow(),
+                style(format!("is not a valid value for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        }
     }
 }
 
 fn handle_choice_input(
     provided_value: Option<String>,
     var_name: &str,
-    choices: &Vec<String>,
+    choices: &[Choice],
     entry: &StringEntry,
     prompt: &Prompt,
 ) -> Result<String> {
     match provided_value {
         Some(value) => {
-            if choices.contains(&value) {
+            if choices.iter().any(|c| c.value == value) {
                 Ok(value)
             } else {
                 bail!(
@@ -200,17 +293,19 @@ fn handle_choice_input(
             }
         }
         None => {
-            let default = entry
-                .default
-                .as_ref()
-                .map_or(0, |default| choices.binary_search(default).unwrap_or(0));
+            let default = entry.default.as_ref().map_or(0, |default| {
+                choices
+                    .iter()
+                    .position(|c| c.value == *default)
+                    .unwrap_or(0)
+            });
             let chosen = Select::with_theme(&ColorfulTheme::default())
                 .items(choices)
                 .with_prompt(&prompt.styled)
                 .default(default)
                 .interact()?;
 
-            Ok(choices.index(chosen).to_string())
+            Ok(choices[chosen].value.clone())
         }
     }
 }
@@ -226,14 +321,16 @@ fn parse_list(provided_value: &str) -> Vec<String> {
 
 fn check_provided_selections(
     provided_value: &str,
-    choices: &[String],
+    choices: &[Choice],
 ) -> Result<Vec<String>, Vec<String>> {
     let list = parse_list(provided_value);
     if list.is_empty() {
         return Ok(Vec::new());
     }
-    let (ok_entries, bad_entries): (Vec<String>, Vec<String>) =
-        list.iter().cloned().partition(|e| choices.contains(e));
+    let (ok_entries, bad_entries): (Vec<String>, Vec<String>) = list
+        .iter()
+        .cloned()
+        .partition(|e| choices.iter().any(|c| c.value == *e));
     if bad_entries.is_empty() {
         Ok(ok_entries)
     } else {
@@ -244,6 +341,7 @@ fn check_provided_selections(
 fn handle_multi_select_input(
     provided_value: Option<String>,
     var_name: &str,
+    choices: &[Choice],
     entry: &ArrayEntry,
     prompt: &Prompt,
 ) -> Result<String> {
@@ -252,36 +350,56 @@ fn handle_multi_select_input(
         Some(value) => value,
         // no value is provided so we have to be smarter
         None => {
-            let mut selected_by_default = Vec::<bool>::with_capacity(entry.choices.len());
+            let mut selected_by_default = Vec::<bool>::with_capacity(choices.len());
             match &entry.default {
                 // if no defaults are provided everything is disselected by default
                 None => {
-                    selected_by_default.resize(entry.choices.len(), false);
+                    selected_by_default.resize(choices.len(), false);
                 }
                 Some(default_choices) => {
-                    for choice in &entry.choices {
-                        selected_by_default.push(default_choices.contains(choice));
+                    for choice in choices {
+                        selected_by_default.push(default_choices.contains(&choice.value));
                     }
                 }
             };
 
-            let choice_indices = MultiSelect::with_theme(&ColorfulTheme::default())
-                .items(&entry.choices)
-                .with_prompt(&prompt.styled)
-                .defaults(&selected_by_default)
-                .interact()?;
+            loop {
+                let choice_indices = MultiSelect::with_theme(&ColorfulTheme::default())
+                    .items(choices)
+                    .with_prompt(&prompt.styled)
+                    .defaults(&selected_by_default)
+                    .interact()?;
 
-            choice_indices
-                .iter()
-                .filter_map(|idx| entry.choices.get(*idx))
-                .cloned()
-                .collect::<Vec<String>>()
-                .join(LIST_SEP)
+                let selected = choice_indices
+                    .iter()
+                    .filter_map(|idx| choices.get(*idx))
+                    .map(|choice| choice.value.clone())
+                    .collect::<Vec<String>>();
+                match entry.check(&selected) {
+                    Ok(()) => break selected.join(LIST_SEP),
+                    Err(reason) => warn!(
+                        "{} {}",
+                        style("Sorry,").bold().red(),
+                        style(format!("{var_name} {reason}")).bold().red()
+                    ),
+                }
+            }
         }
     };
 
-    match check_provided_selections(&val, &entry.choices) {
-        Ok(s) => Ok(s.join(LIST_SEP)),
+    match check_provided_selections(&val, choices) {
+        Ok(s) => match entry.check(&s) {
+            Ok(()) => Ok(s.join(LIST_SEP)),
+            Err(reason) => bail!(
+                "{} {} \"{}\" {}",
+                emoji::WARN,
+                style("Sorry,").bold().red(),
+                style(&val).bold().yellow(),
+                style(format!("is not a valid value for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        },
         Err(s) => {
             let err_string = if s.len() > 1 {
                 format!("are not valid values for {var_name}")
@@ -300,6 +418,100 @@ fn handle_multi_select_input(
     }
 }
 
+/// The items of a free-form list, separated by commas or newlines
+fn parse_items(provided_value: &str) -> Vec<String> {
+    provided_value
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn handle_list_input(
+    provided_value: Option<String>,
+    var_name: &str,
+    entry: &ArrayEntry,
+    prompt: &Prompt,
+) -> Result<String> {
+    if let Some(value) = provided_value {
+        let items = parse_items(&value);
+        return match entry.check(&items) {
+            Ok(()) => Ok(items.join(LIST_SEP)),
+            Err(reason) => bail!(
+                "{} {} \"{}\" {}",
+                emoji::WARN,
+                style("Sorry,").bold().red(),
+                style(&value).bold().yellow(),
+                style(format!("is not a valid value for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        };
+    }
+
+    // ask for one item after the other, until an empty one
+    let default_hint = entry
+        .default
+        .as_ref()
+        .filter(|default| !default.is_empty())
+        .map(|default| format!(" [default: {}]", style(default.join(", ")).bold()))
+        .unwrap_or_default();
+    println!(
+        "{}{default_hint} (one item per line, leave empty to finish)",
+        prompt.styled
+    );
+    let mut items: Vec<String> = vec![];
+    loop {
+        if entry.max_items.is_some_and(|max| items.len() >= max) {
+            if let Err(reason) = entry.check(&items) {
+                bail!(
+                    "{} {} {}",
+                    emoji::WARN,
+                    style("Sorry,").bold().red(),
+                    style(format!("{var_name} {reason}")).bold().red()
+                );
+            }
+            break;
+        }
+        let item = Input::<String>::new()
+            .with_prompt(format!("  {}.", items.len() + 1))
+            .allow_empty(true)
+            .interact()?;
+        let item = item.trim();
+        if item.is_empty() {
+            if items.is_empty() {
+                if let Some(default) = &entry.default {
+                    return Ok(default.join(LIST_SEP));
+                }
+            }
+            match entry.check(&items) {
+                Ok(()) => break,
+                Err(reason) => {
+                    warn!(
+                        "{} {}",
+                        style("Sorry,").bold().red(),
+                        style(format!("{var_name} {reason}")).bold().red()
+                    );
+                    continue;
+                }
+            }
+        }
+        match entry.check_item(item) {
+            Ok(()) => items.push(item.to_string()),
+            Err(reason) => warn!(
+                "{} \"{}\" {}",
+                style("Sorry,").bold().red(),
+                style(item).bold().yellow(),
+                style(format!("is not a valid item for {var_name}, it {reason}"))
+                    .bold()
+                    .red()
+            ),
+        }
+    }
+    Ok(items.join(LIST_SEP))
+}
+
 fn handle_bool_input(
     provided_value: Option<String>,
     prompt: &Prompt,
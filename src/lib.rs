@@ -24,29 +24,43 @@
 
 mod absolute_path;
 mod app_config;
+mod archive;
 mod args;
+mod cache;
 mod config;
 mod copy;
+mod dry_run;
 mod emoji;
+mod events;
+mod extends;
 mod favorites;
 mod filenames;
 mod git;
 mod hooks;
 mod ignore_me;
 mod include_exclude;
+mod inspect;
 mod interactive;
+mod lint;
 mod progressbar;
 mod project_variables;
+mod provenance;
 mod template;
 mod template_filters;
 mod template_source;
 mod template_variables;
+mod update;
 mod user_parsed_input;
+mod validators;
 mod workspace_member;
 
 pub use crate::app_config::{app_config_path, AppConfig};
-pub use crate::favorites::list_favorites;
+pub use crate::cache::cache;
+pub use crate::favorites::{favorite, list_favorites};
+pub use crate::inspect::inspect;
+pub use crate::lint::lint;
 use crate::template::create_liquid_engine;
+pub use crate::update::update;
 pub use args::*;
 
 use anyhow::{anyhow, bail, Context, Result};
@@ -54,18 +68,24 @@ use config::{locate_template_configs, Config, CONFIG_FILE_NAME};
 use console::style;
 use copy::{copy_files_recursively, LIQUID_SUFFIX};
 use env_logger::fmt::Formatter;
+use events::{Event, Events, ValueSource};
 use fs_err as fs;
-use hooks::{execute_hooks, RhaiHooksContext};
+use hooks::{execute_hooks, PoisonError, RhaiHooksContext};
 use ignore_me::remove_dir_files;
+use include_exclude::ShouldInclude;
+use indexmap::IndexMap;
 use interactive::{prompt_and_check_variable, LIST_SEP};
+use liquid_core::ValueView;
 use log::Record;
 use log::{info, warn};
-use project_variables::{StringEntry, StringKind, TemplateSlots, VarInfo};
+use project_variables::{Computed, StringEntry, StringKind, TemplateSlots, VarInfo, SECRET_MASK};
+use provenance::Provenance;
 use std::{
+    borrow::Cow,
     cell::RefCell,
     collections::HashMap,
     env,
-    io::Write,
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
@@ -75,14 +95,19 @@ use workspace_member::WorkspaceMemberStatus;
 
 use crate::git::tmp_dir;
 use crate::template_variables::{
-    load_env_and_args_template_values, CrateName, ProjectDir, ProjectNameInput,
+    load_env_and_args_template_values, load_template_value_sources,
+    log_template_values_from_definitions, read_template_values_from_definitions,
+    write_template_values_file, CrateName, ProjectDir, ProjectNameInput,
 };
 use crate::{project_variables::ConversionError, template_variables::ProjectName};
 
 use self::config::TemplateConfig;
-use self::git::try_get_branch_from_path;
+use self::git::{try_get_branch_from_path, try_get_commit_from_path};
 use self::hooks::evaluate_script;
-use self::template::{create_liquid_object, set_project_name_variables, LiquidObjectResource};
+use self::template::{
+    create_liquid_object, set_project_name_variables, template_values_from_liquid_object,
+    toml_to_liquid_value, FileOutcomes, LiquidObjectResource, Partials, TrackedObject,
+};
 
 /// Logging formatter function
 pub fn log_formatter(
@@ -100,6 +125,13 @@ pub fn log_formatter(
 
 /// # Panics
 pub fn generate(args: GenerateArgs) -> Result<PathBuf> {
+    let mut events = Events::new(args.message_format);
+    let result = generate_project(args, &mut events);
+    events.emit_result(&result);
+    result
+}
+
+fn generate_project(args: GenerateArgs, events: &mut Events) -> Result<PathBuf> {
     let app_config = AppConfig::try_from(app_config_path(&args.config)?.as_path())?;
 
     // mash AppConfig and CLI arguments together into UserParsedInput
@@ -109,11 +141,28 @@ pub fn generate(args: GenerateArgs) -> Result<PathBuf> {
         .template_values_mut()
         .extend(load_env_and_args_template_values(&args)?);
 
-    let (template_base_dir, template_dir, branch) = prepare_local_template(&user_parsed_input)?;
-
-    // read configuration in the template
-    let mut config = Config::from_path(
-        &locate_template_file(CONFIG_FILE_NAME, &template_base_dir, &template_dir).ok(),
+    let (template_base_dir, template_dir, revision) = prepare_local_template(&user_parsed_input)?;
+    events.emit(&Event::SourceResolved {
+        git: match user_parsed_input.location() {
+            TemplateLocation::Git(git) => Some(git.url()),
+            TemplateLocation::Path(_) | TemplateLocation::Archive(_) => None,
+        },
+        path: match user_parsed_input.location() {
+            TemplateLocation::Git(_) => None,
+            TemplateLocation::Path(path) | TemplateLocation::Archive(path) => Some(path),
+        },
+        branch: revision.branch.as_deref(),
+        commit: revision.commit.as_deref(),
+    });
+    events.emit(&Event::TemplateDirSelected {
+        subfolder: relative_template_dir(&template_base_dir, &template_dir).as_deref(),
+    });
+
+    let mut config = read_template_config(
+        &template_base_dir,
+        &template_dir,
+        user_parsed_input.location(),
+        &args,
     )?;
 
     // the `--init` parameter may also be set by the template itself
@@ -134,7 +183,36 @@ pub fn generate(args: GenerateArgs) -> Result<PathBuf> {
 
     check_cargo_generate_version(&config)?;
 
-    let project_dir = expand_template(&template_dir, &mut config, &user_parsed_input, &args)?;
+    // known before the conditional blocks are merged, which drops those that are false
+    let secrets = project_variables::secret_names(&config);
+    events.set_secrets(secrets.clone());
+    log_template_values_from_definitions(&args.define, &secrets)?;
+
+    let (project_dir, liquid_object, outcomes) = expand_template(
+        &template_dir,
+        &mut config,
+        &user_parsed_input,
+        &args,
+        events,
+    )?;
+    events.emit_file_outcomes(&project_dir, &outcomes, user_parsed_input.overwrite());
+    if let Some(path) = args.save_values.as_deref() {
+        write_template_values_file(
+            path,
+            &template_values_from_liquid_object(&liquid_object, &secrets)?,
+        )?;
+        info!(
+            "{} {} `{}`",
+            emoji::WRENCH,
+            style("Values saved to").bold(),
+            style(path.display()).bold().yellow(),
+        );
+    }
+    if args.dry_run {
+        dry_run::report(&project_dir, &outcomes, user_parsed_input.overwrite());
+        return Ok(project_dir);
+    }
+
     let (mut should_initialize_git, with_force) = {
         let vcs = &config
             .template
@@ -151,12 +229,31 @@ pub fn generate(args: GenerateArgs) -> Result<PathBuf> {
     let target_path = if user_parsed_input.test() {
         test_expanded_template(&template_dir, args.other_args)?
     } else {
+        let provenance = args
+            .provenance
+            .then(|| {
+                Provenance::new(
+                    user_parsed_input.location(),
+                    relative_template_dir(&template_base_dir, &template_dir),
+                    &revision,
+                    &user_parsed_input,
+                    &liquid_object,
+                    &secrets,
+                )
+            })
+            .transpose()?;
         let project_path = copy_expanded_template(template_dir, project_dir, user_parsed_input)?;
+        if let Some(provenance) = provenance {
+            provenance.write(&project_path)?;
+        }
 
         if !args.no_workspace {
             match workspace_member::add_to_workspace(&project_path)? {
                 WorkspaceMemberStatus::Added(workspace_cargo_toml) => {
                     should_initialize_git = with_force;
+                    events.emit(&Event::WorkspaceMemberAdded {
+                        workspace: &workspace_cargo_toml,
+                    });
                     info!(
                         "{} {} `{}`",
                         emoji::WRENCH,
@@ -180,7 +277,11 @@ pub fn generate(args: GenerateArgs) -> Result<PathBuf> {
             style("Initializing a fresh Git repository").bold()
         );
 
-        git::init(&target_path, branch.as_deref(), with_force)?;
+        git::init(&target_path, revision.branch.as_deref(), with_force)?;
+        events.emit(&Event::GitInitialized {
+            path: &target_path,
+            branch: revision.branch.as_deref(),
+        });
     }
 
     info!(
@@ -240,48 +341,88 @@ fn test_expanded_template(template_dir: &Path, args: Option<Vec<String>>) -> Res
         .ok_or_else(|| anyhow!("{} Testing failed", emoji::ERROR))
 }
 
+/// The git revision a template was taken from, as far as it is known.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TemplateRevision {
+    pub(crate) branch: Option<String>,
+    pub(crate) commit: Option<String>,
+}
+
 fn prepare_local_template(
     source_template: &UserParsedInput,
-) -> Result<(TempDir, PathBuf, Option<String>), anyhow::Error> {
-    let (temp_dir, branch) = get_source_template_into_temp(source_template.location())?;
+) -> Result<(TempDir, PathBuf, TemplateRevision), anyhow::Error> {
+    let (temp_dir, revision) = get_source_template_into_temp(source_template.location())?;
     let template_folder = resolve_template_dir(
         &temp_dir,
         source_template.subfolder(),
         source_template.silent(),
     )?;
 
-    Ok((temp_dir, template_folder, branch))
+    Ok((temp_dir, template_folder, revision))
 }
 
 fn get_source_template_into_temp(
     template_location: &TemplateLocation,
-) -> Result<(TempDir, Option<String>)> {
+) -> Result<(TempDir, TemplateRevision)> {
     match template_location {
         TemplateLocation::Git(git) => {
-            let result = git::clone_git_template_into_temp(
-                git.url(),
-                git.branch(),
-                git.tag(),
-                git.revision(),
-                git.identity(),
-                git.gitconfig(),
-                git.skip_submodules,
-            );
-            if let Ok((ref temp_dir, _)) = result {
-                git::remove_history(temp_dir.path())?;
-                strip_liquid_suffixes(temp_dir.path())?;
-            };
-            result
+            let (temp_dir, revision) = git::clone_git_template_into_temp(git)?;
+            git::remove_history(temp_dir.path())?;
+            strip_liquid_suffixes(temp_dir.path())?;
+            Ok((temp_dir, revision))
         }
         TemplateLocation::Path(path) => {
             let temp_dir = tmp_dir()?;
             copy_files_recursively(path, temp_dir.path(), false)?;
             git::remove_history(temp_dir.path())?;
-            Ok((temp_dir, try_get_branch_from_path(path)))
+            Ok((
+                temp_dir,
+                TemplateRevision {
+                    branch: try_get_branch_from_path(path),
+                    commit: try_get_commit_from_path(path),
+                },
+            ))
+        }
+        TemplateLocation::Archive(archive) => {
+            let unpacked = tmp_dir()?;
+            let root = archive::unpack(archive, unpacked.path())?;
+            // copying gives the same treatment of `.liquid` suffixes and `.git` as for paths
+            let temp_dir = tmp_dir()?;
+            copy_files_recursively(root, temp_dir.path(), false)?;
+            Ok((temp_dir, TemplateRevision::default()))
         }
     }
 }
 
+/// read the configuration in the template, together with the configurations it extends
+fn read_template_config(
+    template_base_dir: &TempDir,
+    template_dir: &Path,
+    template_location: &TemplateLocation,
+    args: &GenerateArgs,
+) -> Result<Config> {
+    let config_path = locate_template_file(CONFIG_FILE_NAME, template_base_dir, template_dir).ok();
+    config::check_unknown_keys(&config_path, args.strict)?;
+    extends::inherit(
+        Config::from_path(&config_path)?,
+        template_dir,
+        template_location,
+        relative_template_dir(template_base_dir, template_dir).as_deref(),
+        args,
+    )
+}
+
+/// The location of the expanded template relative to the root of the template source,
+/// `None` if the template is the root itself.
+fn relative_template_dir(template_base_dir: &TempDir, template_dir: &Path) -> Option<String> {
+    let base = fs::canonicalize(template_base_dir.path()).ok()?;
+    let dir = fs::canonicalize(template_dir).ok()?;
+    dir.strip_prefix(base)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .map(|relative| relative.display().to_string())
+}
+
 /// remove .liquid suffixes from git templates for parity with path templates
 fn strip_liquid_suffixes(dir: impl AsRef<Path>) -> Result<()> {
     for entry in fs::read_dir(dir.as_ref())? {
@@ -402,16 +543,19 @@ fn auto_locate_template_dir(
             let prompt_args = TemplateSlots {
                 prompt: "Which template should be expanded?".into(),
                 var_name: "Template".into(),
+                when: None,
+                computed: None,
                 var_info: VarInfo::String {
                     entry: Box::new(StringEntry {
                         default: Some(config_paths[0].display().to_string()),
                         kind: StringKind::Choices(
                             config_paths
                                 .into_iter()
-                                .map(|p| p.display().to_string())
+                                .map(|p| p.display().to_string().into())
                                 .collect(),
                         ),
                         regex: None,
+                        validate: None,
                     }),
                 },
             };
@@ -439,11 +583,16 @@ fn resolve_configured_sub_templates(
                 let prompt_args = TemplateSlots {
                     prompt: "Which sub-template should be expanded?".into(),
                     var_name: "Template".into(),
+                    when: None,
+                    computed: None,
                     var_info: VarInfo::String {
                         entry: Box::new(StringEntry {
                             default: Some(sub_templates[0].clone()),
-                            kind: StringKind::Choices(sub_templates.clone()),
+                            kind: StringKind::Choices(
+                                sub_templates.iter().map(|s| s.as_str().into()).collect(),
+                            ),
                             regex: None,
+                            validate: None,
                         }),
                     },
                 };
@@ -486,14 +635,24 @@ fn expand_template(
     config: &mut Config,
     user_parsed_input: &UserParsedInput,
     args: &GenerateArgs,
-) -> Result<PathBuf> {
+    events: &Events,
+) -> Result<(PathBuf, LiquidObjectResource, FileOutcomes)> {
     let liquid_object = create_liquid_object(user_parsed_input)?;
+    // a dry run must not have side effects, unless commands are explicitly allowed
+    let run_hooks = !args.dry_run || user_parsed_input.allow_commands();
+    if !run_hooks && !config.get_hook_files().is_empty() {
+        warn!(
+            "{}",
+            style("Hooks are skipped in a dry run, pass `--allow-commands` to run them").bold()
+        );
+    }
     let context = RhaiHooksContext {
         liquid_object: liquid_object.clone(),
         allow_commands: user_parsed_input.allow_commands(),
         silent: user_parsed_input.silent(),
         working_directory: template_dir.to_owned(),
         destination_directory: user_parsed_input.destination().to_owned(),
+        events: events.clone(),
     };
 
     // run init hooks - these won't have access to `crate_name`/`within_cargo_project`
@@ -501,17 +660,49 @@ fn expand_template(
     // user input!
     // The init hooks are free to set `project-name` (but it will be validated before further
     // use).
-    execute_hooks(&context, &config.get_init_hooks())?;
+    if run_hooks {
+        execute_hooks(&context, &config.get_init_hooks())?;
+    }
 
     let project_name_input = ProjectNameInput::try_from((&liquid_object, user_parsed_input))?;
+    let project_name_source = match user_parsed_input.name() {
+        Some(name) if name == project_name_input.as_ref() => ValueSource::Provided,
+        // any other name in the liquid object was set by an init hook
+        _ if liquid_object
+            .lock()
+            .map_err(|_| PoisonError)?
+            .borrow()
+            .contains_key("project-name") =>
+        {
+            ValueSource::Hook
+        }
+        _ if std::env::var_os("CARGO_GENERATE_VALUE_PROJECT_NAME").is_some() => {
+            ValueSource::Provided
+        }
+        _ => ValueSource::Prompt,
+    };
     let project_name = ProjectName::from((&project_name_input, user_parsed_input));
     let crate_name = CrateName::from(&project_name_input);
     let destination = ProjectDir::try_from((&project_name_input, user_parsed_input))?;
     if !user_parsed_input.init() {
-        destination.create()?;
+        if args.dry_run {
+            destination.ensure_absent()?;
+        } else {
+            destination.create()?;
+        }
     }
 
     set_project_name_variables(&liquid_object, &destination, &project_name, &crate_name)?;
+    for (name, value, source) in [
+        ("project-name", project_name.as_ref(), project_name_source),
+        ("crate_name", crate_name.as_ref(), ValueSource::Builtin),
+    ] {
+        events.emit(&Event::PlaceholderValue {
+            name,
+            value: value.into(),
+            source,
+        });
+    }
 
     info!(
         "{} {} {}",
@@ -534,14 +725,30 @@ fn expand_template(
         style("...").bold()
     );
 
+    // the engine for computed placeholders and defaults, partials are not loaded yet
+    let rhai_filter_files = Arc::new(Mutex::new(vec![]));
+    let placeholder_engine = create_liquid_engine(
+        template_dir.to_owned(),
+        liquid_object.clone(),
+        user_parsed_input.allow_commands(),
+        user_parsed_input.silent(),
+        rhai_filter_files.clone(),
+        Partials::empty(),
+        config.filters.as_ref().unwrap_or(&HashMap::new()),
+        events,
+    );
+
     // evaluate config for placeholders and and any that are undefined
     fill_placeholders_and_merge_conditionals(
         config,
         &liquid_object,
+        &placeholder_engine,
         user_parsed_input.template_values(),
         args,
+        events,
     )?;
-    add_missing_provided_values(&liquid_object, user_parsed_input.template_values())?;
+    project_variables::show_computed_variables_with_value(&liquid_object, config);
+    add_missing_provided_values(&liquid_object, user_parsed_input.template_values(), events)?;
 
     let context = RhaiHooksContext {
         liquid_object: Arc::clone(&liquid_object),
@@ -550,32 +757,61 @@ fn expand_template(
     };
 
     // run pre-hooks
-    execute_hooks(&context, &config.get_pre_hooks())?;
+    if run_hooks {
+        execute_hooks(&context, &config.get_pre_hooks())?;
+    }
 
     // walk/evaluate the template
-    let all_hook_files = config.get_hook_files();
+    // the scripts of hooks and filters are never part of the output
+    let script_files = config
+        .get_hook_files()
+        .into_iter()
+        .chain(config.get_filter_files())
+        .collect::<Vec<_>>();
     let mut template_config = config.template.take().unwrap_or_default();
 
-    ignore_me::remove_unneeded_files(template_dir, &template_config.ignore, args.verbose)?;
+    // partials are read before their folder is removed, they are never part of the output
+    let partials = template::load_partials(template_dir, template_config.partials.as_deref())?;
+    let ignored = template_config
+        .ignore
+        .iter()
+        .flatten()
+        .chain(&template_config.partials)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut outcomes =
+        ignore_me::remove_unneeded_files(template_dir, &Some(ignored), args.verbose)?
+            .into_iter()
+            .filter_map(|ignored| {
+                ignored
+                    .strip_prefix(template_dir)
+                    .ok()
+                    .map(|relative| (relative.to_path_buf(), ShouldInclude::Ignore))
+            })
+            .collect::<Vec<_>>();
     let mut pbar = progressbar::new();
 
-    let rhai_filter_files = Arc::new(Mutex::new(vec![]));
     let rhai_engine = create_liquid_engine(
         template_dir.to_owned(),
         liquid_object.clone(),
         user_parsed_input.allow_commands(),
         user_parsed_input.silent(),
         rhai_filter_files.clone(),
+        partials,
+        config.filters.as_ref().unwrap_or(&HashMap::new()),
+        events,
     );
     let result = template::walk_dir(
         &mut template_config,
         template_dir,
-        &all_hook_files,
+        &script_files,
         &liquid_object,
         rhai_engine,
         &rhai_filter_files,
         &mut pbar,
         args.quiet,
+        &mut outcomes,
     );
 
     match result {
@@ -592,10 +828,12 @@ fn expand_template(
     };
 
     // run post-hooks
-    execute_hooks(&context, &config.get_post_hooks())?;
+    if run_hooks {
+        execute_hooks(&context, &config.get_post_hooks())?;
+    }
 
     // remove all hook and filter files as they are never part of the template output.
-    // Hook files are configured as relative names, so anchor them to `template_dir`;
+    // Hook and filter files are configured as relative names, so anchor them to `template_dir`;
     // `remove_dir_files` checks `Path::exists`, which would otherwise resolve them
     // against the process CWD (the rhai filter files are already absolute).
     let rhai_filter_files = rhai_filter_files
@@ -605,15 +843,15 @@ fn expand_template(
         .cloned()
         .collect::<Vec<_>>();
     remove_dir_files(
-        all_hook_files
+        script_files
             .into_iter()
-            .map(|hook_file| template_dir.join(hook_file))
+            .map(|script_file| template_dir.join(script_file))
             .chain(rhai_filter_files),
         false,
     );
 
     config.template.replace(template_config);
-    Ok(destination.as_ref().to_owned())
+    Ok((destination.as_ref().to_owned(), liquid_object, outcomes))
 }
 
 /// Try to add all provided `template_values` to the `liquid_object`.
@@ -623,6 +861,7 @@ fn expand_template(
 pub(crate) fn add_missing_provided_values(
     liquid_object: &LiquidObjectResource,
     template_values: &HashMap<String, toml::Value>,
+    events: &Events,
 ) -> Result<(), anyhow::Error> {
     template_values.iter().try_for_each(|(k, v)| {
         if RefCell::borrow(&liquid_object.lock().unwrap()).contains_key(k.as_str()) {
@@ -630,28 +869,30 @@ pub(crate) fn add_missing_provided_values(
         }
         // we have a value without a slot in the liquid object.
         // try to create the slot from the provided value
-        let value = match v {
-            toml::Value::String(content) => liquid_core::Value::Scalar(content.clone().into()),
-            toml::Value::Boolean(content) => liquid_core::Value::Scalar((*content).into()),
-            _ => anyhow::bail!(format!(
-                "{} {}",
-                emoji::ERROR,
-                style("Unsupported value type. Only Strings and Booleans are supported.")
-                    .bold()
-                    .red(),
-            )),
-        };
+        let value = toml_to_liquid_value(v);
+        events.emit(&Event::PlaceholderValue {
+            name: k,
+            value: events.placeholder_value(k, &value),
+            source: ValueSource::Provided,
+        });
         liquid_object
             .lock()
             .unwrap()
             .borrow_mut()
             .insert(k.clone().into(), value);
-        Ok(())
+        Ok::<_, anyhow::Error>(())
     })?;
     Ok(())
 }
 
 fn read_default_variable_value_from_template(slot: &TemplateSlots) -> Result<String, ()> {
+    let default_value = default_variable_value_from_template(slot).ok_or(())?;
+    log_default_value(&slot.var_name, &default_value);
+    Ok(default_value)
+}
+
+/// The default of a placeholder, as it would be provided, without logging it
+fn default_variable_value_from_template(slot: &TemplateSlots) -> Option<String> {
     let default_value = match &slot.var_info {
         VarInfo::Bool {
             default: Some(default),
@@ -663,21 +904,42 @@ fn read_default_variable_value_from_template(slot: &TemplateSlots) -> Result<Str
                 default: Some(default),
                 ..
             } => default.clone(),
-            _ => return Err(()),
+            _ => return None,
         },
         VarInfo::Array { entry } => match &entry.default {
             Some(default) => default.join(LIST_SEP),
-            None => return Err(()),
+            None => return None,
         },
-        _ => return Err(()),
+        VarInfo::Number { entry } => match entry.default {
+            Some(default) => default.to_string(),
+            None => return None,
+        },
+        _ => return None,
     };
-    let (key, value) = (&slot.var_name, &default_value);
+    Some(default_value)
+}
+
+fn log_default_value(key: &str, value: &str) {
     info!(
         "{} {} (default value from template)",
         emoji::WRENCH,
         style(format!("{key}: {value:?}")).bold(),
     );
-    Ok(default_value)
+}
+
+/// Is a value for `var_name` given with `--define`?
+fn is_defined_on_command_line(args: &GenerateArgs, var_name: &str) -> bool {
+    read_template_values_from_definitions(&args.define)
+        .is_ok_and(|definitions| definitions.contains_key(var_name))
+}
+
+/// Is `value` structured, a table or an array of tables, rather than a placeholder value?
+fn is_structured_value(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Table(_) => true,
+        toml::Value::Array(items) => items.iter().any(is_structured_value),
+        _ => false,
+    }
 }
 
 /// Turn things into strings that can be turned into strings
@@ -701,13 +963,182 @@ fn extract_toml_string(value: &toml::Value) -> Option<String> {
     }
 }
 
+/// A placeholder value and where it came from, as listed in the review summary.
+struct FilledPlaceholder {
+    slot: TemplateSlots,
+    value: liquid_core::Value,
+    source: ValueSource,
+    /// false, when skipped by its `when` condition
+    asked: bool,
+}
+
 // Evaluate the configuration, adding defined placeholder variables to the liquid object.
+//
+// When the user was asked for any value, all values are listed for review afterwards. An entry
+// can be answered again, then everything is evaluated again from the start, keeping the other
+// answers, as the new answer might add or remove questions of conditional blocks.
 fn fill_placeholders_and_merge_conditionals(
     config: &mut Config,
     liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
     template_values: &HashMap<String, toml::Value>,
     args: &GenerateArgs,
+    events: &Events,
 ) -> Result<()> {
+    // structured values can't fill a placeholder, but placeholders and conditionals may use them
+    let structured_values = template_values
+        .iter()
+        .filter(|(_, value)| is_structured_value(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    add_missing_provided_values(liquid_object, &structured_values, events)?;
+
+    // values from the command line, the environment and values files, the others are configured
+    let user_value_sources = load_template_value_sources(args)?;
+    let filled = fill_and_review(
+        config,
+        liquid_object,
+        placeholder_engine,
+        template_values,
+        args,
+        |filled| {
+            if args.silent || !filled.iter().any(|f| f.source == ValueSource::Prompt) {
+                return Ok(interactive::Review::Generate);
+            }
+            let entries = filled
+                .iter()
+                .map(|f| {
+                    let value = if f.slot.is_secret() {
+                        SECRET_MASK.to_string()
+                    } else {
+                        liquid_value_to_string(&f.value)
+                    };
+                    let source =
+                        value_source_label(&user_value_sources, &f.slot.var_name, f.source);
+                    format!("{}: {value} ({source})", f.slot.var_name)
+                })
+                .collect::<Vec<_>>();
+            interactive::review(&entries)
+        },
+        |slot| interactive::variable(slot, None::<&String>),
+    )?;
+
+    // only the values the project is generated with are reported
+    for f in &filled {
+        events.emit(&Event::PlaceholderValue {
+            name: &f.slot.var_name,
+            value: if f.slot.is_secret() {
+                SECRET_MASK.into()
+            } else {
+                events::json_value(&f.value)
+            },
+            source: f.source,
+        });
+    }
+    Ok(())
+}
+
+/// Fill all placeholders, and let `review` choose entries to `answer` again, until it's done.
+fn fill_and_review(
+    config: &mut Config,
+    liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
+    template_values: &HashMap<String, toml::Value>,
+    args: &GenerateArgs,
+    mut review: impl FnMut(&[FilledPlaceholder]) -> Result<interactive::Review>,
+    mut answer: impl FnMut(&TemplateSlots) -> Result<liquid_core::Value>,
+) -> Result<Vec<FilledPlaceholder>> {
+    let initial_config = config.clone();
+    let initial_object = liquid_object
+        .lock()
+        .map_err(|_| PoisonError)?
+        .borrow()
+        .clone();
+    let mut answers = HashMap::new();
+    loop {
+        let filled = fill_and_merge(
+            config,
+            liquid_object,
+            placeholder_engine,
+            template_values,
+            &answers,
+            args,
+        )?;
+        let index = match review(&filled)? {
+            interactive::Review::Generate => return Ok(filled),
+            interactive::Review::Abort => bail!(
+                "{} {}",
+                emoji::ERROR,
+                style("Generation aborted").bold().red()
+            ),
+            interactive::Review::Answer(index) => index,
+        };
+
+        // keep what was answered, apart from the entry to answer again
+        answers = filled
+            .iter()
+            .filter(|f| f.source == ValueSource::Prompt)
+            .map(|f| (f.slot.var_name.clone(), f.value.clone()))
+            .collect();
+        let FilledPlaceholder { slot, asked, .. } = &filled[index];
+        if *asked {
+            answers.insert(slot.var_name.clone(), answer(slot)?);
+        } else {
+            warn!(
+                "`{}` is skipped by its `when` condition, and can't be answered",
+                slot.var_name
+            );
+        }
+
+        *config = initial_config.clone();
+        *liquid_object.lock().map_err(|_| PoisonError)?.borrow_mut() = initial_object.clone();
+    }
+}
+
+/// Where a value came from, as shown in the review summary
+fn value_source_label(
+    user_value_sources: &IndexMap<String, &'static str>,
+    var_name: &str,
+    source: ValueSource,
+) -> &'static str {
+    match source {
+        // the `values` of the config file, or of a favorite, unless given by the user
+        ValueSource::Provided => user_value_sources
+            .get(var_name)
+            .copied()
+            .unwrap_or("config file"),
+        ValueSource::Default => "default",
+        ValueSource::Prompt => "prompt",
+        ValueSource::Hook => "hook",
+        ValueSource::Builtin => "builtin",
+    }
+}
+
+/// A value, as shown in the review summary, lists are joined by [`LIST_SEP`].
+fn liquid_value_to_string(value: &liquid_core::Value) -> String {
+    match value {
+        liquid_core::Value::Array(items) => items
+            .iter()
+            .map(|item| item.to_kstr().to_string())
+            .collect::<Vec<_>>()
+            .join(LIST_SEP),
+        value => value.to_kstr().to_string(),
+    }
+}
+
+// Fill all placeholders and merge the conditional blocks, returning the filled placeholders.
+fn fill_and_merge(
+    config: &mut Config,
+    liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
+    template_values: &HashMap<String, toml::Value>,
+    answers: &HashMap<String, liquid_core::Value>,
+    args: &GenerateArgs,
+) -> Result<Vec<FilledPlaceholder>> {
+    let filled = RefCell::new(Vec::new());
+    // without a way to ask, all questions without an answer are reported together at the end
+    let can_ask = !args.silent && std::io::stdin().is_terminal();
+    let missing = RefCell::new(Vec::<TemplateSlots>::new());
     let mut conditionals = config.conditional.take().unwrap_or_default();
 
     loop {
@@ -716,19 +1147,72 @@ fn fill_placeholders_and_merge_conditionals(
             let provided_value = template_values
                 .get(&slot.var_name)
                 .and_then(extract_toml_string);
-            if provided_value.is_none() && args.silent {
-                let default_value = match read_default_variable_value_from_template(slot) {
-                    Ok(string) => string,
-                    Err(()) => {
-                        anyhow::bail!(ConversionError::MissingDefaultValueForPlaceholderVariable {
-                            var_name: slot.var_name.clone()
-                        })
-                    }
+            if let (Some(expression), None) = (&slot.computed, &provided_value) {
+                return compute_placeholder(liquid_object, placeholder_engine, slot, expression)?
+                    .map(|value| interactive::variable(slot, Some(&value)))
+                    .transpose();
+            }
+            if slot.is_secret() && is_defined_on_command_line(args, &slot.var_name) {
+                anyhow::bail!(
+                    "{} {}",
+                    emoji::ERROR,
+                    style(format!(
+                        "The secret `{}` can't be passed with `--define`, use an environment variable or a values file",
+                        slot.var_name
+                    ))
+                    .bold()
+                    .red(),
+                );
+            }
+            let slot = &*render_default(liquid_object, placeholder_engine, slot)?;
+            let asked = is_placeholder_asked(liquid_object, slot)?;
+            // an answer given in the review only counts while the question is asked
+            if let Some(answer) = answers.get(&slot.var_name).filter(|_| asked) {
+                filled.borrow_mut().push(FilledPlaceholder {
+                    slot: slot.clone(),
+                    value: answer.clone(),
+                    source: ValueSource::Prompt,
+                    asked,
+                });
+                return Ok(Some(answer.clone()));
+            }
+            let default_value = default_variable_value_from_template(slot);
+            if provided_value.is_none() && default_value.is_none() && asked && !can_ask {
+                // stays undefined, so the conditionals are evaluated with the values that are known
+                let mut missing = missing.borrow_mut();
+                if !missing.iter().any(|m| m.var_name == slot.var_name) {
+                    missing.push(slot.clone());
+                }
+                return Ok(None);
+            }
+            let (value, source) = if provided_value.is_none() && (!can_ask || !asked) {
+                // a skipped placeholder without a default stays undefined
+                let Some(default_value) = default_value else {
+                    return Ok(None);
                 };
-                interactive::variable(slot, Some(&default_value))
+                log_default_value(&slot.var_name, &default_value);
+                (
+                    interactive::variable(slot, Some(&default_value))?,
+                    ValueSource::Default,
+                )
             } else {
-                interactive::variable(slot, provided_value.as_ref())
-            }
+                let source = if provided_value.is_none() {
+                    ValueSource::Prompt
+                } else {
+                    ValueSource::Provided
+                };
+                (
+                    interactive::variable(slot, provided_value.as_ref())?,
+                    source,
+                )
+            };
+            filled.borrow_mut().push(FilledPlaceholder {
+                slot: slot.clone(),
+                value: value.clone(),
+                source,
+                asked,
+            });
+            Ok(Some(value))
         })?;
 
         let placeholders_changed = conditionals
@@ -785,7 +1269,147 @@ fn fill_placeholders_and_merge_conditionals(
         }
     }
 
-    Ok(())
+    let missing = missing.into_inner();
+    if !missing.is_empty() {
+        bail!(ConversionError::MissingValues {
+            var_names: missing.iter().map(|slot| slot.var_name.clone()).collect(),
+            description: inspect::describe_missing_values(missing),
+        });
+    }
+
+    Ok(filled.into_inner())
+}
+
+/// The value of a computed placeholder, from its Rhai expression or its Liquid template
+///
+/// It stays undefined, while a value it's derived from is undefined.
+fn compute_placeholder(
+    liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
+    slot: &TemplateSlots,
+    computed: &Computed,
+) -> Result<Option<String>> {
+    let error = |e: &dyn std::fmt::Display| {
+        anyhow!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!(
+                "computed placeholder `{}` can't be evaluated: {e}",
+                slot.var_name
+            ))
+            .bold()
+            .red()
+        )
+    };
+    match computed {
+        Computed::Rhai(expression) => {
+            match evaluate_script::<rhai::Dynamic>(liquid_object, expression) {
+                Ok(value) if value.is_array() => Ok(Some(
+                    value
+                        .cast::<rhai::Array>()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(LIST_SEP),
+                )),
+                Ok(value) => Ok(Some(value.to_string())),
+                Err(e) if matches!(*e, rhai::EvalAltResult::ErrorVariableNotFound(..)) => Ok(None),
+                Err(e) => Err(error(&e)),
+            }
+        }
+        Computed::Liquid(template) => {
+            render_placeholder_template(liquid_object, placeholder_engine, template)
+                .map_err(|e| error(&e))
+        }
+    }
+}
+
+/// The placeholder with its default rendered, if the default is a Liquid template
+///
+/// The default is dropped, while a value it uses is undefined.
+fn render_default<'a>(
+    liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
+    slot: &'a TemplateSlots,
+) -> Result<Cow<'a, TemplateSlots>> {
+    let VarInfo::String { entry } = &slot.var_info else {
+        return Ok(Cow::Borrowed(slot));
+    };
+    let Some(default) = entry
+        .default
+        .as_ref()
+        .filter(|default| project_variables::is_liquid_template(default))
+    else {
+        return Ok(Cow::Borrowed(slot));
+    };
+    let default =
+        render_placeholder_template(liquid_object, placeholder_engine, default).map_err(|e| {
+            anyhow!(
+                "{} {}",
+                emoji::ERROR,
+                style(format!(
+                    "default of placeholder `{}` can't be rendered: {e}",
+                    slot.var_name
+                ))
+                .bold()
+                .red()
+            )
+        })?;
+    Ok(Cow::Owned(TemplateSlots {
+        var_info: VarInfo::String {
+            entry: Box::new(StringEntry {
+                default,
+                ..(**entry).clone()
+            }),
+        },
+        ..slot.clone()
+    }))
+}
+
+/// Render `template` with the values known so far, `None` if it uses an undefined value
+fn render_placeholder_template(
+    liquid_object: &LiquidObjectResource,
+    placeholder_engine: &liquid::Parser,
+    template: &str,
+) -> Result<Option<String>, liquid::Error> {
+    let template = placeholder_engine.parse(template)?;
+    let object = liquid_object
+        .lock()
+        .map_err(|_| liquid::Error::with_msg(PoisonError.to_string()))?
+        .borrow()
+        .to_owned();
+    let object = TrackedObject::new(&object);
+    match template.render(&object) {
+        Ok(value) => Ok(Some(value)),
+        Err(_) if object.asked_for_undefined() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether the `when` condition of a placeholder is true, given the values known so far
+///
+/// A condition on a placeholder that was skipped itself, and so is undefined, is false.
+fn is_placeholder_asked(
+    liquid_object: &LiquidObjectResource,
+    slot: &TemplateSlots,
+) -> Result<bool> {
+    let Some(when) = &slot.when else {
+        return Ok(true);
+    };
+    match evaluate_script::<bool>(liquid_object, when) {
+        Ok(asked) => Ok(asked),
+        Err(e) if matches!(*e, rhai::EvalAltResult::ErrorVariableNotFound(..)) => Ok(false),
+        Err(e) => bail!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!(
+                "`when` of placeholder `{}` can't be evaluated: {e}",
+                slot.var_name
+            ))
+            .bold()
+            .red()
+        ),
+    }
 }
 
 fn check_cargo_generate_version(template_config: &Config) -> Result<(), anyhow::Error> {
@@ -818,15 +1442,23 @@ fn check_cargo_generate_version(template_config: &Config) -> Result<(), anyhow::
 #[cfg(test)]
 mod tests {
     use crate::{
-        auto_locate_template_dir, extract_toml_string,
-        project_variables::{StringEntry, StringKind, TemplateSlots, VarInfo},
-        resolve_template_dir, select_sub_template, tmp_dir,
+        auto_locate_template_dir,
+        events::ValueSource,
+        extract_toml_string, fill_and_review,
+        interactive::Review,
+        liquid_value_to_string,
+        project_variables::{Choice, StringEntry, StringKind, TemplateSlots, VarInfo},
+        render_placeholder_template, resolve_template_dir, select_sub_template, tmp_dir,
+        value_source_label, Config, FilledPlaceholder, GenerateArgs, IndexMap,
     };
     use anyhow::anyhow;
     use std::{
+        cell::RefCell,
+        collections::HashMap,
         fs,
         io::Write,
         path::{Path, PathBuf},
+        sync::{Arc, Mutex},
     };
     use tempfile::TempDir;
 
@@ -881,10 +1513,12 @@ mod tests {
         let actual = auto_locate_template_dir(tmp.path().to_path_buf(), &mut |slots| match &slots
             .var_info
         {
-            VarInfo::Bool { .. } | VarInfo::Array { .. } => anyhow::bail!("Wrong prompt type"),
+            VarInfo::Bool { .. } | VarInfo::Array { .. } | VarInfo::Number { .. } => {
+                anyhow::bail!("Wrong prompt type")
+            }
             VarInfo::String { entry } => {
                 if let StringKind::Choices(choices) = entry.kind.clone() {
-                    let expected = vec!["sub1".to_string(), "sub2".to_string()];
+                    let expected: Vec<Choice> = vec!["sub1".into(), "sub2".into()];
                     assert_eq!(expected, choices);
                     Ok("sub2".to_string())
                 } else {
@@ -960,11 +1594,14 @@ mod tests {
         TemplateSlots {
             prompt: "Which sub-template should be expanded?".into(),
             var_name: "Template".into(),
+            when: None,
+            computed: None,
             var_info: VarInfo::String {
                 entry: Box::new(StringEntry {
                     default: default.map(str::to_owned),
                     kind: StringKind::Choices(vec!["sub1".into(), "sub2".into()]),
                     regex: None,
+                    validate: None,
                 }),
             },
         }
@@ -1000,7 +1637,9 @@ mod tests {
         let actual = auto_locate_template_dir(tmp.path().to_path_buf(), &mut |slots| match &slots
             .var_info
         {
-            VarInfo::Bool { .. } | VarInfo::Array { .. } => anyhow::bail!("Wrong prompt type"),
+            VarInfo::Bool { .. } | VarInfo::Array { .. } | VarInfo::Number { .. } => {
+                anyhow::bail!("Wrong prompt type")
+            }
             VarInfo::String { entry } => {
                 if let StringKind::Choices(choices) = entry.kind.clone() {
                     let (expected, answer) = match prompt_num {
@@ -1013,7 +1652,7 @@ mod tests {
                     expected
                         .into_iter()
                         .zip(choices.iter())
-                        .for_each(|(a, b)| assert_eq!(a, b));
+                        .for_each(|(a, b)| assert_eq!(a, b.value));
                     Ok(answer.to_string())
                 } else {
                     anyhow::bail!("Missing choices")
@@ -1045,12 +1684,14 @@ mod tests {
         let actual = auto_locate_template_dir(tmp.path().to_path_buf(), &mut |slots| match &slots
             .var_info
         {
-            VarInfo::Bool { .. } | VarInfo::Array { .. } => anyhow::bail!("Wrong prompt type"),
+            VarInfo::Bool { .. } | VarInfo::Array { .. } | VarInfo::Number { .. } => {
+                anyhow::bail!("Wrong prompt type")
+            }
             VarInfo::String { entry } => {
                 if let StringKind::Choices(choices) = entry.kind.clone() {
-                    let expected = vec![
-                        Path::new("dir2").join("dir2_2").to_string(),
-                        "dir4".to_string(),
+                    let expected: Vec<Choice> = vec![
+                        Path::new("dir2").join("dir2_2").to_string().into(),
+                        "dir4".into(),
                     ];
                     assert_eq!(expected, choices);
                     Ok("dir4".to_string())
@@ -1125,4 +1766,102 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn reviewed_values_are_labelled_with_their_source() {
+        let user_value_sources = IndexMap::from([
+            ("license".to_string(), "cli"),
+            ("author".to_string(), "values file"),
+        ]);
+        let label = |var_name, source| value_source_label(&user_value_sources, var_name, source);
+        assert_eq!(label("license", ValueSource::Provided), "cli");
+        assert_eq!(label("author", ValueSource::Provided), "values file");
+        assert_eq!(label("gh_user", ValueSource::Provided), "config file");
+        assert_eq!(label("author", ValueSource::Prompt), "prompt");
+        assert_eq!(label("ci", ValueSource::Default), "default");
+    }
+
+    #[test]
+    fn answering_again_evaluates_the_conditionals_again() -> anyhow::Result<()> {
+        let mut config = Config::try_from(
+            indoc::indoc! {r#"
+                [placeholders]
+                use_db = { type = "bool", prompt = "Database?", default = false }
+
+                [conditional.'use_db'.placeholders]
+                db_url = { prompt = "Database URL?", default = "postgres://localhost" }
+            "#}
+            .to_string(),
+        )?;
+        let liquid_object = Arc::new(Mutex::new(RefCell::new(liquid::Object::new())));
+        let engine = liquid::ParserBuilder::with_stdlib().build()?;
+        let args = GenerateArgs {
+            silent: true,
+            ..GenerateArgs::default()
+        };
+        let names = |filled: &[FilledPlaceholder]| {
+            filled
+                .iter()
+                .map(|f| f.slot.var_name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut reviews = 0;
+        let filled = fill_and_review(
+            &mut config,
+            &liquid_object,
+            &engine,
+            &HashMap::new(),
+            &args,
+            |filled| {
+                reviews += 1;
+                if reviews == 1 {
+                    assert_eq!(names(filled), ["use_db"]);
+                    return Ok(Review::Answer(0));
+                }
+                Ok(Review::Generate)
+            },
+            |_| Ok(liquid_core::Value::scalar(true)),
+        )?;
+
+        assert_eq!(names(&filled), ["use_db", "db_url"]);
+        assert!(filled[0].source == ValueSource::Prompt);
+        assert_eq!(
+            liquid_object.lock().unwrap().borrow().get("db_url"),
+            Some(&liquid_core::Value::scalar("postgres://localhost"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn placeholder_templates_stay_undefined_while_a_value_is_undefined() {
+        let liquid_object = Arc::new(Mutex::new(RefCell::new(liquid::object!({
+            "host": "localhost",
+            "port-number": 8000,
+        }))));
+        let engine = liquid::ParserBuilder::with_stdlib().build().unwrap();
+        let render = |template| render_placeholder_template(&liquid_object, &engine, template);
+
+        assert_eq!(
+            render("{{ host }}:{{ port-number }}").unwrap().as_deref(),
+            Some("localhost:8000")
+        );
+        assert_eq!(render("{{ host }}:{{ port }}").unwrap(), None);
+        assert!(render("{{ host | truncate: 'many' }}").is_err());
+    }
+
+    #[test]
+    fn reviewed_values_are_shown_like_provided_values() {
+        assert_eq!(
+            liquid_value_to_string(&liquid_core::Value::array([
+                liquid_core::Value::scalar("serde"),
+                liquid_core::Value::scalar("tokio"),
+            ])),
+            "serde,tokio"
+        );
+        assert_eq!(
+            liquid_value_to_string(&liquid_core::Value::scalar(true)),
+            "true"
+        );
+    }
 }
@@ -0,0 +1,536 @@
+//! Implementation of `cargo generate lint`
+
+use anyhow::{bail, Result};
+use console::style;
+use fs_err as fs;
+use liquid::Parser;
+use liquid_core::Object;
+use log::{error, info, warn};
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env, fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    config::{find_unknown_keys, Config, TemplateSlotsTable, CONFIG_FILE_NAME},
+    emoji,
+    events::Events,
+    ignore_me::get_ignored,
+    include_exclude::{Matcher, ShouldInclude},
+    project_variables::{
+        is_liquid_template, try_into_template_slots, try_key_value_into_slot, ConversionError,
+        VarInfo,
+    },
+    template::{create_liquid_engine, load_partials, Partials},
+    LintArgs,
+};
+
+/// Check the template at `args.path` and report all findings, fails if any of them is an error
+pub fn lint(args: LintArgs) -> Result<()> {
+    let template_dir = match args.path {
+        Some(path) => path,
+        None => env::current_dir()?,
+    };
+    let template_dir = fs::canonicalize(template_dir)?;
+    let findings = lint_template(&template_dir)?;
+
+    for finding in &findings {
+        match finding.severity {
+            Severity::Error => error!("{finding}"),
+            Severity::Warning => warn!("{finding}"),
+        }
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    if errors > 0 {
+        bail!(
+            "{} {}",
+            emoji::ERROR,
+            style(format!(
+                "Template has {errors} error(s) and {warnings} warning(s)"
+            ))
+            .bold()
+            .red()
+        );
+    }
+
+    info!(
+        "{} {}",
+        emoji::SPARKLE,
+        style(format!("Template looks good, {warnings} warning(s)"))
+            .bold()
+            .green()
+    );
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    severity: Severity,
+    /// the file the finding is about, relative to the linted directory
+    location: String,
+    message: String,
+}
+
+impl Finding {
+    fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", style(&self.location).bold(), self.message)
+    }
+}
+
+fn lint_template(template_dir: &Path) -> Result<Vec<Finding>> {
+    let mut findings = vec![];
+
+    let config_path = template_dir.join(CONFIG_FILE_NAME);
+    let config = if config_path.exists() {
+        let contents = fs::read_to_string(&config_path)?;
+        match find_unknown_keys(&contents) {
+            Ok(unknown_keys) => findings.extend(
+                unknown_keys
+                    .iter()
+                    .map(|key| Finding::error(CONFIG_FILE_NAME, format!("unknown key {key}"))),
+            ),
+            Err(e) => {
+                findings.push(Finding::error(CONFIG_FILE_NAME, e.to_string()));
+                return Ok(findings);
+            }
+        }
+        match Config::try_from(contents) {
+            Ok(config) => config,
+            Err(e) => {
+                findings.push(Finding::error(CONFIG_FILE_NAME, e.to_string()));
+                return Ok(findings);
+            }
+        }
+    } else {
+        Config::default()
+    };
+
+    let placeholders = all_placeholders(&config);
+    let mut whens = vec![];
+    // computed values and liquid defaults
+    let mut expressions = vec![];
+    for (name, value) in &placeholders {
+        match try_key_value_into_slot(name, value) {
+            Ok(slot) => {
+                whens.extend(slot.when.map(|when| (name, when)));
+                expressions.extend(slot.computed.map(|c| c.expression().to_owned()));
+                if let VarInfo::String { entry } = slot.var_info {
+                    expressions.extend(entry.default.filter(|d| is_liquid_template(d)));
+                }
+            }
+            Err(e) => findings.push(Finding::error(CONFIG_FILE_NAME, e.to_string())),
+        }
+    }
+    if let Some(Err(e @ ConversionError::ComputedCycle { .. })) =
+        config.placeholders.as_ref().map(try_into_template_slots)
+    {
+        findings.push(Finding::error(CONFIG_FILE_NAME, e.to_string()));
+    }
+
+    let engine = rhai::Engine::new();
+    let conditions = config
+        .conditional
+        .iter()
+        .flatten()
+        .map(|(condition, _)| condition.as_str())
+        .collect::<Vec<_>>();
+    for condition in &conditions {
+        if let Err(e) = engine.compile_expression(condition) {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!("conditional `{condition}` is not a valid Rhai expression: {e}"),
+            ));
+        }
+    }
+    for (name, when) in &whens {
+        if let Err(e) = engine.compile_expression(when) {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!("`when` of placeholder `{name}` is not a valid Rhai expression: {e}"),
+            ));
+        }
+    }
+
+    let hook_files = config.get_hook_files();
+    for hook in &hook_files {
+        if !template_dir.join(hook).is_file() {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!("hook file `{hook}` does not exist"),
+            ));
+        }
+    }
+    let filters = config.filters.clone().unwrap_or_default();
+    let mut filter_names = filters.keys().collect::<Vec<_>>();
+    filter_names.sort();
+    for name in filter_names {
+        if !template_dir.join(&filters[name]).is_file() {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!(
+                    "script `{}` of filter `{name}` does not exist",
+                    filters[name]
+                ),
+            ));
+        }
+    }
+    let script_files = hook_files
+        .into_iter()
+        .chain(config.get_filter_files())
+        .collect::<Vec<_>>();
+
+    let template_config = config.template.clone().unwrap_or_default();
+    for sub_template in template_config.sub_templates.iter().flatten() {
+        if !template_dir.join(sub_template).is_dir() {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!("sub-template folder `{sub_template}` does not exist"),
+            ));
+        }
+    }
+
+    if let Some(partials) = &template_config.partials {
+        if !template_dir.join(partials).is_dir() {
+            findings.push(Finding::error(
+                CONFIG_FILE_NAME,
+                format!("partials folder `{partials}` does not exist"),
+            ));
+        }
+    }
+
+    // everything placeholders may be referenced from
+    let mut references = conditions
+        .into_iter()
+        .chain(whens.iter().map(|(_, when)| when.as_str()))
+        .chain(expressions.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for script in &script_files {
+        references.push('\n');
+        references.push_str(&fs::read_to_string(template_dir.join(script)).unwrap_or_default());
+    }
+
+    let mut nested_templates = vec![];
+    findings.extend(lint_files(
+        template_dir,
+        &template_config,
+        &filters,
+        &script_files,
+        &mut references,
+        &mut nested_templates,
+    )?);
+
+    for (name, _) in &placeholders {
+        let used = Regex::new(&format!(r"(?:^|[^\w-]){}(?:$|[^\w-])", regex::escape(name)))?;
+        if !used.is_match(&references) {
+            findings.push(Finding::warning(
+                CONFIG_FILE_NAME,
+                format!("placeholder `{name}` is not used by any file or conditional"),
+            ));
+        }
+    }
+
+    for nested in nested_templates {
+        let prefix = nested.strip_prefix(template_dir)?.display().to_string();
+        findings.extend(lint_template(&nested)?.into_iter().map(|finding| Finding {
+            location: format!("{prefix}/{}", finding.location),
+            ..finding
+        }));
+    }
+
+    Ok(findings)
+}
+
+/// All placeholders, including the ones of the conditional blocks
+fn all_placeholders(config: &Config) -> Vec<(String, toml::Value)> {
+    config
+        .placeholders
+        .iter()
+        .chain(
+            config
+                .conditional
+                .iter()
+                .flat_map(|c| c.values())
+                .filter_map(|c| c.placeholders.as_ref()),
+        )
+        .flat_map(|TemplateSlotsTable(table)| table.clone())
+        .collect()
+}
+
+/// Parse all files of the template, that would be rendered, with the liquid engine.
+///
+/// The contents of the rendered files and all file names are appended to `references`,
+/// folders with their own configuration are collected in `nested_templates`.
+fn lint_files(
+    template_dir: &Path,
+    template_config: &crate::config::TemplateConfig,
+    filters: &HashMap<String, String>,
+    script_files: &[String],
+    references: &mut String,
+    nested_templates: &mut Vec<PathBuf>,
+) -> Result<Vec<Finding>> {
+    let mut findings = vec![];
+    let mut ignored = get_ignored(template_dir);
+    ignored.extend(
+        template_config
+            .ignore
+            .iter()
+            .flatten()
+            .map(|ignore| template_dir.join(ignore)),
+    );
+    let matcher = Matcher::new(&mut template_config.clone(), template_dir, script_files)?;
+    let partials_dir = template_config
+        .partials
+        .as_ref()
+        .map(|partials| template_dir.join(partials));
+    let partials = match &partials_dir {
+        Some(dir) if dir.is_dir() => {
+            load_partials(template_dir, template_config.partials.as_deref())?
+        }
+        _ => Partials::empty(),
+    };
+    let parser = create_parser(template_dir, partials, filters);
+
+    let mut entries = WalkDir::new(template_dir).sort_by_file_name().into_iter();
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        let path = entry.path();
+        if path == template_dir {
+            continue;
+        }
+        if entry.file_name() == ".git" || ignored.iter().any(|i| path.starts_with(i)) {
+            if entry.file_type().is_dir() {
+                entries.skip_current_dir();
+            }
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            if path.join(CONFIG_FILE_NAME).exists() {
+                nested_templates.push(path.to_path_buf());
+                entries.skip_current_dir();
+            }
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(template_dir)?;
+        let location = relative_path.display().to_string();
+        let is_partial = partials_dir
+            .as_ref()
+            .is_some_and(|dir| path.starts_with(dir));
+        references.push('\n');
+        references.push_str(&location);
+        if is_partial {
+            // partials are never part of the output, so only their contents matter
+        } else if let Err(e) = parser.parse(&location) {
+            findings.push(Finding::error(
+                &location,
+                format!(
+                    "invalid Liquid syntax in the file name: {}",
+                    e.to_string().trim()
+                ),
+            ));
+        }
+
+        if !is_partial
+            && !matches!(
+                matcher.should_include(relative_path),
+                ShouldInclude::Include
+            )
+        {
+            continue;
+        }
+        // binary files are never rendered
+        let Ok(contents) = fs::read_to_string(path) else {
+            continue;
+        };
+        if let Err(e) = parser.parse(&contents) {
+            findings.push(Finding::error(
+                &location,
+                format!("invalid Liquid syntax: {}", e.to_string().trim()),
+            ));
+        }
+        references.push('\n');
+        references.push_str(&contents);
+    }
+
+    Ok(findings)
+}
+
+fn create_parser(
+    template_dir: &Path,
+    partials: Partials,
+    filters: &HashMap<String, String>,
+) -> Parser {
+    create_liquid_engine(
+        template_dir.to_path_buf(),
+        Arc::new(Mutex::new(RefCell::new(Object::new()))),
+        false,
+        true,
+        Arc::new(Mutex::new(vec![])),
+        partials,
+        filters,
+        &Events::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::create_file, tmp_dir};
+
+    fn messages(findings: &[Finding], severity: Severity) -> Vec<String> {
+        findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .map(|f| format!("{}: {}", f.location, f.message))
+            .collect()
+    }
+
+    #[test]
+    fn a_valid_template_has_no_findings() -> Result<()> {
+        let tmp = tmp_dir()?;
+        create_file(
+            &tmp,
+            CONFIG_FILE_NAME,
+            indoc::indoc! {r#"
+                [placeholders]
+                license = { prompt = "License?", choices = ["MIT", "Apache-2.0"] }
+                ci = { type = "bool", prompt = "CI?" }
+                registry = { prompt = "Registry?", when = "ci" }
+                owner = { prompt = "Owner?" }
+                image = { prompt = "Image?", default = "ghcr.io/{{owner}}/{{project-name}}" }
+
+                [conditional.'ci']
+                placeholders = { runner = { prompt = "Runner?" } }
+
+                [hooks]
+                pre = ["pre.rhai"]
+            "#},
+        )?;
+        create_file(&tmp, "pre.rhai", r#"let runner = variable::get("runner");"#)?;
+        create_file(&tmp, "LICENSE-{{license}}", "")?;
+        create_file(
+            &tmp,
+            "Cargo.toml",
+            "name = \"{{project-name}}\"\npublish = [\"{{registry}}\"]\n# {{image}}",
+        )?;
+
+        assert_eq!(lint_template(tmp.path())?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn placeholders_used_in_partials_and_filters_count_as_used() -> Result<()> {
+        let tmp = tmp_dir()?;
+        create_file(
+            &tmp,
+            CONFIG_FILE_NAME,
+            indoc::indoc! {r#"
+                [template]
+                partials = "_partials"
+
+                [placeholders]
+                license = { prompt = "License?", choices = ["MIT", "Apache-2.0"] }
+                year = { prompt = "Year?" }
+
+                [filters]
+                copyright = "filters/copyright.rhai"
+            "#},
+        )?;
+        create_file(&tmp, "_partials/header.rs", "// {{license}}")?;
+        create_file(&tmp, "_partials/{% broken", "")?;
+        create_file(&tmp, "filters/copyright.rhai", r#"variable::get("year")"#)?;
+        create_file(
+            &tmp,
+            "src/main.rs",
+            "{% include 'header.rs' %} {{ crate_name | copyright }}",
+        )?;
+
+        assert_eq!(lint_template(tmp.path())?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_all_kinds_of_mistakes() -> Result<()> {
+        let tmp = tmp_dir()?;
+        create_file(
+            &tmp,
+            CONFIG_FILE_NAME,
+            indoc::indoc! {r#"
+                [template]
+                exlude = ["raw.txt"]
+
+                [placeholders]
+                unused = { prompt = "Unused?" }
+                broken = { type = "bool", prompt = "Broken?", regex = ".*" }
+                dangling = { prompt = "Dangling?", when = "unused &&" }
+
+                [conditional.'broken ==']
+                ignore = ["x"]
+
+                [hooks]
+                post = ["missing.rhai"]
+
+                [filters]
+                shout = "filters/shout.rhai"
+            "#},
+        )?;
+        create_file(&tmp, "main.rs", "{{ broken ")?;
+        create_file(&tmp, "sub/cargo-generate.toml", "[hook]\n")?;
+
+        let findings = lint_template(tmp.path())?;
+        let errors = messages(&findings, Severity::Error);
+        assert_eq!(errors.len(), 8, "{errors:#?}");
+        assert!(errors[0].contains("unknown key `template.exlude`"));
+        assert!(errors[1].contains("bool type does not support `regex` field"));
+        assert!(errors[2].contains("conditional `broken ==` is not a valid Rhai expression"));
+        assert!(
+            errors[3].contains("`when` of placeholder `dangling` is not a valid Rhai expression")
+        );
+        assert!(errors[4].contains("hook file `missing.rhai` does not exist"));
+        assert!(errors[5].contains("script `filters/shout.rhai` of filter `shout` does not exist"));
+        assert!(errors[6].starts_with("main.rs: invalid Liquid syntax"));
+        assert!(errors[7].starts_with("sub/cargo-generate.toml: unknown key `hook`"));
+        assert_eq!(
+            messages(&findings, Severity::Warning),
+            vec![
+                "cargo-generate.toml: placeholder `dangling` is not used by any file or conditional"
+            ]
+        );
+        Ok(())
+    }
+}
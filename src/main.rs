@@ -1,8 +1,23 @@
 use anyhow::Result;
-use cargo_generate::{generate, list_favorites, Cli};
+use cargo_generate::{
+    cache, favorite, generate, inspect, lint, list_favorites, update, Cli, GenerateCommand,
+    InspectFormat, MessageFormat,
+};
 use clap::Parser;
 
 fn main() -> Result<()> {
+    let args = resolve_args();
+
+    // keep stdout clean for machine readable output
+    let log_target = match &args.command {
+        Some(GenerateCommand::Inspect(inspect_args))
+            if inspect_args.format == InspectFormat::Json =>
+        {
+            env_logger::Target::Stderr
+        }
+        None if args.message_format == MessageFormat::Json => env_logger::Target::Stderr,
+        _ => env_logger::Target::Stdout,
+    };
     env_logger::builder()
         .format(cargo_generate::log_formatter)
         .filter_level(log::LevelFilter::Info)
@@ -11,15 +26,21 @@ fn main() -> Result<()> {
         .format_target(false)
         .format_module_path(false)
         .format_level(false)
-        .target(env_logger::Target::Stdout)
+        .target(log_target)
         .init();
 
-    let args = resolve_args();
-
-    if args.list_favorites {
-        list_favorites(&args)?;
-    } else {
-        generate(args)?;
+    match args.command {
+        Some(GenerateCommand::Update(update_args)) => {
+            update(update_args)?;
+        }
+        Some(GenerateCommand::Inspect(inspect_args)) => inspect(inspect_args)?,
+        Some(GenerateCommand::Lint(lint_args)) => lint(lint_args)?,
+        Some(GenerateCommand::Cache(cache_args)) => cache(cache_args)?,
+        Some(GenerateCommand::Favorite(favorite_args)) => favorite(favorite_args)?,
+        None if args.list_favorites => list_favorites(&args)?,
+        None => {
+            generate(args)?;
+        }
     }
 
     Ok(())
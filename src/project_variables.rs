@@ -1,9 +1,9 @@
 use anyhow::Result;
 use indexmap::IndexMap;
-use liquid_core::model::map::Entry;
 use liquid_core::{Value, ValueView};
 use log::info;
 use regex::Regex;
+use serde::Serialize;
 use thiserror::Error;
 
 use crate::emoji;
@@ -13,18 +13,56 @@ use crate::{
     config::{Config, TemplateSlotsTable},
     interactive::LIST_SEP,
     template::LiquidObjectResource,
+    validators::Validator,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TemplateSlots {
     pub(crate) var_name: String,
     pub(crate) var_info: VarInfo,
     pub(crate) prompt: Prompt,
+    /// A Rhai expression, the placeholder is only asked for if it's true
+    pub(crate) when: Option<String>,
+    /// The expression the value is derived from, instead of asking for it
+    pub(crate) computed: Option<Computed>,
+}
+
+/// The expression of a computed placeholder, in the language it's written in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Computed {
+    /// A Rhai expression, like the ones of conditionals
+    Rhai(String),
+    /// A Liquid template
+    Liquid(String),
+}
+
+impl Computed {
+    pub fn expression(&self) -> &str {
+        match self {
+            Self::Rhai(expression) | Self::Liquid(expression) => expression,
+        }
+    }
+
+    /// The names, that might refer to other placeholders. Names of Liquid may contain a `-`.
+    fn names(&self) -> Vec<&str> {
+        let (identifiers, strings) = match self {
+            Self::Rhai(_) => (r"[A-Za-z_]\w*", r#""[^"]*"|`[^`]*`|'[^']*'"#),
+            Self::Liquid(_) => (r"[A-Za-z_][\w-]*", r#""[^"]*"|'[^']*'"#),
+        };
+        let identifiers = Regex::new(identifiers).unwrap();
+        // names are only matched outside of strings
+        Regex::new(strings)
+            .unwrap()
+            .split(self.expression())
+            .flat_map(|code| identifiers.find_iter(code).map(|name| name.as_str()))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Prompt {
-    pub(crate) _raw: String,
+    pub(crate) raw: String,
     pub(crate) styled: String,
     pub(crate) styled_with_default: String,
     pub(crate) with_default: String,
@@ -48,7 +86,7 @@ impl Prompt {
                 .unwrap_or_default()
         );
         Self {
-            _raw: prompt,
+            raw: prompt,
             styled,
             styled_with_default,
             with_default,
@@ -80,13 +118,183 @@ impl From<&String> for Prompt {
 pub enum VarInfo {
     Array { entry: Box<ArrayEntry> },
     Bool { default: Option<bool> },
+    Number { entry: Box<NumberEntry> },
     String { entry: Box<StringEntry> },
 }
 
+/// A list, either selected from `choices`, or of free-form items without them
 #[derive(Debug, Clone)]
 pub struct ArrayEntry {
     pub(crate) default: Option<Vec<String>>,
-    pub(crate) choices: Vec<String>,
+    pub(crate) choices: Option<Vec<Choice>>,
+    pub(crate) regex: Option<Regex>,
+    pub(crate) validate: Option<Validator>,
+    pub(crate) min_items: Option<usize>,
+    pub(crate) max_items: Option<usize>,
+}
+
+impl ArrayEntry {
+    /// Check a single item against the regex and the validator, the error completes "`item` ..."
+    pub(crate) fn check_item(&self, item: &str) -> Result<(), String> {
+        check_string(self.regex.as_ref(), self.validate, item)
+    }
+
+    /// Check all `items` and their number, the error completes "`items` ..."
+    pub(crate) fn check(&self, items: &[String]) -> Result<(), String> {
+        for item in items {
+            self.check_item(item)
+                .map_err(|reason| format!("has the item `{item}`, that {reason}"))?;
+        }
+        let count = |n: usize| format!("{n} item{}", if n == 1 { "" } else { "s" });
+        if let Some(min) = self.min_items.filter(|&min| items.len() < min) {
+            return Err(format!("must have at least {}", count(min)));
+        }
+        if let Some(max) = self.max_items.filter(|&max| items.len() > max) {
+            return Err(format!("must have at most {}", count(max)));
+        }
+        Ok(())
+    }
+}
+
+/// One of the values a placeholder can be chosen from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub(crate) value: String,
+    pub(crate) label: Option<String>,
+    pub(crate) help: Option<String>,
+}
+
+/// How the choice is shown in a selection, its label and help, or just its value
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = self.label.as_deref().unwrap_or(&self.value);
+        match &self.help {
+            Some(help) => write!(f, "{label} {}", style(format!("- {help}")).dim()),
+            None => f.write_str(label),
+        }
+    }
+}
+
+impl From<&str> for Choice {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<String> for Choice {
+    fn from(value: String) -> Self {
+        Self {
+            value,
+            label: None,
+            help: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NumberEntry {
+    pub(crate) default: Option<Number>,
+    pub(crate) kind: NumberKind,
+    pub(crate) min: Option<Number>,
+    pub(crate) max: Option<Number>,
+    pub(crate) step: Option<Number>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberKind {
+    Integer,
+    Float,
+}
+
+/// The value of a number placeholder, integers keep all of their digits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Number {
+    const fn as_f64(self) -> f64 {
+        match self {
+            Self::Integer(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+
+    /// The liquid value of the number, an integer or a float scalar
+    pub(crate) fn to_value(self) -> Value {
+        match self {
+            Self::Integer(i) => Value::scalar(i),
+            Self::Float(f) => Value::scalar(f),
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.partial_cmp(b),
+            _ => self.as_f64().partial_cmp(&other.as_f64()),
+        }
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(i) => i.fmt(f),
+            Self::Float(float) => float.fmt(f),
+        }
+    }
+}
+
+impl NumberEntry {
+    /// Parse `value` as a number of this kind, and check it against the bounds
+    pub(crate) fn parse(&self, value: &str) -> Result<Number, String> {
+        let value = value.trim();
+        let number = match self.kind {
+            NumberKind::Integer => value
+                .parse::<i64>()
+                .map(Number::Integer)
+                .map_err(|_| "must be a whole number".to_string())?,
+            NumberKind::Float => value
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .map(Number::Float)
+                .ok_or_else(|| "must be a number".to_string())?,
+        };
+        self.check(number)
+    }
+
+    /// Check `number` against the bounds
+    pub(crate) fn check(&self, number: Number) -> Result<Number, String> {
+        if let Some(min) = self.min.filter(|&min| number < min) {
+            return Err(format!("must be at least {min}"));
+        }
+        if let Some(max) = self.max.filter(|&max| number > max) {
+            return Err(format!("must be at most {max}"));
+        }
+        if let Some(step) = self.step {
+            let min = self.min.unwrap_or(match self.kind {
+                NumberKind::Integer => Number::Integer(0),
+                NumberKind::Float => Number::Float(0.0),
+            });
+            let off_step = match (number, min, step) {
+                (Number::Integer(number), Number::Integer(min), Number::Integer(step)) => {
+                    step != 0 && (i128::from(number) - i128::from(min)) % i128::from(step) != 0
+                }
+                _ => {
+                    let steps = (number.as_f64() - min.as_f64()) / step.as_f64();
+                    (steps - steps.round()).abs() > 1e-9
+                }
+            };
+            if off_step {
+                return Err(format!("must be {min} plus a multiple of {step}"));
+            }
+        }
+        Ok(number)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,14 +302,34 @@ pub struct StringEntry {
     pub(crate) default: Option<String>,
     pub(crate) kind: StringKind,
     pub(crate) regex: Option<Regex>,
+    pub(crate) validate: Option<Validator>,
+}
+
+impl StringEntry {
+    /// Check `value` against the regex and the validator, the error completes "`value` ..."
+    pub(crate) fn check(&self, value: &str) -> Result<(), String> {
+        check_string(self.regex.as_ref(), self.validate, value)
+    }
+}
+
+fn check_string(
+    regex: Option<&Regex>,
+    validate: Option<Validator>,
+    value: &str,
+) -> Result<(), String> {
+    if let Some(regex) = regex.filter(|regex| !regex.is_match(value)) {
+        return Err(format!("does not match the regex `{regex}`"));
+    }
+    validate.map_or(Ok(()), |validator| validator.check(value))
 }
 
 #[derive(Debug, Clone)]
 pub enum StringKind {
-    Choices(Vec<String>),
+    Choices(Vec<Choice>),
     String,
     Editor,
     Text,
+    Secret,
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -116,6 +344,8 @@ pub enum ConversionError {
     InvalidPlaceholderFormat { var_name: String },
     #[error("missing prompt question for `{var_name}`")]
     MissingPrompt { var_name: String },
+    #[error("choice of `{var_name}` should be a string, or a table with a string `value` and optionally a `label` and `help`")]
+    InvalidChoice { var_name: String },
     #[error("choices array empty for `{var_name}`")]
     EmptyChoices { var_name: String },
     #[error("default is `{default}`, but is not a valid value in choices array `{choices:?}` for `{var_name}`")]
@@ -125,17 +355,49 @@ pub enum ConversionError {
         choices: Vec<String>,
     },
     #[error(
-        "invalid type for variable `{var_name}`: `{value}` possible values are `bool`, `string`, `text` and `editor`"
+        "invalid type for variable `{var_name}`: `{value}` possible values are `bool`, `string`, `text`, `editor`, `secret`, `array`, `integer` and `float`"
     )]
     InvalidVariableType { var_name: String, value: String },
     #[error("{var_type} type does not support `choices` field")]
     UnsupportedChoices { var_type: String },
+    #[error("secret `{var_name}` can't have a default, it would be readable by everyone using the template")]
+    SecretWithDefault { var_name: String },
     #[error("bool type does not support `regex` field")]
     RegexOnBool { var_name: String },
+    #[error("{var_type} type does not support `regex` field")]
+    UnsupportedRegex { var_type: String },
+    #[error("{var_type} type does not support `validate` field")]
+    UnsupportedValidator { var_type: String },
+    #[error("unknown validator `{value}` for `{var_name}`, possible values are `semver`, `url`, `email`, `rust_ident`, `crate_name`, `spdx` and `relative_path`")]
+    InvalidValidator { var_name: String, value: String },
+    #[error("{field} of `{var_name}` {reason}")]
+    ValidationFailed {
+        var_name: String,
+        field: String,
+        reason: String,
+    },
+    #[error("`{parameter}` of `{var_name}` is only supported by integer and float placeholders")]
+    UnsupportedBound { var_name: String, parameter: String },
+    #[error("`{parameter}` of `{var_name}` is only supported by array placeholders")]
+    UnsupportedItemBound { var_name: String, parameter: String },
     #[error(
-        "variable `{var_name}` is missing default value in config file running in silent mode"
+        "`min_items` of `{var_name}` is {min_items}, more than its `max_items` of {max_items}"
     )]
-    MissingDefaultValueForPlaceholderVariable { var_name: String },
+    InvalidItemBounds {
+        var_name: String,
+        min_items: usize,
+        max_items: usize,
+    },
+    #[error("default of `{var_name}` {reason}")]
+    DefaultOutOfBounds { var_name: String, reason: String },
+    #[error(
+        "no value for {}, and no way to ask while not running interactively\n{description}",
+        .var_names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+    )]
+    MissingValues {
+        var_names: Vec<String>,
+        description: String,
+    },
     #[error("field `{field}` of variable `{var_name}` does not match configured regex")]
     RegexDoesntMatchField { var_name: String, field: String },
     #[error("regex of `{var_name}` is not a valid regex. {error}")]
@@ -144,6 +406,10 @@ pub enum ConversionError {
         regex: String,
         error: regex::Error,
     },
+    #[error("`computed` of `{var_name}` should be a table with either a `rhai` expression or a `liquid` template")]
+    InvalidComputed { var_name: String },
+    #[error("computed placeholders depend on each other: {}", .var_names.join(" -> "))]
+    ComputedCycle { var_names: Vec<String> },
     #[error("placeholder `{var_name}` is not valid as you can't override `project-name`, `crate_name`, `crate_type`, `authors` and `os-arch`")]
     InvalidPlaceholderName { var_name: String },
 }
@@ -153,6 +419,7 @@ enum SupportedVarValue {
     Bool(bool),
     String(String),
     Array(Vec<String>),
+    Number(Number),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -161,10 +428,13 @@ enum SupportedVarType {
     String,
     Editor,
     Text,
+    Secret,
     Array,
+    Integer,
+    Float,
 }
 
-const RESERVED_NAMES: [&str; 7] = [
+pub const RESERVED_NAMES: [&str; 7] = [
     "authors",
     "os-arch",
     "project-name",
@@ -174,7 +444,64 @@ const RESERVED_NAMES: [&str; 7] = [
     "is_init",
 ];
 
+/// Shown instead of the value of a secret placeholder
+pub const SECRET_MASK: &str = "********";
+
+impl TemplateSlots {
+    /// Is this a secret placeholder, whose value must never be shown?
+    pub fn is_secret(&self) -> bool {
+        matches!(&self.var_info, VarInfo::String { entry } if matches!(entry.kind, StringKind::Secret))
+    }
+}
+
+/// The names of all secret placeholders of `config`, including those of conditional blocks
+pub fn secret_names(config: &Config) -> Vec<String> {
+    let conditional_placeholders = config
+        .conditional
+        .iter()
+        .flatten()
+        .filter_map(|(_, conditional)| conditional.placeholders.as_ref());
+    config
+        .placeholders
+        .iter()
+        .chain(conditional_placeholders)
+        .flat_map(|placeholders| try_into_template_slots(placeholders).unwrap_or_default())
+        .map(|(_, slot)| slot)
+        .filter(TemplateSlots::is_secret)
+        .map(|slot| slot.var_name)
+        .collect()
+}
+
+/// Is `value` a Liquid template, rather than a plain value or expression?
+pub fn is_liquid_template(value: &str) -> bool {
+    value.contains("{{") || value.contains("{%")
+}
+
 pub fn show_project_variables_with_value(template_object: &LiquidObjectResource, config: &Config) {
+    show_variables_with_value(
+        template_object,
+        config,
+        |slot| slot.computed.is_none(),
+        "placeholder provided by cli argument",
+    );
+}
+
+/// Show the values of the computed placeholders, once all placeholders are filled
+pub fn show_computed_variables_with_value(template_object: &LiquidObjectResource, config: &Config) {
+    show_variables_with_value(
+        template_object,
+        config,
+        |slot| slot.computed.is_some(),
+        "computed placeholder",
+    );
+}
+
+fn show_variables_with_value(
+    template_object: &LiquidObjectResource,
+    config: &Config,
+    filter: impl Fn(&TemplateSlots) -> bool,
+    origin: &str,
+) {
     let template_slots = config
         .placeholders
         .as_ref()
@@ -184,19 +511,24 @@ pub fn show_project_variables_with_value(template_object: &LiquidObjectResource,
 
     template_slots
         .iter()
+        .filter(|(_, v)| filter(v))
         .filter(|(k, _)| template_object.lock().unwrap().borrow().contains_key(**k))
         .for_each(|(k, v)| {
             let name = v.var_name.as_str();
-            let value = template_object
-                .lock()
-                .unwrap()
-                .borrow()
-                .get(*k)
-                .unwrap()
-                .to_kstr()
-                .to_string();
+            let value = if v.is_secret() {
+                SECRET_MASK.to_string()
+            } else {
+                template_object
+                    .lock()
+                    .unwrap()
+                    .borrow()
+                    .get(*k)
+                    .unwrap()
+                    .to_kstr()
+                    .to_string()
+            };
             info!(
-                "{} {} (placeholder provided by cli argument)",
+                "{} {} ({origin})",
                 emoji::WRENCH,
                 style(format!("{name}: {value:?}")).bold(),
             )
@@ -204,10 +536,12 @@ pub fn show_project_variables_with_value(template_object: &LiquidObjectResource,
 }
 
 /// For each defined placeholder, try to add it with value as a variable to the template_object.
+///
+/// A placeholder is left undefined, if `value_provider` returns `None` for it.
 pub fn fill_project_variables(
     template_object: &LiquidObjectResource,
     config: &Config,
-    value_provider: impl Fn(&TemplateSlots) -> Result<Value>,
+    value_provider: impl Fn(&TemplateSlots) -> Result<Option<Value>>,
 ) -> Result<()> {
     let template_slots = config
         .placeholders
@@ -215,37 +549,97 @@ pub fn fill_project_variables(
         .map(try_into_template_slots)
         .unwrap_or_else(|| Ok(IndexMap::new()))?;
 
-    for (&key, slot) in template_slots.iter() {
-        match template_object
-            .lock()
-            .unwrap()
-            .borrow_mut()
-            .entry(key.to_string())
-        {
-            Entry::Occupied(_) => {
-                // we already have the value from the config file
-            }
-            Entry::Vacant(entry) => {
-                // we don't have the file from the config but we can ask for it
-                let value = value_provider(slot)?;
-                entry.insert(value);
-            }
+    // computed placeholders come last, when the values they are derived from are known
+    let asked = template_slots
+        .values()
+        .filter(|slot| slot.computed.is_none());
+    for slot in asked.chain(computed_in_dependency_order(&template_slots)?) {
+        let key = slot.var_name.as_str();
+        if template_object.lock().unwrap().borrow().contains_key(key) {
+            // we already have the value from the config file
+            continue;
+        }
+        // we don't have the value from the config but we can ask for it,
+        // without holding the lock, as `when` conditions read the values asked so far
+        if let Some(value) = value_provider(slot)? {
+            template_object
+                .lock()
+                .unwrap()
+                .borrow_mut()
+                .insert(key.to_string().into(), value);
         }
     }
     Ok(())
 }
 
-fn try_into_template_slots(
+pub fn try_into_template_slots(
     TemplateSlotsTable(table): &TemplateSlotsTable,
 ) -> Result<IndexMap<&str, TemplateSlots>, ConversionError> {
     let mut slots = IndexMap::with_capacity(table.len());
     for (key, values) in table.iter() {
         slots.insert(key.as_str(), try_key_value_into_slot(key, values)?);
     }
+    // reports dependency cycles
+    computed_in_dependency_order(&slots)?;
     Ok(slots)
 }
 
-fn try_key_value_into_slot(
+/// The computed placeholders, each one after the computed placeholders it is derived from
+fn computed_in_dependency_order<'a>(
+    slots: &'a IndexMap<&str, TemplateSlots>,
+) -> Result<Vec<&'a TemplateSlots>, ConversionError> {
+    // the placeholders named in the expression of each computed placeholder
+    let dependencies = slots
+        .values()
+        .filter_map(|slot| {
+            let names = slot
+                .computed
+                .as_ref()?
+                .names()
+                .into_iter()
+                .filter_map(|name| slots.get(name))
+                .filter(|dependency| dependency.computed.is_some())
+                .collect::<Vec<_>>();
+            Some((slot.var_name.as_str(), names))
+        })
+        .collect::<IndexMap<_, _>>();
+
+    fn visit<'a>(
+        name: &'a str,
+        slots: &'a IndexMap<&str, TemplateSlots>,
+        dependencies: &IndexMap<&'a str, Vec<&'a TemplateSlots>>,
+        path: &mut Vec<&'a str>,
+        ordered: &mut Vec<&'a TemplateSlots>,
+    ) -> Result<(), ConversionError> {
+        if ordered.iter().any(|done| done.var_name == name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|visiting| *visiting == name) {
+            return Err(ConversionError::ComputedCycle {
+                var_names: path[start..]
+                    .iter()
+                    .chain([&name])
+                    .map(|name| format!("`{name}`"))
+                    .collect(),
+            });
+        }
+        path.push(name);
+        for dependency in &dependencies[name] {
+            visit(&dependency.var_name, slots, dependencies, path, ordered)?;
+        }
+        path.pop();
+        ordered.push(&slots[name]);
+        Ok(())
+    }
+
+    let mut ordered: Vec<&TemplateSlots> = vec![];
+    for name in dependencies.keys() {
+        visit(name, slots, &dependencies, &mut vec![], &mut ordered)?;
+    }
+    Ok(ordered)
+}
+
+pub fn try_key_value_into_slot(
     key: &str,
     values: &toml::Value,
 ) -> Result<TemplateSlots, ConversionError> {
@@ -263,7 +657,13 @@ fn try_key_value_into_slot(
 
     let var_type = extract_type(key, table.get("type"))?;
     let regex = extract_regex(key, var_type, table.get("regex"))?;
-    let prompt = extract_prompt(key, table.get("prompt"))?;
+    let validate = extract_validator(key, var_type, table.get("validate"))?;
+    let computed = extract_computed(key, table.get("computed"))?;
+    // computed placeholders are never asked for, so they don't need a prompt
+    let prompt = match (&computed, table.get("prompt")) {
+        (Some(_), None) => key.to_string(),
+        (_, prompt) => extract_prompt(key, prompt)?,
+    };
     let choices = extract_choices(key, var_type, regex.as_ref(), table.get("choices"))?;
     let default_choice = extract_default(
         key,
@@ -273,7 +673,76 @@ fn try_key_value_into_slot(
         choices.as_ref(),
     )?;
 
+    if let Some(validator) = validate {
+        let choices = choices
+            .iter()
+            .flatten()
+            .map(|c| (format!("choice `{}`", c.value), &c.value));
+        let default = match &default_choice {
+            Some(SupportedVarValue::String(default)) if !is_liquid_template(default) => {
+                vec![("default".to_string(), default)]
+            }
+            Some(SupportedVarValue::Array(defaults)) => defaults
+                .iter()
+                .map(|item| (format!("default item `{item}`"), item))
+                .collect(),
+            _ => vec![],
+        };
+        for (field, value) in default.into_iter().chain(choices) {
+            validator
+                .check(value)
+                .map_err(|reason| ConversionError::ValidationFailed {
+                    var_name: key.into(),
+                    field,
+                    reason,
+                })?;
+        }
+    }
+
+    let [min, max, step] =
+        ["min", "max", "step"].map(|bound| extract_bound(key, var_type, bound, table.get(bound)));
+    let (min, max, step) = (min?, max?, step?);
+    let [min_items, max_items] = ["min_items", "max_items"]
+        .map(|bound| extract_item_bound(key, var_type, bound, table.get(bound)));
+    let (min_items, max_items) = (min_items?, max_items?);
+    if let (Some(min_items), Some(max_items)) = (min_items, max_items) {
+        if min_items > max_items {
+            return Err(ConversionError::InvalidItemBounds {
+                var_name: key.into(),
+                min_items,
+                max_items,
+            });
+        }
+    }
+
     let var_info = match var_type {
+        SupportedVarType::Integer | SupportedVarType::Float => {
+            let entry = NumberEntry {
+                default: None,
+                kind: if var_type == SupportedVarType::Integer {
+                    NumberKind::Integer
+                } else {
+                    NumberKind::Float
+                },
+                min,
+                max,
+                step,
+            };
+            let default = match default_choice {
+                Some(SupportedVarValue::Number(value)) => {
+                    Some(entry.check(value).map_err(|reason| {
+                        ConversionError::DefaultOutOfBounds {
+                            var_name: key.into(),
+                            reason,
+                        }
+                    })?)
+                }
+                _ => None,
+            };
+            VarInfo::Number {
+                entry: Box::new(NumberEntry { default, ..entry }),
+            }
+        }
         SupportedVarType::Bool => VarInfo::Bool {
             default: if let Some(SupportedVarValue::Bool(value)) = default_choice {
                 Some(value)
@@ -290,6 +759,7 @@ fn try_key_value_into_slot(
                 },
                 kind: choices.map_or(StringKind::String, StringKind::Choices),
                 regex,
+                validate,
             }),
         },
         SupportedVarType::Editor => VarInfo::String {
@@ -301,18 +771,42 @@ fn try_key_value_into_slot(
                 },
                 kind: StringKind::Editor,
                 regex,
+                validate,
             }),
         },
-        SupportedVarType::Array => VarInfo::Array {
-            entry: Box::new(ArrayEntry {
+        SupportedVarType::Secret => VarInfo::String {
+            entry: Box::new(StringEntry {
+                default: None,
+                kind: StringKind::Secret,
+                regex,
+                validate,
+            }),
+        },
+        SupportedVarType::Array => {
+            let entry = ArrayEntry {
                 default: if let Some(SupportedVarValue::Array(value)) = default_choice {
                     Some(value)
                 } else {
                     None
                 },
-                choices: choices.unwrap_or_default(),
-            }),
-        },
+                choices,
+                regex,
+                validate,
+                min_items,
+                max_items,
+            };
+            if let Some(default) = &entry.default {
+                entry
+                    .check(default)
+                    .map_err(|reason| ConversionError::DefaultOutOfBounds {
+                        var_name: key.into(),
+                        reason,
+                    })?;
+            }
+            VarInfo::Array {
+                entry: Box::new(entry),
+            }
+        }
         SupportedVarType::Text => VarInfo::String {
             entry: Box::new(StringEntry {
                 default: if let Some(SupportedVarValue::String(value)) = default_choice {
@@ -322,6 +816,7 @@ fn try_key_value_into_slot(
                 },
                 kind: StringKind::Text,
                 regex,
+                validate,
             }),
         },
     };
@@ -329,6 +824,8 @@ fn try_key_value_into_slot(
         var_name: key.to_string(),
         var_info,
         prompt: prompt.into(),
+        when: extract_string(key, "when", table.get("when"))?,
+        computed,
     })
 }
 
@@ -341,8 +838,17 @@ fn extract_regex(
         (SupportedVarType::Bool, Some(_)) => Err(ConversionError::RegexOnBool {
             var_name: var_name.into(),
         }),
+        (SupportedVarType::Integer | SupportedVarType::Float, Some(_)) => {
+            Err(ConversionError::UnsupportedRegex {
+                var_type: format!("{var_type:?}"),
+            })
+        }
         (
-            SupportedVarType::String | SupportedVarType::Editor | SupportedVarType::Text,
+            SupportedVarType::String
+            | SupportedVarType::Editor
+            | SupportedVarType::Text
+            | SupportedVarType::Secret
+            | SupportedVarType::Array,
             Some(toml::Value::String(value)),
         ) => match Regex::new(value) {
             Ok(regex) => Ok(Some(regex)),
@@ -356,6 +862,7 @@ fn extract_regex(
             SupportedVarType::String
             | SupportedVarType::Editor
             | SupportedVarType::Text
+            | SupportedVarType::Secret
             | SupportedVarType::Array,
             Some(_),
         ) => Err(ConversionError::WrongTypeParameter {
@@ -367,6 +874,100 @@ fn extract_regex(
     }
 }
 
+fn extract_validator(
+    var_name: &str,
+    var_type: SupportedVarType,
+    table_entry: Option<&toml::Value>,
+) -> Result<Option<Validator>, ConversionError> {
+    match (var_type, table_entry) {
+        (_, None) => Ok(None),
+        (
+            SupportedVarType::String
+            | SupportedVarType::Editor
+            | SupportedVarType::Text
+            | SupportedVarType::Secret
+            | SupportedVarType::Array,
+            Some(toml::Value::String(value)),
+        ) => value
+            .parse()
+            .map(Some)
+            .map_err(|()| ConversionError::InvalidValidator {
+                var_name: var_name.into(),
+                value: value.clone(),
+            }),
+        (
+            SupportedVarType::String
+            | SupportedVarType::Editor
+            | SupportedVarType::Text
+            | SupportedVarType::Secret
+            | SupportedVarType::Array,
+            Some(_),
+        ) => Err(ConversionError::WrongTypeParameter {
+            var_name: var_name.into(),
+            parameter: "validate".to_string(),
+            correct_type: "String".to_string(),
+        }),
+        (_, Some(_)) => Err(ConversionError::UnsupportedValidator {
+            var_type: format!("{var_type:?}"),
+        }),
+    }
+}
+
+fn extract_bound(
+    var_name: &str,
+    var_type: SupportedVarType,
+    bound: &str,
+    table_entry: Option<&toml::Value>,
+) -> Result<Option<Number>, ConversionError> {
+    match (table_entry, var_type) {
+        (None, _) => Ok(None),
+        (Some(toml::Value::Integer(value)), SupportedVarType::Integer) => {
+            Ok(Some(Number::Integer(*value)))
+        }
+        (Some(toml::Value::Integer(value)), SupportedVarType::Float) => {
+            Ok(Some(Number::Float(*value as f64)))
+        }
+        (Some(toml::Value::Float(value)), SupportedVarType::Float) => {
+            Ok(Some(Number::Float(*value)))
+        }
+        (Some(_), SupportedVarType::Integer | SupportedVarType::Float) => {
+            Err(ConversionError::WrongTypeParameter {
+                var_name: var_name.into(),
+                parameter: bound.into(),
+                correct_type: format!("{var_type:?}").to_lowercase(),
+            })
+        }
+        (Some(_), _) => Err(ConversionError::UnsupportedBound {
+            var_name: var_name.into(),
+            parameter: bound.into(),
+        }),
+    }
+}
+
+fn extract_item_bound(
+    var_name: &str,
+    var_type: SupportedVarType,
+    bound: &str,
+    table_entry: Option<&toml::Value>,
+) -> Result<Option<usize>, ConversionError> {
+    let wrong_type = || ConversionError::WrongTypeParameter {
+        var_name: var_name.into(),
+        parameter: bound.into(),
+        correct_type: "non-negative integer".into(),
+    };
+    match (table_entry, var_type) {
+        (None, _) => Ok(None),
+        (Some(toml::Value::Integer(value)), SupportedVarType::Array) => {
+            usize::try_from(*value).map(Some).map_err(|_| wrong_type())
+        }
+        (Some(_), SupportedVarType::Array) => Err(wrong_type()),
+        (Some(_), _) => Err(ConversionError::UnsupportedItemBound {
+            var_name: var_name.into(),
+            parameter: bound.into(),
+        }),
+    }
+}
+
 fn extract_type(
     var_name: &str,
     table_entry: Option<&toml::Value>,
@@ -376,8 +977,11 @@ fn extract_type(
         Some(toml::Value::String(value)) if value == "string" => Ok(SupportedVarType::String),
         Some(toml::Value::String(value)) if value == "editor" => Ok(SupportedVarType::Editor),
         Some(toml::Value::String(value)) if value == "text" => Ok(SupportedVarType::Text),
+        Some(toml::Value::String(value)) if value == "secret" => Ok(SupportedVarType::Secret),
         Some(toml::Value::String(value)) if value == "bool" => Ok(SupportedVarType::Bool),
         Some(toml::Value::String(value)) if value == "array" => Ok(SupportedVarType::Array),
+        Some(toml::Value::String(value)) if value == "integer" => Ok(SupportedVarType::Integer),
+        Some(toml::Value::String(value)) if value == "float" => Ok(SupportedVarType::Float),
         Some(toml::Value::String(value)) => Err(ConversionError::InvalidVariableType {
             var_name: var_name.into(),
             value: value.clone(),
@@ -390,6 +994,44 @@ fn extract_type(
     }
 }
 
+fn extract_string(
+    var_name: &str,
+    parameter: &str,
+    table_entry: Option<&toml::Value>,
+) -> Result<Option<String>, ConversionError> {
+    match table_entry {
+        Some(toml::Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(ConversionError::WrongTypeParameter {
+            var_name: var_name.into(),
+            parameter: parameter.into(),
+            correct_type: "String".into(),
+        }),
+        None => Ok(None),
+    }
+}
+
+fn extract_computed(
+    var_name: &str,
+    table_entry: Option<&toml::Value>,
+) -> Result<Option<Computed>, ConversionError> {
+    let Some(table_entry) = table_entry else {
+        return Ok(None);
+    };
+    let invalid = || ConversionError::InvalidComputed {
+        var_name: var_name.into(),
+    };
+    let table = table_entry.as_table().ok_or_else(invalid)?;
+    match (table.len(), table.get("rhai"), table.get("liquid")) {
+        (1, Some(toml::Value::String(expression)), None) => {
+            Ok(Some(Computed::Rhai(expression.clone())))
+        }
+        (1, None, Some(toml::Value::String(template))) => {
+            Ok(Some(Computed::Liquid(template.clone())))
+        }
+        _ => Err(invalid()),
+    }
+}
+
 fn extract_prompt(
     var_name: &str,
     table_entry: Option<&toml::Value>,
@@ -412,11 +1054,21 @@ fn extract_default(
     var_type: SupportedVarType,
     regex: Option<&Regex>,
     table_entry: Option<&toml::Value>,
-    choices: Option<&Vec<String>>,
+    choices: Option<&Vec<Choice>>,
 ) -> Result<Option<SupportedVarValue>, ConversionError> {
+    let values = || {
+        choices
+            .into_iter()
+            .flatten()
+            .map(|c| c.value.clone())
+            .collect()
+    };
     match (table_entry, choices, var_type) {
         // no default set
         (None, _, _) => Ok(None),
+        (Some(_), _, SupportedVarType::Secret) => Err(ConversionError::SecretWithDefault {
+            var_name: var_name.into(),
+        }),
         // default set without choices
         (Some(toml::Value::Boolean(value)), _, SupportedVarType::Bool) => {
             Ok(Some(SupportedVarValue::Bool(*value)))
@@ -426,7 +1078,7 @@ fn extract_default(
             None,
             SupportedVarType::String | SupportedVarType::Editor | SupportedVarType::Text,
         ) => {
-            if let Some(reg) = regex {
+            if let Some(reg) = regex.filter(|_| !is_liquid_template(value)) {
                 if !reg.is_match(value) {
                     return Err(ConversionError::RegexDoesntMatchField {
                         var_name: var_name.into(),
@@ -437,6 +1089,16 @@ fn extract_default(
             Ok(Some(SupportedVarValue::String(value.clone())))
         }
 
+        (Some(toml::Value::Integer(value)), _, SupportedVarType::Integer) => {
+            Ok(Some(SupportedVarValue::Number(Number::Integer(*value))))
+        }
+        (Some(toml::Value::Integer(value)), _, SupportedVarType::Float) => Ok(Some(
+            SupportedVarValue::Number(Number::Float(*value as f64)),
+        )),
+        (Some(toml::Value::Float(value)), _, SupportedVarType::Float) => {
+            Ok(Some(SupportedVarValue::Number(Number::Float(*value))))
+        }
+
         // default and choices set
         // No need to check bool because it always has a choices vec with two values
         (
@@ -444,11 +1106,14 @@ fn extract_default(
             Some(choices),
             SupportedVarType::String | SupportedVarType::Editor | SupportedVarType::Text,
         ) => {
-            if !choices.contains(value) {
+            // a liquid default is checked, once it's rendered
+            if is_liquid_template(value) {
+                Ok(Some(SupportedVarValue::String(value.clone())))
+            } else if !choices.iter().any(|c| c.value == *value) {
                 Err(ConversionError::InvalidDefault {
                     var_name: var_name.into(),
                     default: value.clone(),
-                    choices: choices.clone(),
+                    choices: values(),
                 })
             } else {
                 if let Some(reg) = regex {
@@ -462,19 +1127,42 @@ fn extract_default(
                 Ok(Some(SupportedVarValue::String(value.clone())))
             }
         }
+        (Some(toml::Value::Array(defaults)), None, SupportedVarType::Array) => defaults
+            .iter()
+            .map(|item| match item {
+                toml::Value::String(item) => match regex {
+                    Some(reg) if !reg.is_match(item) => {
+                        Err(ConversionError::RegexDoesntMatchField {
+                            var_name: var_name.into(),
+                            field: "default".to_string(),
+                        })
+                    }
+                    _ => Ok(item.clone()),
+                },
+                _ => Err(ConversionError::WrongTypeParameter {
+                    var_name: var_name.into(),
+                    parameter: "default".to_string(),
+                    correct_type: "array".to_string(),
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(|items| Some(SupportedVarValue::Array(items))),
         (Some(toml::Value::Array(defaults)), Some(choices), SupportedVarType::Array) => {
             let default_string_array: Vec<String> = defaults
                 .iter()
                 .filter(|f| !(f.is_table() && f.is_array()))
                 .map(|f| f.as_str().unwrap_or_default().to_string())
                 .collect();
-            if default_string_array.iter().all(|v| choices.contains(v)) {
+            if default_string_array
+                .iter()
+                .all(|v| choices.iter().any(|c| c.value == *v))
+            {
                 Ok(Some(SupportedVarValue::Array(default_string_array.clone())))
             } else {
                 Err(ConversionError::InvalidDefault {
                     var_name: var_name.into(),
                     default: default_string_array.join(LIST_SEP),
-                    choices: choices.clone(),
+                    choices: values(),
                 })
             }
         }
@@ -488,7 +1176,10 @@ fn extract_default(
                 SupportedVarType::String => "string".to_string(),
                 SupportedVarType::Editor => "editor".to_string(),
                 SupportedVarType::Text => "text".to_string(),
+                SupportedVarType::Secret => "secret".to_string(),
                 SupportedVarType::Array => "array".to_string(),
+                SupportedVarType::Integer => "integer".to_string(),
+                SupportedVarType::Float => "float".to_string(),
             },
         }),
     }
@@ -499,47 +1190,44 @@ fn extract_choices(
     var_type: SupportedVarType,
     regex: Option<&Regex>,
     table_entry: Option<&toml::Value>,
-) -> Result<Option<Vec<String>>, ConversionError> {
+) -> Result<Option<Vec<Choice>>, ConversionError> {
     match (table_entry, var_type) {
         (
             None,
             SupportedVarType::Bool
             | SupportedVarType::Editor
             | SupportedVarType::Text
+            | SupportedVarType::Secret
             | SupportedVarType::Array,
         ) => Ok(None),
-        (Some(_), SupportedVarType::Bool | SupportedVarType::Editor | SupportedVarType::Text) => {
-            Err(ConversionError::UnsupportedChoices {
-                var_type: format!("{var_type:?}"),
-            })
-        }
+        (None, SupportedVarType::Integer | SupportedVarType::Float) => Ok(None),
+        (
+            Some(_),
+            SupportedVarType::Bool
+            | SupportedVarType::Editor
+            | SupportedVarType::Text
+            | SupportedVarType::Secret
+            | SupportedVarType::Integer
+            | SupportedVarType::Float,
+        ) => Err(ConversionError::UnsupportedChoices {
+            var_type: format!("{var_type:?}"),
+        }),
         (Some(toml::Value::Array(arr)), SupportedVarType::String) if arr.is_empty() => {
             Err(ConversionError::EmptyChoices {
                 var_name: var_name.into(),
             })
         }
         (Some(toml::Value::Array(arr)), SupportedVarType::Array) => {
-            let converted = arr
-                .iter()
-                .map(|entry| match entry {
-                    toml::Value::String(s) => Ok(s.clone()),
-                    _ => Err(()),
-                })
-                .collect::<Vec<_>>();
-            if converted.iter().any(|v| v.is_err()) {
-                return Err(ConversionError::WrongTypeParameter {
-                    var_name: var_name.into(),
-                    parameter: "choices".to_string(),
-                    correct_type: "String Array".to_string(),
-                });
+            let choices = extract_choice_list(var_name, arr)?;
+            if let Some(reg) = regex {
+                if choices.iter().any(|c| !reg.is_match(&c.value)) {
+                    return Err(ConversionError::RegexDoesntMatchField {
+                        var_name: var_name.into(),
+                        field: "choices".to_string(),
+                    });
+                }
             }
-
-            let strings = converted
-                .iter()
-                .cloned()
-                .map(|v| v.unwrap())
-                .collect::<Vec<_>>();
-            Ok(Some(strings))
+            Ok(Some(choices))
         }
         (Some(_), SupportedVarType::Array) => Err(ConversionError::WrongTypeParameter {
             var_name: var_name.into(),
@@ -547,30 +1235,10 @@ fn extract_choices(
             correct_type: "String Array".to_string(),
         }),
         (Some(toml::Value::Array(arr)), SupportedVarType::String) => {
-            // Checks if very entry in the array is a String
-            let converted = arr
-                .iter()
-                .map(|entry| match entry {
-                    toml::Value::String(s) => Ok(s.clone()),
-                    _ => Err(()),
-                })
-                .collect::<Vec<_>>();
-            if converted.iter().any(|v| v.is_err()) {
-                return Err(ConversionError::WrongTypeParameter {
-                    var_name: var_name.into(),
-                    parameter: "choices".to_string(),
-                    correct_type: "String Array".to_string(),
-                });
-            }
-
-            let strings = converted
-                .iter()
-                .cloned()
-                .map(|v| v.unwrap())
-                .collect::<Vec<_>>();
+            let choices = extract_choice_list(var_name, arr)?;
             // check if regex matches every choice
             if let Some(reg) = regex {
-                if strings.iter().any(|v| !reg.is_match(v)) {
+                if choices.iter().any(|c| !reg.is_match(&c.value)) {
                     return Err(ConversionError::RegexDoesntMatchField {
                         var_name: var_name.into(),
                         field: "choices".to_string(),
@@ -578,7 +1246,7 @@ fn extract_choices(
                 }
             }
 
-            Ok(Some(strings))
+            Ok(Some(choices))
         }
         (Some(_), SupportedVarType::String) => Err(ConversionError::WrongTypeParameter {
             var_name: var_name.into(),
@@ -589,11 +1257,429 @@ fn extract_choices(
     }
 }
 
+/// Each choice is either its plain value, or a table with the `value` and how it's shown
+fn extract_choice_list(
+    var_name: &str,
+    entries: &[toml::Value],
+) -> Result<Vec<Choice>, ConversionError> {
+    entries
+        .iter()
+        .map(|entry| match entry {
+            toml::Value::String(value) => Ok(Choice::from(value.as_str())),
+            toml::Value::Table(table) => {
+                let invalid = || ConversionError::InvalidChoice {
+                    var_name: var_name.into(),
+                };
+                if table
+                    .keys()
+                    .any(|key| !["value", "label", "help"].contains(&key.as_str()))
+                {
+                    return Err(invalid());
+                }
+                let field = |name: &str| match table.get(name) {
+                    None => Ok(None),
+                    Some(toml::Value::String(s)) => Ok(Some(s.clone())),
+                    Some(_) => Err(invalid()),
+                };
+                Ok(Choice {
+                    value: field("value")?.ok_or_else(invalid)?,
+                    label: field("label")?,
+                    help: field("help")?,
+                })
+            }
+            _ => Err(ConversionError::WrongTypeParameter {
+                var_name: var_name.into(),
+                parameter: "choices".to_string(),
+                correct_type: "String Array".to_string(),
+            }),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    fn number_slot(table: &str) -> Result<NumberEntry, ConversionError> {
+        let value =
+            toml::from_str::<toml::Value>(&format!("p = {{ prompt = \"p\", {table} }}")).unwrap();
+        try_key_value_into_slot("p", &value["p"]).map(|slot| match slot.var_info {
+            VarInfo::Number { entry } => *entry,
+            other => panic!("expected a number, got {other:?}"),
+        })
+    }
+
+    #[test]
+    fn integer_placeholders_check_their_bounds() {
+        let entry =
+            number_slot(r#"type = "integer", min = 1024, max = 65535, default = 8080"#).unwrap();
+
+        assert_eq!(entry.kind, NumberKind::Integer);
+        assert_eq!(entry.default, Some(Number::Integer(8080)));
+        assert_eq!(entry.parse(" 3000 "), Ok(Number::Integer(3000)));
+        assert_eq!(entry.parse("80"), Err("must be at least 1024".into()));
+        assert_eq!(entry.parse("70000"), Err("must be at most 65535".into()));
+        assert_eq!(entry.parse("3000.5"), Err("must be a whole number".into()));
+        assert_eq!(Number::Integer(3000).to_value(), Value::scalar(3000));
+    }
+
+    #[test]
+    fn large_integers_keep_all_of_their_digits() {
+        let entry = number_slot(r#"type = "integer", min = 9007199254740993, step = 2"#).unwrap();
+
+        assert_eq!(
+            entry.parse("9007199254740995"),
+            Ok(Number::Integer(9_007_199_254_740_995))
+        );
+        assert_eq!(
+            entry.parse("9007199254740994"),
+            Err("must be 9007199254740993 plus a multiple of 2".into())
+        );
+        assert_eq!(
+            entry.parse("9007199254740992"),
+            Err("must be at least 9007199254740993".into())
+        );
+    }
+
+    #[test]
+    fn float_placeholders_check_their_step() {
+        let entry = number_slot(r#"type = "float", min = 0.5, step = 0.25"#).unwrap();
+
+        assert_eq!(entry.parse("1.25"), Ok(Number::Float(1.25)));
+        assert_eq!(entry.parse("2"), Ok(Number::Float(2.0)));
+        assert_eq!(
+            entry.parse("1.3"),
+            Err("must be 0.5 plus a multiple of 0.25".into())
+        );
+        assert_eq!(Number::Float(1.25).to_value(), Value::scalar(1.25));
+    }
+
+    #[test]
+    fn number_placeholders_reject_invalid_config() {
+        assert_eq!(
+            number_slot(r#"type = "integer", max = 10, default = 11"#).unwrap_err(),
+            ConversionError::DefaultOutOfBounds {
+                var_name: "p".into(),
+                reason: "must be at most 10".into()
+            }
+        );
+        assert_eq!(
+            number_slot(r#"type = "integer", min = 0.5"#).unwrap_err(),
+            ConversionError::WrongTypeParameter {
+                var_name: "p".into(),
+                parameter: "min".into(),
+                correct_type: "integer".into()
+            }
+        );
+        assert_eq!(
+            number_slot(r#"type = "string", step = 2"#).unwrap_err(),
+            ConversionError::UnsupportedBound {
+                var_name: "p".into(),
+                parameter: "step".into()
+            }
+        );
+        assert_eq!(
+            number_slot(r#"type = "float", regex = ".*""#).unwrap_err(),
+            ConversionError::UnsupportedRegex {
+                var_type: "Float".into()
+            }
+        );
+    }
+
+    #[test]
+    fn validators_check_default_and_choices() {
+        let slot = |table: &str| {
+            let value =
+                toml::from_str::<toml::Value>(&format!("p = {{ prompt = \"p\", {table} }}"))
+                    .unwrap();
+            try_key_value_into_slot("p", &value["p"])
+        };
+
+        let VarInfo::String { entry } = slot(r#"validate = "semver", regex = '^0\.'"#)
+            .unwrap()
+            .var_info
+        else {
+            panic!("expected a string placeholder");
+        };
+        assert_eq!(entry.validate, Some(Validator::Semver));
+        assert_eq!(entry.check("0.1.0"), Ok(()));
+        assert_eq!(
+            entry.check("1.0.0"),
+            Err(r"does not match the regex `^0\.`".into())
+        );
+        assert!(entry.check("0.1").is_err());
+
+        assert_eq!(
+            slot(r#"validate = "crate_name", choices = ["ok", "not ok"]"#).unwrap_err(),
+            ConversionError::ValidationFailed {
+                var_name: "p".into(),
+                field: "choice `not ok`".into(),
+                reason: "must be a crate name (invalid character ` ` in package name: `not ok`, characters must be Unicode XID characters (numbers, `-`, `_`, or most letters))".into(),
+            }
+        );
+        assert!(matches!(
+            slot(r#"validate = "email", default = "nobody""#),
+            Err(ConversionError::ValidationFailed { field, .. }) if field == "default"
+        ));
+        assert_eq!(
+            slot(r#"validate = "isbn""#).unwrap_err(),
+            ConversionError::InvalidValidator {
+                var_name: "p".into(),
+                value: "isbn".into()
+            }
+        );
+        assert_eq!(
+            slot(r#"type = "bool", validate = "url""#).unwrap_err(),
+            ConversionError::UnsupportedValidator {
+                var_type: "Bool".into()
+            }
+        );
+    }
+
+    #[test]
+    fn choices_can_have_a_label_and_help() {
+        let slot = |table: &str| {
+            let value =
+                toml::from_str::<toml::Value>(&format!("p = {{ prompt = \"p\", {table} }}"))
+                    .unwrap();
+            try_key_value_into_slot("p", &value["p"])
+        };
+
+        let VarInfo::String { entry } = slot(indoc::indoc! {r#"
+            choices = [
+                { value = "tokio-multi-thread", label = "Tokio (multi-threaded)", help = "recommended" },
+                { value = "tokio-current-thread", label = "Tokio (current thread)" },
+                "none",
+            ], default = "none"
+        "#}.replace('\n', " ").as_str())
+        .unwrap()
+        .var_info
+        else {
+            panic!("expected a string placeholder");
+        };
+        let StringKind::Choices(choices) = entry.kind else {
+            panic!("expected choices");
+        };
+        let shown = choices
+            .iter()
+            .map(|c| console::strip_ansi_codes(&c.to_string()).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            shown,
+            [
+                "Tokio (multi-threaded) - recommended",
+                "Tokio (current thread)",
+                "none"
+            ]
+        );
+        assert_eq!(choices[0].value, "tokio-multi-thread");
+
+        assert!(matches!(
+            slot(r#"choices = [{ value = "a" }], default = "A""#),
+            Err(ConversionError::InvalidDefault { choices, .. }) if choices == ["a"]
+        ));
+        for invalid in [
+            r#"choices = [{ label = "A" }]"#,
+            r#"choices = [{ value = "a", description = "A" }]"#,
+            r#"type = "array", choices = [{ value = 1 }]"#,
+        ] {
+            assert_eq!(
+                slot(invalid).unwrap_err(),
+                ConversionError::InvalidChoice {
+                    var_name: "p".into()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn free_form_arrays_check_their_items() {
+        let slot = |table: &str| {
+            let value = toml::from_str::<toml::Value>(&format!(
+                "bins = {{ type = \"array\", prompt = \"Binaries?\", {table} }}"
+            ))
+            .unwrap();
+            try_key_value_into_slot("bins", &value["bins"])
+        };
+
+        let VarInfo::Array { entry } = slot(
+            r#"regex = '^[a-z]+$', validate = "rust_ident", min_items = 1, max_items = 2, default = ["cli"]"#,
+        )
+        .unwrap()
+        .var_info
+        else {
+            panic!("expected an array placeholder");
+        };
+        assert_eq!(entry.choices, None);
+        assert_eq!(entry.default, Some(vec!["cli".to_string()]));
+        assert_eq!(entry.check(&["cli".into(), "server".into()]), Ok(()));
+        assert_eq!(
+            entry.check(&["cli".into(), "Server".into()]),
+            Err("has the item `Server`, that does not match the regex `^[a-z]+$`".into())
+        );
+        assert_eq!(
+            entry.check(&["cli".into(), "fn".into()]),
+            Err("has the item `fn`, that must not be the rust keyword `fn`".into())
+        );
+        assert_eq!(entry.check(&[]), Err("must have at least 1 item".into()));
+        assert_eq!(
+            entry.check(&["a".into(), "b".into(), "c".into()]),
+            Err("must have at most 2 items".into())
+        );
+
+        assert_eq!(
+            slot(r#"min_items = 2, default = ["cli"]"#).unwrap_err(),
+            ConversionError::DefaultOutOfBounds {
+                var_name: "bins".into(),
+                reason: "must have at least 2 items".into()
+            }
+        );
+        assert_eq!(
+            slot("min_items = 3, max_items = 2").unwrap_err(),
+            ConversionError::InvalidItemBounds {
+                var_name: "bins".into(),
+                min_items: 3,
+                max_items: 2
+            }
+        );
+        assert_eq!(
+            slot("max_items = -1").unwrap_err(),
+            ConversionError::WrongTypeParameter {
+                var_name: "bins".into(),
+                parameter: "max_items".into(),
+                correct_type: "non-negative integer".into()
+            }
+        );
+        assert_eq!(
+            try_key_value_into_slot(
+                "name",
+                &toml::from_str::<toml::Value>(
+                    r#"prompt = "Name?"
+min_items = 1"#
+                )
+                .unwrap()
+            )
+            .unwrap_err(),
+            ConversionError::UnsupportedItemBound {
+                var_name: "name".into(),
+                parameter: "min_items".into()
+            }
+        );
+    }
+
+    #[test]
+    fn secret_names_include_those_of_conditionals() {
+        let config = Config::try_from(
+            indoc::indoc! {r#"
+                [placeholders]
+                token = { type = "secret", prompt = "Token?" }
+                region = { prompt = "Region?" }
+
+                [conditional.'use_db'.placeholders]
+                db_password = { type = "secret", prompt = "Password?" }
+            "#}
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(secret_names(&config), ["token", "db_password"]);
+    }
+
+    #[test]
+    fn secrets_have_no_default_and_no_choices() {
+        let slot = |table: &str| {
+            let value = toml::from_str::<toml::Value>(&format!(
+                "token = {{ type = \"secret\", prompt = \"Token?\", {table} }}"
+            ))
+            .unwrap();
+            try_key_value_into_slot("token", &value["token"])
+        };
+
+        let secret = slot("regex = '^ghp_'").unwrap();
+        assert!(secret.is_secret());
+        let VarInfo::String { entry } = secret.var_info else {
+            panic!("expected a string placeholder");
+        };
+        assert!(entry.check("ghp_123").is_ok());
+
+        assert_eq!(
+            slot(r#"default = "hunter2""#).unwrap_err(),
+            ConversionError::SecretWithDefault {
+                var_name: "token".into()
+            }
+        );
+        assert_eq!(
+            slot(r#"choices = ["a", "b"]"#).unwrap_err(),
+            ConversionError::UnsupportedChoices {
+                var_type: "Secret".into()
+            }
+        );
+    }
+
+    #[test]
+    fn computed_placeholders_are_ordered_by_their_dependencies() {
+        let table = |toml: &str| TemplateSlotsTable(toml::from_str(toml).unwrap());
+
+        let placeholders = table(indoc::indoc! {r#"
+            url = { computed = { rhai = "'https://' + host + ':' + port" } }
+            port = { computed = { rhai = "8000 + index" } }
+            index = { type = "integer", prompt = "Index?" }
+            host = { computed = { liquid = "{{ service-name | replace: 'port', 'url' }}" } }
+            service-name = { computed = { liquid = "{{ project-name }}-service" } }
+        "#});
+        let slots = try_into_template_slots(&placeholders).unwrap();
+        let ordered = computed_in_dependency_order(&slots)
+            .unwrap()
+            .into_iter()
+            .map(|slot| slot.var_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ordered, ["service-name", "host", "port", "url"]);
+        assert_eq!(slots["url"].prompt.raw, "url");
+
+        let placeholders = table(indoc::indoc! {r#"
+            a = { computed = { rhai = "b" } }
+            b = { computed = { rhai = "c + 1" } }
+            c = { computed = { liquid = "{{ a }}" } }
+        "#});
+        assert_eq!(
+            try_into_template_slots(&placeholders)
+                .unwrap_err()
+                .to_string(),
+            "computed placeholders depend on each other: `a` -> `b` -> `c` -> `a`"
+        );
+    }
+
+    #[test]
+    fn computed_placeholders_name_their_language() {
+        let slot = |toml: &str| {
+            try_key_value_into_slot("value", &toml::from_str::<toml::Value>(toml).unwrap())
+                .map(|slot| slot.computed)
+        };
+
+        assert_eq!(
+            slot(r#"computed = { rhai = "1 + 1" }"#).unwrap(),
+            Some(Computed::Rhai("1 + 1".into()))
+        );
+        assert_eq!(
+            slot(r#"computed = { liquid = "{{a}}" }"#).unwrap(),
+            Some(Computed::Liquid("{{a}}".into()))
+        );
+        for invalid in [
+            r#"computed = "{{a}}""#,
+            r#"computed = { rhai = "a", liquid = "{{a}}" }"#,
+            r#"computed = { python = "a" }"#,
+        ] {
+            assert_eq!(
+                slot(invalid).unwrap_err(),
+                ConversionError::InvalidComputed {
+                    var_name: "value".into()
+                },
+                "{invalid}"
+            );
+        }
+    }
+
     #[test]
     fn no_choices_boolean() {
         let result = extract_choices("foo", SupportedVarType::Bool, None, None);
@@ -780,7 +1866,7 @@ mod tests {
             ])),
         );
 
-        assert_eq!(result, Ok(Some(vec!["bar".to_string(), "zoo".to_string()])));
+        assert_eq!(result, Ok(Some(vec!["bar".into(), "zoo".into()])));
     }
 
     #[test]
@@ -820,10 +1906,7 @@ mod tests {
             ])),
         );
 
-        assert_eq!(
-            result,
-            Ok(Some(vec!["bar0".to_string(), "zoo".to_string()]))
-        );
+        assert_eq!(result, Ok(Some(vec!["bar0".into(), "zoo".into()])));
     }
 
     #[test]
@@ -859,10 +1942,8 @@ mod tests {
 
         assert_eq!(
             result,
-            Err(ConversionError::WrongTypeParameter {
+            Err(ConversionError::InvalidChoice {
                 var_name: "foo".into(),
-                parameter: "choices".into(),
-                correct_type: "String Array".into()
             })
         );
     }
@@ -876,7 +1957,7 @@ mod tests {
             Some(&toml::Value::Array(vec![toml::Value::String(
                 "true".into(),
             )])),
-            Some(&vec!["bar0".to_string()]),
+            Some(&vec!["bar0".into()]),
         );
 
         assert_eq!(
@@ -978,7 +2059,7 @@ mod tests {
             SupportedVarType::String,
             None,
             Some(&toml::Value::String("bar".to_string())),
-            Some(&vec!["zoo".to_string(), "far".to_string()]),
+            Some(&vec!["zoo".into(), "far".into()]),
         );
 
         assert_eq!(
@@ -998,7 +2079,7 @@ mod tests {
             SupportedVarType::String,
             None,
             Some(&toml::Value::String("bar".to_string())),
-            Some(&vec!["zoo".to_string(), "bar".to_string()]),
+            Some(&vec!["zoo".into(), "bar".into()]),
         );
 
         assert_eq!(result, Ok(Some(SupportedVarValue::String("bar".into()))))
@@ -1013,7 +2094,7 @@ mod tests {
             SupportedVarType::String,
             Some(&valid_ident),
             Some(&toml::Value::String("bar".to_string())),
-            Some(&vec!["zoo".to_string(), "bar".to_string()]),
+            Some(&vec!["zoo".into(), "bar".into()]),
         );
 
         assert_eq!(result, Ok(Some(SupportedVarValue::String("bar".into()))))
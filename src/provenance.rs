@@ -0,0 +1,222 @@
+//! Records where a generated project came from, so that it can be updated later on
+//! with `cargo generate update`.
+
+use anyhow::{Context, Result};
+use console::style;
+use fs_err as fs;
+use indexmap::IndexMap;
+use liquid::ValueView;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    emoji, git,
+    hooks::PoisonError,
+    template::{template_values_from_liquid_object, LiquidObjectResource},
+    user_parsed_input::{TemplateLocation, UserParsedInput},
+    TemplateRevision,
+};
+
+pub const PROVENANCE_FILE_NAME: &str = ".cargo-generate-provenance.toml";
+
+const PROVENANCE_FILE_HEADER: &str = "\
+# This file was created by cargo-generate and records the template this project was
+# generated from. It is used by `cargo generate update`, do not edit it by hand.
+";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Provenance {
+    pub template: TemplateProvenance,
+    pub project: ProjectProvenance,
+    /// the final values of all placeholders, in the same format as a `--values-file`
+    #[serde(default)]
+    pub values: IndexMap<String, toml::Value>,
+    /// the names of the secrets, that had a value, as their values are never recorded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct TemplateProvenance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subfolder: Option<String>,
+    /// the commit the template was expanded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub cargo_generate_version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ProjectProvenance {
+    pub name: String,
+    pub crate_type: String,
+    #[serde(default)]
+    pub init: bool,
+}
+
+impl Provenance {
+    pub fn new(
+        location: &TemplateLocation,
+        subfolder: Option<String>,
+        revision: &TemplateRevision,
+        user_parsed_input: &UserParsedInput,
+        liquid_object: &LiquidObjectResource,
+        secrets: &[String],
+    ) -> Result<Self> {
+        let template = match location {
+            TemplateLocation::Git(git) => TemplateProvenance {
+                git: Some(git.url().to_owned()),
+                branch: git.branch().map(str::to_owned),
+                tag: git.tag().map(str::to_owned),
+                revision: git.revision().map(str::to_owned),
+                ..TemplateProvenance::default()
+            },
+            TemplateLocation::Path(path) | TemplateLocation::Archive(path) => TemplateProvenance {
+                path: Some(path.canonicalize().unwrap_or_else(|_| path.clone())),
+                branch: revision.branch.clone(),
+                ..TemplateProvenance::default()
+            },
+        };
+
+        let (name, secrets_with_value) = {
+            let object = liquid_object.lock().map_err(|_| PoisonError)?;
+            let object = object.borrow();
+            let name = object
+                .get("project-name")
+                .map(|v| v.to_kstr().to_string())
+                .unwrap_or_default();
+            let secrets_with_value = secrets
+                .iter()
+                .filter(|secret| object.contains_key(secret.as_str()))
+                .cloned()
+                .collect::<Vec<_>>();
+            (name, secrets_with_value)
+        };
+
+        // the project was generated from the working tree, that the commit doesn't reproduce
+        let commit = match location {
+            TemplateLocation::Path(path)
+                if revision.commit.is_some() && git::has_uncommitted_changes(path) =>
+            {
+                warn!(
+                    "{}",
+                    style("The template has uncommitted changes, its commit is not recorded, so `cargo generate update` can't tell your changes from those of the template")
+                        .bold()
+                        .yellow()
+                );
+                None
+            }
+            _ => revision.commit.clone(),
+        };
+
+        Ok(Self {
+            template: TemplateProvenance {
+                subfolder,
+                commit,
+                cargo_generate_version: env!("CARGO_PKG_VERSION").to_owned(),
+                ..template
+            },
+            project: ProjectProvenance {
+                name,
+                crate_type: user_parsed_input.crate_type().to_string(),
+                init: user_parsed_input.init(),
+            },
+            values: template_values_from_liquid_object(liquid_object, secrets)?,
+            secrets: secrets_with_value,
+        })
+    }
+
+    /// Read the provenance file of the project located at `project_dir`
+    pub fn read(project_dir: &Path) -> Result<Self> {
+        let path = project_dir.join(PROVENANCE_FILE_NAME);
+        let contents = fs::read_to_string(&path).with_context(|| {
+            format!(
+                "{} {} `{}` {}",
+                emoji::ERROR,
+                style("No provenance found in").bold().red(),
+                style(project_dir.display()).bold(),
+                style("was the project generated with `--provenance`?")
+                    .bold()
+                    .red(),
+            )
+        })?;
+
+        toml::from_str(&contents).with_context(|| {
+            format!(
+                "{} {} `{}`",
+                emoji::ERROR,
+                style("Invalid provenance file").bold().red(),
+                style(path.display()).bold(),
+            )
+        })
+    }
+
+    /// Write the provenance file into the project located at `project_dir`
+    pub fn write(&self, project_dir: &Path) -> Result<()> {
+        let path = project_dir.join(PROVENANCE_FILE_NAME);
+        let contents = format!("{PROVENANCE_FILE_HEADER}\n{}", toml::to_string(self)?);
+        fs::write(&path, contents)?;
+
+        info!(
+            "{} {} `{}`",
+            emoji::WRENCH,
+            style("Template provenance recorded in").bold(),
+            style(path.display()).bold().yellow(),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provenance_roundtrips_through_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provenance = Provenance {
+            template: TemplateProvenance {
+                git: Some("https://github.com/o/r.git".into()),
+                tag: Some("v1.0.0".into()),
+                subfolder: Some("sub".into()),
+                commit: Some("0123456789abcdef".into()),
+                cargo_generate_version: "0.1.0".into(),
+                ..TemplateProvenance::default()
+            },
+            project: ProjectProvenance {
+                name: "my-project".into(),
+                crate_type: "bin".into(),
+                init: false,
+            },
+            values: IndexMap::from([
+                ("a".to_string(), toml::Value::Boolean(true)),
+                (
+                    "b".to_string(),
+                    toml::Value::Array(vec![toml::Value::String("x".into())]),
+                ),
+            ]),
+            secrets: vec!["token".into()],
+        };
+
+        provenance.write(tmp.path()).unwrap();
+        assert_eq!(Provenance::read(tmp.path()).unwrap(), provenance);
+    }
+
+    #[test]
+    fn reading_a_missing_provenance_explains_how_to_get_one() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = Provenance::read(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("--provenance"));
+    }
+}
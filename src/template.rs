@@ -1,12 +1,16 @@
 use anyhow::{bail, Context, Result};
 use console::style;
+use indexmap::IndexMap;
 use indicatif::{MultiProgress, ProgressBar};
 use liquid::model::KString;
+use liquid::partials::{EagerCompiler, InMemorySource};
 use liquid::{Parser, ParserBuilder};
-use liquid_core::{Object, Value};
+use liquid_core::model::{DisplayCow, KStringCow, ScalarCow, State};
+use liquid_core::{Object, ObjectView, Value, ValueView};
 use std::sync::{Arc, Mutex};
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -14,10 +18,12 @@ use walkdir::{DirEntry, WalkDir};
 
 use crate::config::TemplateConfig;
 use crate::emoji;
+use crate::events::Events;
 use crate::filenames::substitute_filename;
 use crate::hooks::PoisonError;
 use crate::include_exclude::*;
 use crate::progressbar::spinner;
+use crate::project_variables::RESERVED_NAMES;
 use crate::template_filters::*;
 use crate::template_variables::{
     get_authors, get_os_arch, Authors, CrateName, ProjectDir, ProjectName,
@@ -26,14 +32,33 @@ use crate::user_parsed_input::UserParsedInput;
 
 pub type LiquidObjectResource = Arc<Mutex<RefCell<Object>>>;
 
+/// The files of an expanded template, with how they were treated
+pub type FileOutcomes = Vec<(PathBuf, ShouldInclude)>;
+
+/// The liquid partials of a template, available to `{% include %}` and `{% render %}`
+pub type Partials = EagerCompiler<InMemorySource>;
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_liquid_engine(
     template_dir: PathBuf,
     liquid_object: LiquidObjectResource,
     allow_commands: bool,
     silent: bool,
     rhai_filter_files: Arc<Mutex<Vec<PathBuf>>>,
+    partials: Partials,
+    filters: &HashMap<String, String>,
+    events: &Events,
 ) -> Parser {
-    ParserBuilder::with_stdlib()
+    let rhai_filter = RhaiFilterParser::new(
+        template_dir,
+        liquid_object,
+        allow_commands,
+        silent,
+        rhai_filter_files,
+        events.clone(),
+    );
+    let builder = ParserBuilder::with_stdlib()
+        .partials(partials)
         .filter(KebabCaseFilterParser)
         .filter(LowerCamelCaseFilterParser)
         .filter(PascalCaseFilterParser)
@@ -42,15 +67,56 @@ pub fn create_liquid_engine(
         .filter(SnakeCaseFilterParser)
         .filter(TitleCaseFilterParser)
         .filter(UpperCamelCaseFilterParser)
-        .filter(RhaiFilterParser::new(
-            template_dir,
-            liquid_object,
-            allow_commands,
-            silent,
-            rhai_filter_files,
-        ))
+        .filter(rhai_filter.clone())
+        .filter(RhaiExprFilterParser::from(rhai_filter.clone()));
+    // declared last, so the template's own filters take precedence
+    filters
+        .iter()
+        .fold(builder, |builder, (name, script)| {
+            builder.filter(NamedRhaiFilterParser::new(
+                name.clone(),
+                script.clone(),
+                rhai_filter.clone(),
+            ))
+        })
         .build()
-        .expect("can't fail due to no partials support")
+        .expect("can't fail, as eager partials report their errors when rendered")
+}
+
+/// Load all files below the `partials` folder of the template.
+///
+/// Each partial is named by its path relative to that folder, e.g. `ci/steps.yml`.
+pub fn load_partials(template_dir: &Path, partials: Option<&str>) -> Result<Partials> {
+    let mut loaded = Partials::empty();
+    let Some(partials) = partials else {
+        return Ok(loaded);
+    };
+    let partials_dir = template_dir.join(partials);
+    if !partials_dir.is_dir() {
+        bail!(
+            "{} {} `{}`",
+            emoji::ERROR,
+            style("The partials folder does not exist:").bold().red(),
+            style(partials).bold().red(),
+        );
+    }
+    for entry in WalkDir::new(&partials_dir).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .strip_prefix(&partials_dir)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = fs::read_to_string(entry.path())
+            .with_context(|| format!("Failed to read the partial `{name}`"))?;
+        loaded.add(name, contents);
+    }
+    Ok(loaded)
 }
 
 /// create liquid object for the template, and pre-fill it with all known variables
@@ -107,12 +173,169 @@ pub fn set_project_name_variables(
     Ok(())
 }
 
+/// Collect all values of the liquid object, that are not provided by cargo-generate itself.
+/// These are the values of placeholders, and any value set by hooks.
+/// The values of `secrets` are left out, they must never be written to disk.
+pub fn template_values_from_liquid_object(
+    liquid_object: &LiquidObjectResource,
+    secrets: &[String],
+) -> Result<IndexMap<String, toml::Value>> {
+    let ref_cell = liquid_object.lock().map_err(|_| PoisonError)?;
+    let liquid_object = ref_cell.borrow();
+
+    Ok(liquid_object
+        .iter()
+        .filter(|(k, _)| !RESERVED_NAMES.contains(&k.as_str()) && k.as_str() != "username")
+        .filter(|(k, _)| !secrets.iter().any(|secret| secret == k.as_str()))
+        .filter_map(|(k, v)| liquid_to_toml_value(v).map(|v| (k.to_string(), v)))
+        .collect())
+}
+
+fn liquid_to_toml_value(value: &Value) -> Option<toml::Value> {
+    match value {
+        Value::Scalar(scalar) => scalar_to_toml_value(scalar),
+        Value::Array(items) => Some(toml::Value::Array(
+            items.iter().filter_map(liquid_to_toml_value).collect(),
+        )),
+        Value::Object(object) => Some(toml::Value::Table(
+            object
+                .iter()
+                .filter_map(|(k, v)| liquid_to_toml_value(v).map(|v| (k.to_string(), v)))
+                .collect(),
+        )),
+        Value::State(_) | Value::Nil => None,
+    }
+}
+
+/// A scalar of the same kind in TOML, dates become strings
+///
+/// The kind of a scalar is only known to its serialization, a string like `"42"` stays a string.
+pub fn scalar_to_toml_value(scalar: &ScalarCow<'_>) -> Option<toml::Value> {
+    match toml::Value::try_from(scalar).ok()? {
+        toml::Value::Datetime(datetime) => Some(toml::Value::String(datetime.to_string())),
+        value => Some(value),
+    }
+}
+
+/// Convert a provided value, tables become liquid objects
+pub fn toml_to_liquid_value(value: &toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::scalar(s.clone()),
+        toml::Value::Integer(i) => Value::scalar(*i),
+        toml::Value::Float(f) => Value::scalar(*f),
+        toml::Value::Boolean(b) => Value::scalar(*b),
+        toml::Value::Datetime(d) => Value::scalar(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.iter().map(toml_to_liquid_value).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.clone().into(), toml_to_liquid_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// The values of a liquid object, that remembers whether a template asked for an undefined one
+#[derive(Debug)]
+pub struct TrackedObject<'a> {
+    object: &'a Object,
+    asked_for_undefined: Cell<bool>,
+}
+
+impl<'a> TrackedObject<'a> {
+    pub const fn new(object: &'a Object) -> Self {
+        Self {
+            object,
+            asked_for_undefined: Cell::new(false),
+        }
+    }
+
+    pub const fn asked_for_undefined(&self) -> bool {
+        self.asked_for_undefined.get()
+    }
+
+    fn track<T>(&self, found: Option<T>) -> Option<T> {
+        if found.is_none() {
+            self.asked_for_undefined.set(true);
+        }
+        found
+    }
+}
+
+impl ValueView for TrackedObject<'_> {
+    fn as_debug(&self) -> &dyn std::fmt::Debug {
+        self
+    }
+
+    fn render(&self) -> DisplayCow<'_> {
+        self.object.render()
+    }
+
+    fn source(&self) -> DisplayCow<'_> {
+        self.object.source()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.object.type_name()
+    }
+
+    fn query_state(&self, state: State) -> bool {
+        self.object.query_state(state)
+    }
+
+    fn to_kstr(&self) -> KStringCow<'_> {
+        self.object.to_kstr()
+    }
+
+    fn to_value(&self) -> Value {
+        self.object.to_value()
+    }
+
+    fn as_object(&self) -> Option<&dyn ObjectView> {
+        Some(self)
+    }
+}
+
+impl ObjectView for TrackedObject<'_> {
+    fn as_value(&self) -> &dyn ValueView {
+        self
+    }
+
+    fn size(&self) -> i64 {
+        self.object.size()
+    }
+
+    fn keys<'k>(&'k self) -> Box<dyn Iterator<Item = KStringCow<'k>> + 'k> {
+        ObjectView::keys(self.object)
+    }
+
+    fn values<'k>(&'k self) -> Box<dyn Iterator<Item = &'k dyn ValueView> + 'k> {
+        ObjectView::values(self.object)
+    }
+
+    fn iter<'k>(&'k self) -> Box<dyn Iterator<Item = (KStringCow<'k>, &'k dyn ValueView)> + 'k> {
+        ObjectView::iter(self.object)
+    }
+
+    fn contains_key(&self, index: &str) -> bool {
+        self.track(self.object.contains_key(index).then_some(()))
+            .is_some()
+    }
+
+    fn get<'s>(&'s self, index: &str) -> Option<&'s dyn ValueView> {
+        self.track(ObjectView::get(self.object, index))
+    }
+}
+
 fn is_within_cargo_project(project_dir: &Path) -> bool {
     Path::new(project_dir)
         .ancestors()
         .any(|folder| folder.join("Cargo.toml").exists())
 }
 
+/// Walks the template and renders it in place.
+///
+/// For every file, its (possibly renamed) path and how it was treated is pushed to `outcomes`.
 #[allow(clippy::too_many_arguments)]
 pub fn walk_dir(
     template_config: &mut TemplateConfig,
@@ -123,6 +346,7 @@ pub fn walk_dir(
     rhai_filter_files: &Arc<Mutex<Vec<PathBuf>>>,
     mp: &mut MultiProgress,
     quiet: bool,
+    outcomes: &mut FileOutcomes,
 ) -> Result<()> {
     fn is_git_metadata(entry: &DirEntry) -> bool {
         entry
@@ -172,6 +396,9 @@ pub fn walk_dir(
             pb.finish_with_message(format!(
                 "Skipped: {filename_display} - used as Rhai filter!"
             ));
+            if entry.file_type().is_file() {
+                outcomes.push((relative_path.to_path_buf(), ShouldInclude::Ignore));
+            }
             continue;
         }
 
@@ -184,6 +411,7 @@ pub fn walk_dir(
                         Err(e) => {
                             files_with_errors
                                 .push((relative_path.display().to_string(), e.clone()));
+                            outcomes.push((relative_path.to_path_buf(), ShouldInclude::Exclude));
                         }
                         Ok(new_contents) => {
                             let new_filename =
@@ -211,6 +439,7 @@ pub fn walk_dir(
                             if filename != new_filename {
                                 fs::remove_file(filename)?;
                             }
+                            outcomes.push((relative_path.to_path_buf(), ShouldInclude::Include));
                             pb.inc(50);
                             pb.finish_with_message(format!("Done: {f}"));
                         }
@@ -229,6 +458,12 @@ pub fn walk_dir(
             }
             ShouldInclude::Exclude => {
                 let new_filename = substitute_filename(filename, &rhai_engine, liquid_object)?;
+                if entry.file_type().is_file() {
+                    outcomes.push((
+                        new_filename.strip_prefix(project_dir)?.to_path_buf(),
+                        ShouldInclude::Exclude,
+                    ));
+                }
                 let mut f = filename_display;
                 // Check if the file to exclude is in a templated path
                 // If it is, we need to copy it to the new location
@@ -251,6 +486,9 @@ pub fn walk_dir(
                 pb.finish_with_message(format!("Skipped: {f}"));
             }
             ShouldInclude::Ignore => {
+                if entry.file_type().is_file() {
+                    outcomes.push((relative_path.to_path_buf(), ShouldInclude::Ignore));
+                }
                 pb.finish_with_message(format!("Ignored: {filename_display}"));
             }
         }
@@ -7,16 +7,21 @@ use heck::{
     ToKebabCase, ToLowerCamelCase, ToPascalCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
     ToTitleCase, ToUpperCamelCase,
 };
-use liquid::model;
-use liquid_core::{parser::FilterArguments, Filter, ParseFilter, Runtime, Value, ValueView};
+use liquid_core::{
+    parser::{FilterArguments, ParameterReflection},
+    runtime::Expression,
+    Filter, ParseFilter, Runtime, Value, ValueView,
+};
 use liquid_derive::FilterReflection;
 use log::warn;
+use rhai::Dynamic;
 use std::{
     path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use crate::{
+    events::Events,
     hooks::{create_rhai_engine, PoisonError, RhaiHooksContext},
     template::LiquidObjectResource,
 };
@@ -68,7 +73,7 @@ create_case_filter!("title_case", TitleCase, |i: String| i.to_title_case());
 create_case_filter!("upper_camel_case", UpperCamelCase, |i: String| i
     .to_upper_camel_case());
 
-#[derive(Clone, FilterReflection)]
+#[derive(Debug, Clone, FilterReflection)]
 #[filter(
     name = "rhai",
     description = "Run Rhai script as a filter",
@@ -80,6 +85,7 @@ pub struct RhaiFilterParser {
     allow_commands: bool,
     silent: bool,
     rhai_filter_files: Arc<Mutex<Vec<PathBuf>>>,
+    events: Events,
 }
 
 impl RhaiFilterParser {
@@ -89,6 +95,7 @@ impl RhaiFilterParser {
         allow_commands: bool,
         silent: bool,
         rhai_filter_files: Arc<Mutex<Vec<PathBuf>>>,
+        events: Events,
     ) -> Self {
         Self {
             template_dir,
@@ -96,28 +103,33 @@ impl RhaiFilterParser {
             allow_commands,
             silent,
             rhai_filter_files,
+            events,
         }
     }
+
+    fn engine(&self) -> rhai::Engine {
+        create_rhai_engine(&RhaiHooksContext {
+            liquid_object: Arc::clone(&self.liquid_object),
+            allow_commands: self.allow_commands,
+            silent: self.silent,
+            working_directory: self.template_dir.clone(),
+            destination_directory: self.template_dir.clone(),
+            events: self.events.clone(),
+        })
+    }
 }
 
 impl ParseFilter for RhaiFilterParser {
     fn parse(&self, mut args: FilterArguments) -> liquid_core::Result<Box<dyn Filter>> {
+        let script = args.positional.next();
         if args.positional.next().is_some() {
             return Err(Error::with_msg("Invalid number of positional arguments")
-                .context("cause", "expected at most 0 positional arguments"));
-        }
-        if let Some(arg) = args.keyword.next() {
-            return Err(Error::with_msg(format!(
-                "Unexpected named argument `{}`",
-                arg.0
-            )));
+                .context("cause", "expected at most 1 positional argument"));
         }
         Ok(Box::new(RhaiFilter {
-            template_dir: self.template_dir.clone(),
-            liquid_object: self.liquid_object.clone(),
-            allow_commands: self.allow_commands,
-            silent: self.silent,
-            rhai_filter_files: self.rhai_filter_files.clone(),
+            parser: self.clone(),
+            script,
+            arguments: keyword_arguments(args),
         }))
     }
 
@@ -126,42 +138,50 @@ impl ParseFilter for RhaiFilterParser {
     }
 }
 
+/// Runs a Rhai script file.
+///
+/// `{{ "script.rhai" | rhai }}` runs the piped script, `{{ value | rhai: "script.rhai" }}`
+/// runs the given one with the piped value as `value`. Keyword arguments are available to the
+/// script as variables of the same name.
 #[derive(Debug, liquid_derive::Display_filter)]
 #[name = "rhai"]
 struct RhaiFilter {
-    template_dir: PathBuf,
-    liquid_object: LiquidObjectResource,
-    allow_commands: bool,
-    silent: bool,
-    rhai_filter_files: Arc<Mutex<Vec<PathBuf>>>,
+    parser: RhaiFilterParser,
+    script: Option<Expression>,
+    arguments: Vec<(String, Expression)>,
 }
 
 impl Filter for RhaiFilter {
     fn evaluate(
         &self,
         input: &dyn ValueView,
-        _runtime: &dyn Runtime,
+        runtime: &dyn Runtime,
     ) -> Result<Value, liquid_core::Error> {
-        // Unfortunately, liquid filters can't really cause liquid to fail. It just leaves the
-        // substitution as is - thus we resort to displaying warnings to the user.
-        let context = RhaiHooksContext {
-            liquid_object: Arc::clone(&self.liquid_object),
-            allow_commands: self.allow_commands,
-            silent: self.silent,
-            working_directory: self.template_dir.clone(),
-            destination_directory: self.template_dir.clone(),
+        let mut scope = rhai::Scope::new();
+        let script_name = match &self.script {
+            Some(script) => {
+                scope.push_dynamic("value", liquid_to_rhai(input));
+                script.evaluate(runtime)?.to_kstr().to_string()
+            }
+            None => input.to_kstr().to_string(),
         };
+        push_arguments(&mut scope, &self.arguments, runtime)?;
 
-        let engine = create_rhai_engine(&context);
-        let script_name = input.to_kstr().to_string();
-        let file_path = self.template_dir.join(&script_name);
-        self.rhai_filter_files
+        let file_path = self.parser.template_dir.join(&script_name);
+        self.parser
+            .rhai_filter_files
             .lock()
             .map_err(|_| liquid_core::Error::with_msg(PoisonError.to_string()))?
             .push(file_path.clone());
 
-        match engine.eval_file::<String>(file_path) {
-            Ok(r) => Ok(Value::Scalar(model::Scalar::from(r))),
+        // Unfortunately, liquid filters can't really cause liquid to fail. It just leaves the
+        // substitution as is - thus we resort to displaying warnings to the user.
+        match self
+            .parser
+            .engine()
+            .eval_file_with_scope::<Dynamic>(&mut scope, file_path)
+        {
+            Ok(r) => Ok(rhai_to_liquid(r)),
             Err(err) => {
                 warn!(
                     "{} {} {} {}",
@@ -177,3 +197,191 @@ impl Filter for RhaiFilter {
         }
     }
 }
+
+/// A filter declared in the `[filters]` of the template config, e.g.
+/// `semver_major = "filters/semver_major.rhai"`, that runs its script like
+/// `{{ value | rhai: "filters/semver_major.rhai" }}` does.
+#[derive(Clone)]
+pub struct NamedRhaiFilterParser {
+    name: String,
+    script: String,
+    parser: RhaiFilterParser,
+}
+
+impl NamedRhaiFilterParser {
+    pub const fn new(name: String, script: String, parser: RhaiFilterParser) -> Self {
+        Self {
+            name,
+            script,
+            parser,
+        }
+    }
+}
+
+impl liquid_core::FilterReflection for NamedRhaiFilterParser {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Run the Rhai script declared for this filter"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for NamedRhaiFilterParser {
+    fn parse(&self, mut args: FilterArguments) -> liquid_core::Result<Box<dyn Filter>> {
+        if args.positional.next().is_some() {
+            return Err(Error::with_msg("Invalid number of positional arguments")
+                .context("cause", "expected at most 0 positional arguments"));
+        }
+        Ok(Box::new(RhaiFilter {
+            parser: self.parser.clone(),
+            script: Some(Expression::Literal(Value::scalar(self.script.clone()))),
+            arguments: keyword_arguments(args),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn liquid_core::FilterReflection {
+        self
+    }
+}
+
+#[derive(Clone, FilterReflection)]
+#[filter(
+    name = "rhai_expr",
+    description = "Evaluate an inline Rhai expression as a filter",
+    parsed(RhaiExprFilter)
+)]
+pub struct RhaiExprFilterParser(RhaiFilterParser);
+
+impl From<RhaiFilterParser> for RhaiExprFilterParser {
+    fn from(parser: RhaiFilterParser) -> Self {
+        Self(parser)
+    }
+}
+
+impl ParseFilter for RhaiExprFilterParser {
+    fn parse(&self, mut args: FilterArguments) -> liquid_core::Result<Box<dyn Filter>> {
+        let (Some(expression), None) = (args.positional.next(), args.positional.next()) else {
+            return Err(Error::with_msg("Invalid number of positional arguments")
+                .context("cause", "expected exactly 1 positional argument"));
+        };
+        Ok(Box::new(RhaiExprFilter {
+            parser: self.0.clone(),
+            expression,
+            arguments: keyword_arguments(args),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn liquid_core::FilterReflection {
+        self
+    }
+}
+
+/// Evaluates an inline Rhai expression, e.g. `{{ name | rhai_expr: "value.len()" }}`.
+///
+/// The piped value is available as `value`, keyword arguments as variables of the same name.
+#[derive(Debug, liquid_derive::Display_filter)]
+#[name = "rhai_expr"]
+struct RhaiExprFilter {
+    parser: RhaiFilterParser,
+    expression: Expression,
+    arguments: Vec<(String, Expression)>,
+}
+
+impl Filter for RhaiExprFilter {
+    fn evaluate(
+        &self,
+        input: &dyn ValueView,
+        runtime: &dyn Runtime,
+    ) -> Result<Value, liquid_core::Error> {
+        let mut scope = rhai::Scope::new();
+        scope.push_dynamic("value", liquid_to_rhai(input));
+        push_arguments(&mut scope, &self.arguments, runtime)?;
+        let expression = self.expression.evaluate(runtime)?.to_kstr().to_string();
+
+        match self
+            .parser
+            .engine()
+            .eval_expression_with_scope::<Dynamic>(&mut scope, &expression)
+        {
+            Ok(r) => Ok(rhai_to_liquid(r)),
+            Err(err) => {
+                warn!(
+                    "{} {} {} {}",
+                    style("Filter expression").bold().yellow(),
+                    style(&expression).bold().red(),
+                    style("failed:").bold().yellow(),
+                    style(err.to_string()).bold().red(),
+                );
+                Err(liquid_core::Error::with_msg(format!(
+                    "Filter expression {expression} failed: {err}"
+                )))
+            }
+        }
+    }
+}
+
+fn keyword_arguments(args: FilterArguments) -> Vec<(String, Expression)> {
+    args.keyword
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+fn push_arguments(
+    scope: &mut rhai::Scope,
+    arguments: &[(String, Expression)],
+    runtime: &dyn Runtime,
+) -> liquid_core::Result<()> {
+    for (name, value) in arguments {
+        scope.push_dynamic(
+            name.as_str(),
+            liquid_to_rhai(value.evaluate(runtime)?.as_view()),
+        );
+    }
+    Ok(())
+}
+
+/// Convert a liquid value into the closest Rhai type, keeping numbers and booleans as such
+fn liquid_to_rhai(value: &dyn ValueView) -> Dynamic {
+    if let Some(scalar) = value.as_scalar() {
+        return match value.type_name() {
+            "boolean" => scalar.to_bool().map(Dynamic::from),
+            "whole number" => scalar.to_integer().map(Dynamic::from),
+            "fractional number" => scalar.to_float().map(Dynamic::from),
+            _ => None,
+        }
+        .unwrap_or_else(|| Dynamic::from(scalar.into_string().to_string()));
+    }
+    if let Some(array) = value.as_array() {
+        return Dynamic::from(array.values().map(liquid_to_rhai).collect::<rhai::Array>());
+    }
+    if let Some(object) = value.as_object() {
+        return Dynamic::from(
+            object
+                .iter()
+                .map(|(key, value)| (key.as_str().into(), liquid_to_rhai(value)))
+                .collect::<rhai::Map>(),
+        );
+    }
+    Dynamic::UNIT
+}
+
+/// Convert the result of a Rhai filter into a liquid value, anything but scalars is rendered
+/// as Rhai would print it
+fn rhai_to_liquid(value: Dynamic) -> Value {
+    value
+        .as_bool()
+        .map(Value::scalar)
+        .or_else(|_| value.as_int().map(Value::scalar))
+        .or_else(|_| value.as_float().map(Value::scalar))
+        .unwrap_or_else(|_| Value::scalar(value.to_string()))
+}
@@ -3,6 +3,8 @@
 
 use std::path::PathBuf;
 
+use crate::archive::is_archive;
+
 /// Options threaded into git clones for remote sources. Local sources
 /// ignore these. Used by `TemplateSource::into_template_location`.
 #[derive(Debug, Clone, Default)]
@@ -14,6 +16,7 @@ pub struct CloneOptions {
     pub gitconfig: Option<PathBuf>,
     pub force_git_init: bool,
     pub skip_submodules: bool,
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,11 +31,19 @@ pub enum GitHost {
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum TemplateSource {
-    HostShorthand { host: GitHost, owner_repo: String },
-    GithubOwnerRepo { owner: String, repo: String },
+    HostShorthand {
+        host: GitHost,
+        owner_repo: String,
+    },
+    GithubOwnerRepo {
+        owner: String,
+        repo: String,
+    },
     RemoteUrl(String),
     LocalRelative(PathBuf),
     LocalAbsolute(PathBuf),
+    /// A local archive file, given as a path or a `file://` URL
+    Archive(PathBuf),
     Favorite(Box<Self>),
 }
 
@@ -132,7 +143,9 @@ impl TemplateSource {
                     return Self::Favorite(Box::new(inner));
                 }
                 if let Some(p) = fav.path.as_ref() {
-                    let inner = if p.is_absolute() {
+                    let inner = if is_archive(p) {
+                        Self::Archive(cwd.join(p))
+                    } else if p.is_absolute() {
                         Self::LocalAbsolute(p.clone())
                     } else {
                         Self::LocalRelative(cwd.join(p))
@@ -151,25 +164,37 @@ impl TemplateSource {
                 owner_repo: rest.to_owned(),
             };
         }
-        // 3. Full URL
+        // 3. Archive file, also as a `file://` URL
+        if let Some(archive) = input
+            .starts_with("file://")
+            .then(|| url::Url::parse(input).ok()?.to_file_path().ok())
+            .flatten()
+            .filter(|p| is_archive(p))
+        {
+            return Self::Archive(archive);
+        }
+        let p = std::path::Path::new(input);
+        if is_archive(p) && (p.is_absolute() || cwd.join(p).is_file()) {
+            return Self::Archive(cwd.join(p));
+        }
+        // 4. Full URL
         if looks_like_url(input) {
             return Self::RemoteUrl(input.to_owned());
         }
-        // 4. Absolute path
-        let p = std::path::Path::new(input);
+        // 5. Absolute path
         if p.is_absolute() {
             return Self::LocalAbsolute(p.to_path_buf());
         }
-        // 5. Relative path that exists as a directory
+        // 6. Relative path that exists as a directory
         let resolved = cwd.join(p);
         if resolved.is_dir() {
             return Self::LocalRelative(resolved);
         }
-        // 6. Bare owner/repo → github
+        // 7. Bare owner/repo → github
         if let Some((owner, repo)) = parse_owner_repo(input) {
             return Self::GithubOwnerRepo { owner, repo };
         }
-        // 7. Catch-all — let git produce the clearer error.
+        // 8. Catch-all — let git produce the clearer error.
         Self::RemoteUrl(input.to_owned())
     }
 
@@ -190,7 +215,9 @@ impl TemplateSource {
             }
             Self::GithubOwnerRepo { owner, repo } => Cow::Owned(format!("{owner}/{repo}")),
             Self::RemoteUrl(url) => Cow::Borrowed(url.as_str()),
-            Self::LocalRelative(p) | Self::LocalAbsolute(p) => Cow::Owned(p.display().to_string()),
+            Self::LocalRelative(p) | Self::LocalAbsolute(p) | Self::Archive(p) => {
+                Cow::Owned(p.display().to_string())
+            }
             Self::Favorite(inner) => Cow::Owned(format!("favorite → {}", inner.display_label())),
         }
     }
@@ -218,6 +245,7 @@ impl TemplateSource {
                 TemplateLocation::Git(GitUserInput::with_url_and_clone_opts(url, clone_opts))
             }
             Self::LocalAbsolute(p) | Self::LocalRelative(p) => TemplateLocation::Path(p),
+            Self::Archive(p) => TemplateLocation::Archive(p),
             Self::Favorite(inner) => inner.into_template_location(clone_opts),
         }
     }
@@ -250,7 +278,7 @@ impl TemplateSource {
     pub fn is_remote(&self) -> bool {
         match self {
             Self::HostShorthand { .. } | Self::GithubOwnerRepo { .. } | Self::RemoteUrl(_) => true,
-            Self::LocalRelative(_) | Self::LocalAbsolute(_) => false,
+            Self::LocalRelative(_) | Self::LocalAbsolute(_) | Self::Archive(_) => false,
             Self::Favorite(inner) => inner.is_remote(),
         }
     }
@@ -328,6 +356,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn classify_existing_relative_archive() {
+        let cwd = tempfile::TempDir::new().unwrap();
+        std::fs::write(cwd.path().join("template.tar.gz"), "").unwrap();
+        let s = TemplateSource::classify("template.tar.gz", &empty_config(), cwd.path());
+        assert_eq!(
+            s,
+            TemplateSource::Archive(cwd.path().join("template.tar.gz"))
+        );
+    }
+
+    #[test]
+    fn classify_file_url_of_an_archive() {
+        let s = TemplateSource::classify(
+            "file:///srv/templates/web-1.2.zip",
+            &empty_config(),
+            Path::new("/tmp"),
+        );
+        assert_eq!(
+            s,
+            TemplateSource::Archive(PathBuf::from("/srv/templates/web-1.2.zip"))
+        );
+
+        let s = TemplateSource::classify(
+            "file:///tmp/my%20template.tar.gz",
+            &empty_config(),
+            Path::new("/tmp"),
+        );
+        assert_eq!(
+            s,
+            TemplateSource::Archive(PathBuf::from("/tmp/my template.tar.gz"))
+        );
+
+        let s = TemplateSource::classify(
+            "file:///srv/templates/web.git",
+            &empty_config(),
+            Path::new("/tmp"),
+        );
+        assert_eq!(
+            s,
+            TemplateSource::RemoteUrl("file:///srv/templates/web.git".to_owned())
+        );
+    }
+
     #[test]
     fn classify_owner_repo_when_no_local_dir() {
         let cwd = tempfile::TempDir::new().unwrap();
@@ -653,7 +725,7 @@ mod tests {
         };
         match s.into_template_location_for_test() {
             TemplateLocation::Git(g) => assert_eq!(g.url(), "https://github.com/o/r.git"),
-            TemplateLocation::Path(_) => panic!("expected Git"),
+            _ => panic!("expected Git"),
         }
     }
     #[test]
@@ -664,7 +736,7 @@ mod tests {
         };
         match s.into_template_location_for_test() {
             TemplateLocation::Git(g) => assert_eq!(g.url(), "https://github.com/o/r.git"),
-            TemplateLocation::Path(_) => panic!("expected Git"),
+            _ => panic!("expected Git"),
         }
     }
     #[test]
@@ -672,7 +744,7 @@ mod tests {
         let s = TemplateSource::RemoteUrl("ssh://git@x/y.git".to_owned());
         match s.into_template_location_for_test() {
             TemplateLocation::Git(g) => assert_eq!(g.url(), "ssh://git@x/y.git"),
-            TemplateLocation::Path(_) => panic!("expected Git"),
+            _ => panic!("expected Git"),
         }
     }
     #[test]
@@ -680,7 +752,15 @@ mod tests {
         let s = TemplateSource::LocalAbsolute(PathBuf::from("/abs"));
         match s.into_template_location_for_test() {
             TemplateLocation::Path(p) => assert_eq!(p, Path::new("/abs")),
-            TemplateLocation::Git(_) => panic!("expected Path"),
+            _ => panic!("expected Path"),
+        }
+    }
+    #[test]
+    fn into_template_location_maps_archive_to_archive() {
+        let s = TemplateSource::Archive(PathBuf::from("/abs/t.tar.zst"));
+        match s.into_template_location_for_test() {
+            TemplateLocation::Archive(p) => assert_eq!(p, Path::new("/abs/t.tar.zst")),
+            _ => panic!("expected Archive"),
         }
     }
 
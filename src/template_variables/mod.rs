@@ -6,14 +6,14 @@ mod project_dir;
 mod project_name;
 mod project_name_input;
 
-use crate::{emoji, GenerateArgs};
+use crate::{emoji, project_variables::SECRET_MASK, GenerateArgs};
 
 use anyhow::Result;
 use console::style;
 use indexmap::IndexMap;
 use log::info;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Display, fs, path::Path};
 use toml::Value;
 
@@ -25,41 +25,105 @@ pub use project_dir::ProjectDir;
 pub use project_name::ProjectName;
 pub use project_name_input::ProjectNameInput;
 
-fn load_env_template_values() -> Result<IndexMap<String, toml::Value>> {
+/// The values given by `CARGO_GENERATE_TEMPLATE_VALUES_FILE` and `CARGO_GENERATE_VALUE_*`
+pub fn load_env_template_values() -> Result<IndexMap<String, toml::Value>> {
+    let mut values = load_env_values_file()?;
+    values.extend(load_env_var_values());
+    Ok(values)
+}
+
+fn load_env_values_file() -> Result<IndexMap<String, toml::Value>> {
     //FIXME: use this variable to be in sync with args
-    let mut values = std::env::var("CARGO_GENERATE_TEMPLATE_VALUES_FILE")
+    std::env::var("CARGO_GENERATE_TEMPLATE_VALUES_FILE")
         .ok()
         .map_or(Ok(Default::default()), |path| {
             read_template_values_file(Path::new(&path))
-        })?;
-
-    values.extend(std::env::vars().filter_map(|(key, value)| {
-        key.strip_prefix("CARGO_GENERATE_VALUE_")
-            .map(|key| (key.to_lowercase(), Value::from(value)))
-    }));
+        })
+}
 
-    Ok(values)
+fn load_env_var_values() -> IndexMap<String, toml::Value> {
+    std::env::vars()
+        .filter_map(|(key, value)| {
+            key.strip_prefix("CARGO_GENERATE_VALUE_")
+                .map(|key| (key.to_lowercase(), Value::from(value)))
+        })
+        .collect()
 }
 
-fn load_args_template_values(args: &GenerateArgs) -> Result<IndexMap<String, toml::Value>> {
-    let mut values = args
-        .template_values_file
+fn load_args_values_file(args: &GenerateArgs) -> Result<IndexMap<String, toml::Value>> {
+    args.template_values_file
         .as_ref()
         .map(Path::new)
         .map_or(Ok(Default::default()), |path| {
             read_template_values_file(path)
-        })?;
+        })
+}
 
-    values.extend(read_template_values_from_definitions(&args.define)?);
+/// The values given by the environment, a values file and `--define`, and where each one
+/// came from: `env`, `values file` or `cli`.
+fn load_template_values_with_sources(
+    args: &GenerateArgs,
+) -> Result<IndexMap<String, (toml::Value, &'static str)>> {
+    let mut values = IndexMap::new();
+    // later ones override earlier ones
+    for (provided, source) in [
+        (load_env_values_file()?, "values file"),
+        (load_env_var_values(), "env"),
+        (load_args_values_file(args)?, "values file"),
+        (
+            read_template_values_from_definitions(&args.define)?
+                .into_iter()
+                .collect(),
+            "cli",
+        ),
+    ] {
+        values.extend(
+            provided
+                .into_iter()
+                .map(|(name, value)| (name, (value, source))),
+        );
+    }
     Ok(values)
 }
 
 pub fn load_env_and_args_template_values(
     args: &GenerateArgs,
 ) -> Result<IndexMap<String, toml::Value>> {
-    let mut template_variables = load_env_template_values()?;
-    template_variables.extend(load_args_template_values(args)?);
-    Ok(template_variables)
+    Ok(load_template_values_with_sources(args)?
+        .into_iter()
+        .map(|(name, (value, _))| (name, value))
+        .collect())
+}
+
+/// Where each of the values of [`load_env_and_args_template_values`] came from
+pub fn load_template_value_sources(args: &GenerateArgs) -> Result<IndexMap<String, &'static str>> {
+    Ok(load_template_values_with_sources(args)?
+        .into_iter()
+        .map(|(name, (_, source))| (name, source))
+        .collect())
+}
+
+/// Log the values given with `--define`, the values of `secrets` are masked
+pub fn log_template_values_from_definitions(
+    definitions: &[impl AsRef<str> + Display],
+    secrets: &[String],
+) -> Result<()> {
+    for definition in definitions {
+        let values = read_template_values_from_definitions(std::slice::from_ref(definition))?;
+        for (key, value) in values {
+            let value = if secrets.contains(&key) {
+                SECRET_MASK
+            } else {
+                value.as_str().unwrap_or_default()
+            };
+            info!(
+                "{} {} (value from CLI)",
+                emoji::WRENCH,
+                style(format!("{key}: {value:?}")).bold(),
+            );
+        }
+    }
+    Ok(())
 }
 
 fn read_template_values_file(path: &Path) -> Result<IndexMap<String, Value>> {
@@ -77,7 +141,7 @@ fn read_template_values_file(path: &Path) -> Result<IndexMap<String, Value>> {
     }
 }
 
-fn read_template_values_from_definitions(
+pub fn read_template_values_from_definitions(
     definitions: &[impl AsRef<str> + Display],
 ) -> Result<HashMap<String, toml::Value>> {
     let mut values = HashMap::with_capacity(definitions.len());
@@ -99,11 +163,6 @@ fn read_template_values_from_definitions(
                     let key = cap.get(1).unwrap().as_str().to_string();
                     let value = cap.get(2).map(|s| s.as_str()).unwrap_or("").to_owned();
 
-                    info!(
-                        "{} {} (value from CLI)",
-                        emoji::WRENCH,
-                        style(format!("{key}: {value:?}")).bold(),
-                    );
                     template_values.insert(key, Value::from(value));
                     Ok(template_values)
                 },
@@ -112,14 +171,74 @@ fn read_template_values_from_definitions(
     Ok(values)
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+/// Write `values` to `path`, in the format read by `--values-file`
+pub fn write_template_values_file(
+    path: &Path,
+    values: &IndexMap<String, toml::Value>,
+) -> Result<()> {
+    let contents = toml::to_string(&TemplateValuesToml {
+        values: values.clone(),
+    })?;
+    fs::write(path, contents).map_err(|e| {
+        anyhow::anyhow!(
+            "{} {} \"{}\": {}",
+            emoji::ERROR,
+            style("Values File Error:").bold().red(),
+            style(path.display()).bold().red(),
+            style(e).bold().red(),
+        )
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 struct TemplateValuesToml {
     pub(crate) values: IndexMap<String, toml::Value>,
 }
 
 #[cfg(test)]
 mod test {
-    use super::read_template_values_from_definitions;
+    use super::{
+        load_template_value_sources, read_template_values_file,
+        read_template_values_from_definitions, write_template_values_file,
+    };
+    use crate::GenerateArgs;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn written_values_are_read_back_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("values.toml");
+        let values = IndexMap::from([
+            ("name".to_string(), toml::Value::from("demo")),
+            ("ci".to_string(), toml::Value::from(true)),
+            ("port".to_string(), toml::Value::from(8080)),
+            (
+                "features".to_string(),
+                toml::Value::Array(vec!["serde".into(), "tokio".into()]),
+            ),
+        ]);
+
+        write_template_values_file(&path, &values).unwrap();
+
+        assert_eq!(read_template_values_file(&path).unwrap(), values);
+    }
+
+    #[test]
+    fn definitions_take_precedence_over_values_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("values.toml");
+        std::fs::write(&path, "[values]\nlicense = \"MIT\"\nMy-Key = \"file\"\n").unwrap();
+        let args = GenerateArgs {
+            template_values_file: Some(path.display().to_string()),
+            define: vec!["My-Key = cli".into()],
+            ..GenerateArgs::default()
+        };
+
+        let sources = load_template_value_sources(&args).unwrap();
+
+        assert_eq!(sources["license"], "values file");
+        assert_eq!(sources["My-Key"], "cli");
+    }
 
     #[test]
     fn names_must_start_with_word_char() {
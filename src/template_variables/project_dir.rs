@@ -82,8 +82,15 @@ impl TryFrom<(&ProjectNameInput, &UserParsedInput)> for ProjectDir {
 
 impl ProjectDir {
     pub fn create(&self) -> anyhow::Result<()> {
-        let path = self.0.as_path();
-        if path.exists() {
+        self.ensure_absent()?;
+        std::fs::create_dir(&self.0)?;
+
+        Ok(())
+    }
+
+    /// Fails if the directory already exists, as it would not be created then
+    pub fn ensure_absent(&self) -> anyhow::Result<()> {
+        if self.0.exists() {
             bail!(
                 "{} {}",
                 emoji::ERROR,
@@ -93,8 +100,6 @@ impl ProjectDir {
             );
         }
 
-        std::fs::create_dir(&self.0)?;
-
         Ok(())
     }
 }
@@ -0,0 +1,541 @@
+//! Implementation of `cargo generate update`
+//!
+//! The project is updated by rendering the template twice, once at the recorded revision
+//! (the "base") and once at the requested revision ("theirs"), with the recorded placeholder
+//! values. The differences between both renderings are then merged into the project ("ours"),
+//! very much like `git merge` would do it.
+
+use anyhow::{anyhow, Context, Result};
+use console::style;
+use fs_err as fs;
+use git2::{MergeFileInput, MergeFileOptions};
+use indexmap::IndexMap;
+use log::{info, warn};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    io::{stdin, IsTerminal},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    emoji,
+    git::tmp_dir,
+    interactive,
+    project_variables::{ConversionError, Prompt, StringKind},
+    provenance::{Provenance, PROVENANCE_FILE_NAME},
+    template_variables::{load_env_template_values, write_template_values_file},
+    GenerateArgs, TemplatePath, UpdateArgs, Vcs,
+};
+
+/// Update a project, that was generated with `--provenance`, to a newer revision of its template
+pub fn update(args: UpdateArgs) -> Result<PathBuf> {
+    let project_dir = match &args.project_dir {
+        Some(dir) => dir.clone(),
+        None => env::current_dir()?,
+    };
+    let project_dir = fs::canonicalize(project_dir)?;
+    let provenance = Provenance::read(&project_dir)?;
+
+    info!(
+        "{} {} `{}`{}",
+        emoji::WRENCH,
+        style("Updating project").bold(),
+        style(project_dir.display()).bold().yellow(),
+        style("...").bold()
+    );
+
+    let values_dir = tmp_dir()?;
+    let secrets = secret_values(&provenance)?;
+    let values_file = write_values_file(&provenance, secrets, values_dir.path())?;
+
+    let base_dir = tmp_dir()?;
+    let base = match provenance.template.commit.as_deref() {
+        Some(commit) => Some(render(
+            &args,
+            &provenance,
+            &values_file,
+            base_dir.path(),
+            TemplateRef::Revision(commit.to_owned()),
+            false,
+        )?),
+        None => {
+            warn!(
+                "{}",
+                style("The revision the project was generated from is unknown, all changes of the template are treated as conflicts").bold().red()
+            );
+            None
+        }
+    };
+
+    let theirs_dir = tmp_dir()?;
+    let theirs = render(
+        &args,
+        &provenance,
+        &values_file,
+        theirs_dir.path(),
+        TemplateRef::from_args_or_provenance(&args, &provenance),
+        true,
+    )?;
+
+    let summary = merge_into_project(base.as_deref(), &theirs, &project_dir)?;
+    update_provenance(&provenance, &theirs, &project_dir)?;
+    summary.report();
+
+    Ok(project_dir)
+}
+
+enum TemplateRef {
+    Branch(String),
+    Tag(String),
+    Revision(String),
+    Default,
+}
+
+impl TemplateRef {
+    fn from_args_or_provenance(args: &UpdateArgs, provenance: &Provenance) -> Self {
+        match (&args.branch, &args.tag, &args.revision) {
+            (Some(branch), _, _) => Self::Branch(branch.clone()),
+            (_, Some(tag), _) => Self::Tag(tag.clone()),
+            (_, _, Some(revision)) => Self::Revision(revision.clone()),
+            _ => provenance
+                .template
+                .branch
+                .clone()
+                .map_or(Self::Default, Self::Branch),
+        }
+    }
+}
+
+/// Expand the template into `destination`, the path to the expanded project is returned
+fn render(
+    args: &UpdateArgs,
+    provenance: &Provenance,
+    values_file: &Path,
+    destination: &Path,
+    template_ref: TemplateRef,
+    prompt_new_values: bool,
+) -> Result<PathBuf> {
+    // a template from a local git repository is taken via git, so that the revision applies
+    let (git, path) = match (&provenance.template.git, &provenance.template.path) {
+        (Some(git), _) => (Some(git.clone()), None),
+        (None, Some(path)) if provenance.template.commit.is_some() => {
+            (Some(path.display().to_string()), None)
+        }
+        (None, path) => (None, path.as_ref().map(|p| p.display().to_string())),
+    };
+    let (branch, tag, revision) = match (git.is_some(), template_ref) {
+        (false, _) | (_, TemplateRef::Default) => (None, None, None),
+        (true, TemplateRef::Branch(branch)) => (Some(branch), None, None),
+        (true, TemplateRef::Tag(tag)) => (None, Some(tag), None),
+        (true, TemplateRef::Revision(revision)) => (None, None, Some(revision)),
+    };
+
+    let is_bin = provenance.project.crate_type == "bin";
+    crate::generate(GenerateArgs {
+        template_path: TemplatePath {
+            auto_path: provenance.template.subfolder.clone(),
+            git,
+            path,
+            branch,
+            tag,
+            revision,
+            ..TemplatePath::default()
+        },
+        name: Some(provenance.project.name.clone()),
+        force: true,
+        verbose: args.verbose,
+        template_values_file: Some(values_file.to_string_lossy().to_string()),
+        silent: !prompt_new_values,
+        config: args.config.clone(),
+        vcs: Some(Vcs::None),
+        lib: !is_bin,
+        bin: is_bin,
+        ssh_identity: args.ssh_identity.clone(),
+        gitconfig: args.gitconfig.clone(),
+        init: provenance.project.init,
+        destination: Some(destination.to_path_buf()),
+        allow_commands: args.allow_commands,
+        skip_submodules: args.skip_submodules,
+        offline: args.offline,
+        no_workspace: true,
+        provenance: true,
+        ..GenerateArgs::default()
+    })
+    .map_err(|error| {
+        // the hint of `generate` names `--values-file`, which `update` doesn't take
+        match error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ConversionError>())
+        {
+            Some(ConversionError::MissingValues { var_names, .. }) => missing_values(var_names),
+            _ => error,
+        }
+    })
+}
+
+/// The values of the recorded secrets, that are not given by environment variables.
+/// They're asked for once, so that both renderings use the same ones.
+fn secret_values(provenance: &Provenance) -> Result<IndexMap<String, toml::Value>> {
+    let provided = load_env_template_values()?;
+    let missing = provenance
+        .secrets
+        .iter()
+        .filter(|name| !provided.contains_key(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(IndexMap::new());
+    }
+    if !stdin().is_terminal() {
+        return Err(missing_values(&missing));
+    }
+
+    missing
+        .into_iter()
+        .map(|name| {
+            let prompt = Prompt::new(format!("Secret `{name}`"), None);
+            let value = interactive::user_question(&prompt, &None, &StringKind::Secret)?;
+            Ok((name, toml::Value::from(value)))
+        })
+        .collect()
+}
+
+fn missing_values(var_names: &[String]) -> anyhow::Error {
+    anyhow!(
+        "{} {} {}",
+        emoji::ERROR,
+        style(format!(
+            "No value for {}, and no way to ask while not running interactively, set",
+            quoted(var_names.iter())
+        ))
+        .bold()
+        .red(),
+        style(quoted(var_names.iter().map(|name| format!(
+            "CARGO_GENERATE_VALUE_{}",
+            name.to_uppercase()
+        ))))
+        .bold(),
+    )
+}
+
+fn quoted(names: impl Iterator<Item = impl std::fmt::Display>) -> String {
+    names
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The recorded placeholder values and the `secrets`, written to a file in the `--values-file`
+/// format. It's removed together with the temporary `dir`.
+fn write_values_file(
+    provenance: &Provenance,
+    secrets: IndexMap<String, toml::Value>,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let path = dir.join("values.toml");
+    let mut values = provenance.values.clone();
+    values.extend(secrets);
+    write_template_values_file(&path, &values)?;
+    Ok(path)
+}
+
+/// Keep the template source of the original provenance, but take everything else
+/// from the freshly expanded template.
+fn update_provenance(original: &Provenance, theirs: &Path, project_dir: &Path) -> Result<()> {
+    let mut provenance = Provenance::read(theirs)?;
+    provenance.template.git = original.template.git.clone();
+    provenance.template.path = original.template.path.clone();
+    provenance.write(project_dir)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MergeSummary {
+    updated: Vec<PathBuf>,
+    added: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    merged: Vec<PathBuf>,
+    conflicts: Vec<PathBuf>,
+    kept: Vec<PathBuf>,
+    /// removed locally, but changed in the template, which is written next to it
+    changed_after_removal: Vec<PathBuf>,
+}
+
+impl MergeSummary {
+    fn report(&self) {
+        for (label, files) in [
+            ("Updated", &self.updated),
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+            ("Merged", &self.merged),
+        ] {
+            for file in files {
+                info!(
+                    "{} {} `{}`",
+                    emoji::WRENCH,
+                    style(label).bold(),
+                    file.display()
+                );
+            }
+        }
+        for file in &self.kept {
+            warn!(
+                "{} `{}`",
+                style("Kept the local version, as it was removed from the template or is binary:")
+                    .bold()
+                    .yellow(),
+                file.display()
+            );
+        }
+
+        for file in &self.changed_after_removal {
+            warn!(
+                "{} `{}`, {} `{}`",
+                style("Removed locally, but changed in the template:")
+                    .bold()
+                    .yellow(),
+                file.display(),
+                style("its new version is in").bold().yellow(),
+                template_version_path(file).display()
+            );
+        }
+
+        if self.conflicts.is_empty() {
+            info!(
+                "{} {}",
+                emoji::SPARKLE,
+                style("Done! Project updated").bold().green()
+            );
+        } else {
+            for file in &self.conflicts {
+                warn!("{} `{}`", style("Conflict in").bold().red(), file.display());
+            }
+            warn!(
+                "{}",
+                style("Project updated with conflicts, please resolve the conflict markers")
+                    .bold()
+                    .red()
+            );
+        }
+    }
+}
+
+/// Apply the changes between `base` and `theirs` to the project at `ours`
+fn merge_into_project(base: Option<&Path>, theirs: &Path, ours: &Path) -> Result<MergeSummary> {
+    let base_files = base.map(collect_files).transpose()?.unwrap_or_default();
+    let their_files = collect_files(theirs)?;
+    let mut summary = MergeSummary::default();
+
+    let all_files: BTreeSet<&PathBuf> = base_files.keys().chain(their_files.keys()).collect();
+    for file in all_files {
+        let base_content = base_files.get(file).map(fs::read).transpose()?;
+        let their_content = their_files.get(file).map(fs::read).transpose()?;
+        if base.is_some() && base_content == their_content {
+            // the template did not change this file
+            continue;
+        }
+
+        let our_path = ours.join(file);
+        let our_content = our_path
+            .is_file()
+            .then(|| fs::read(&our_path))
+            .transpose()?;
+        if our_content == their_content {
+            continue;
+        }
+
+        match (base_content, our_content, their_content) {
+            (base_content, our_content, Some(_)) if our_content == base_content => {
+                if let Some(parent) = our_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&their_files[file], &our_path)?;
+                if our_content.is_some() {
+                    summary.updated.push(file.clone());
+                } else {
+                    summary.added.push(file.clone());
+                }
+            }
+            // removed locally, but changed in the template
+            (_, None, Some(_)) => {
+                let template_version = ours.join(template_version_path(file));
+                if let Some(parent) = template_version.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&their_files[file], template_version)?;
+                summary.changed_after_removal.push(file.clone());
+            }
+            (Some(base_content), Some(our_content), None) if base_content == our_content => {
+                fs::remove_file(&our_path)?;
+                summary.removed.push(file.clone());
+            }
+            // removed in the template, but changed locally
+            (_, _, None) => summary.kept.push(file.clone()),
+            (base_content, Some(our_content), Some(their_content)) => {
+                let base_content = base_content.unwrap_or_default();
+                if [&base_content, &our_content, &their_content]
+                    .iter()
+                    .any(|c| is_binary(c))
+                {
+                    summary.kept.push(file.clone());
+                    continue;
+                }
+
+                let (merged, clean) = merge_file(file, &base_content, &our_content, &their_content)
+                    .with_context(|| format!("Failed to merge `{}`", file.display()))?;
+                fs::write(&our_path, merged)?;
+                if clean {
+                    summary.merged.push(file.clone());
+                } else {
+                    summary.conflicts.push(file.clone());
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Where the template version of a file that was removed locally is written, `<file>.template`
+fn template_version_path(file: &Path) -> PathBuf {
+    let mut path = file.as_os_str().to_owned();
+    path.push(".template");
+    PathBuf::from(path)
+}
+
+fn is_binary(content: &[u8]) -> bool {
+    content.contains(&0)
+}
+
+/// Three-way merge of a single file, returns the merged content and whether it merged cleanly
+fn merge_file(file: &Path, base: &[u8], ours: &[u8], theirs: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let name = file.to_string_lossy().to_string();
+    let mut base_input = MergeFileInput::new();
+    base_input.path(name.as_str()).content(base);
+    let mut our_input = MergeFileInput::new();
+    our_input.path(name.as_str()).content(ours);
+    let mut their_input = MergeFileInput::new();
+    their_input.path(name.as_str()).content(theirs);
+
+    let mut opts = MergeFileOptions::new();
+    opts.ancestor_label("template (generated)")
+        .our_label("project")
+        .their_label("template (updated)");
+
+    let result = git2::merge_file(&base_input, &our_input, &their_input, Some(&mut opts))?;
+    Ok((result.content().to_vec(), result.is_automergeable()))
+}
+
+/// All files below `root`, relative to it, except for git internals and the provenance file
+fn collect_files(root: &Path) -> Result<BTreeMap<PathBuf, PathBuf>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() || entry.file_name() == PROVENANCE_FILE_NAME {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root)?.to_path_buf();
+        files.insert(relative, entry.into_path());
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_file;
+
+    fn read(dir: &Path, file: &str) -> String {
+        fs::read_to_string(dir.join(file)).unwrap()
+    }
+
+    #[test]
+    fn merge_takes_template_changes_of_untouched_files() -> Result<()> {
+        let (base, theirs, ours) = (tmp_dir()?, tmp_dir()?, tmp_dir()?);
+        create_file(&base, "a.txt", "a\n")?;
+        create_file(&theirs, "a.txt", "a2\n")?;
+        create_file(&ours, "a.txt", "a\n")?;
+
+        let summary = merge_into_project(Some(base.path()), theirs.path(), ours.path())?;
+
+        assert_eq!(summary.updated, vec![PathBuf::from("a.txt")]);
+        assert_eq!(read(ours.path(), "a.txt"), "a2\n");
+        Ok(())
+    }
+
+    #[test]
+    fn merge_combines_local_and_template_changes() -> Result<()> {
+        let (base, theirs, ours) = (tmp_dir()?, tmp_dir()?, tmp_dir()?);
+        create_file(&base, "a.txt", "1\n2\n3\n4\n5\n")?;
+        create_file(&theirs, "a.txt", "1\n2\n3\n4\nfive\n")?;
+        create_file(&ours, "a.txt", "one\n2\n3\n4\n5\n")?;
+
+        let summary = merge_into_project(Some(base.path()), theirs.path(), ours.path())?;
+
+        assert_eq!(summary.merged, vec![PathBuf::from("a.txt")]);
+        assert_eq!(read(ours.path(), "a.txt"), "one\n2\n3\n4\nfive\n");
+        Ok(())
+    }
+
+    #[test]
+    fn merge_marks_conflicts() -> Result<()> {
+        let (base, theirs, ours) = (tmp_dir()?, tmp_dir()?, tmp_dir()?);
+        create_file(&base, "a.txt", "a\n")?;
+        create_file(&theirs, "a.txt", "template\n")?;
+        create_file(&ours, "a.txt", "project\n")?;
+
+        let summary = merge_into_project(Some(base.path()), theirs.path(), ours.path())?;
+
+        assert_eq!(summary.conflicts, vec![PathBuf::from("a.txt")]);
+        let merged = read(ours.path(), "a.txt");
+        assert!(merged.contains("<<<<<<< project"));
+        assert!(merged.contains(">>>>>>> template (updated)"));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_adds_and_removes_files() -> Result<()> {
+        let (base, theirs, ours) = (tmp_dir()?, tmp_dir()?, tmp_dir()?);
+        create_file(&base, "removed.txt", "r\n")?;
+        create_file(&base, "modified_and_removed.txt", "m\n")?;
+        create_file(&theirs, "dir/added.txt", "new\n")?;
+        create_file(&ours, "removed.txt", "r\n")?;
+        create_file(&ours, "modified_and_removed.txt", "local change\n")?;
+        create_file(&ours, "local.txt", "local\n")?;
+
+        let summary = merge_into_project(Some(base.path()), theirs.path(), ours.path())?;
+
+        assert_eq!(summary.added, vec![PathBuf::from("dir/added.txt")]);
+        assert_eq!(summary.removed, vec![PathBuf::from("removed.txt")]);
+        assert_eq!(
+            summary.kept,
+            vec![PathBuf::from("modified_and_removed.txt")]
+        );
+        assert!(!ours.path().join("removed.txt").exists());
+        assert_eq!(read(ours.path(), "local.txt"), "local\n");
+        Ok(())
+    }
+
+    #[test]
+    fn merge_writes_template_changes_of_locally_removed_files_next_to_them() -> Result<()> {
+        let (base, theirs, ours) = (tmp_dir()?, tmp_dir()?, tmp_dir()?);
+        create_file(&base, "dir/a.txt", "a\n")?;
+        create_file(&theirs, "dir/a.txt", "a2\n")?;
+        fs::create_dir(ours.path().join("dir"))?;
+
+        let summary = merge_into_project(Some(base.path()), theirs.path(), ours.path())?;
+
+        assert_eq!(
+            summary.changed_after_removal,
+            vec![PathBuf::from("dir/a.txt")]
+        );
+        assert!(summary.conflicts.is_empty());
+        assert!(!ours.path().join("dir/a.txt").exists());
+        assert_eq!(read(ours.path(), "dir/a.txt.template"), "a2\n");
+        Ok(())
+    }
+}
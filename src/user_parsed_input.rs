@@ -202,6 +202,7 @@ impl UserParsedInput {
                         None,
                         args.force_git_init,
                         args.skip_submodules,
+                        args.offline,
                     );
 
                     TemplateLocation::from(git_user_input)
@@ -256,6 +257,9 @@ impl UserParsedInput {
             TemplateLocation::Path(path) => {
                 format!("local path: {}", style(path.display()).bold())
             }
+            TemplateLocation::Archive(path) => {
+                format!("local archive: {}", style(path.display()).bold())
+            }
         };
         warn!(
             "Favorite `{}` not found in config, using it as a {}",
@@ -356,6 +360,8 @@ pub struct GitUserInput {
     gitconfig: Option<PathBuf>,
     _force_init: bool,
     pub skip_submodules: bool,
+    /// only use the git cache, never the network
+    pub offline: bool,
 }
 
 impl GitUserInput {
@@ -369,6 +375,7 @@ impl GitUserInput {
         gitconfig: Option<PathBuf>,
         force_init: bool,
         skip_submodules: bool,
+        offline: bool,
     ) -> Self {
         Self {
             url: url.as_ref().to_owned(),
@@ -379,6 +386,7 @@ impl GitUserInput {
             gitconfig,
             _force_init: force_init,
             skip_submodules,
+            offline,
         }
     }
 
@@ -397,6 +405,7 @@ impl GitUserInput {
             opts.gitconfig.clone(),
             opts.force_git_init,
             opts.skip_submodules,
+            opts.offline,
         )
     }
 
@@ -437,14 +446,28 @@ fn clone_opts_from_args(
         gitconfig: args.gitconfig.clone(),
         force_git_init: args.force_git_init,
         skip_submodules: args.skip_submodules,
+        offline: args.offline,
     }
 }
 
-// Distinguish between plain copy and clone
+// Distinguish between plain copy, unpacking and clone
 #[derive(Debug)]
 pub enum TemplateLocation {
     Git(GitUserInput),
     Path(PathBuf),
+    /// a local archive file, e.g. a `.tar.gz`
+    Archive(PathBuf),
+}
+
+impl TemplateLocation {
+    /// A local template, either a directory or an archive file
+    fn local(path: PathBuf) -> Self {
+        if crate::archive::is_archive(&path) && !path.is_dir() {
+            Self::Archive(path)
+        } else {
+            Self::Path(path)
+        }
+    }
 }
 
 impl From<GitUserInput> for TemplateLocation {
@@ -455,37 +478,37 @@ impl From<GitUserInput> for TemplateLocation {
 
 impl From<PathBuf> for TemplateLocation {
     fn from(source: PathBuf) -> Self {
-        Self::Path(source)
+        Self::local(source)
     }
 }
 
 impl From<&PathBuf> for TemplateLocation {
     fn from(source: &PathBuf) -> Self {
-        Self::Path(source.clone())
+        Self::local(source.clone())
     }
 }
 
 impl From<&std::path::Path> for TemplateLocation {
     fn from(source: &std::path::Path) -> Self {
-        Self::Path(PathBuf::from(source))
+        Self::local(PathBuf::from(source))
     }
 }
 
 impl From<&str> for TemplateLocation {
     fn from(source: &str) -> Self {
-        Self::Path(PathBuf::from(source))
+        Self::local(PathBuf::from(source))
     }
 }
 
 impl From<String> for TemplateLocation {
     fn from(source: String) -> Self {
-        Self::Path(PathBuf::from(source))
+        Self::local(PathBuf::from(source))
     }
 }
 
 impl From<&String> for TemplateLocation {
     fn from(source: &String) -> Self {
-        Self::Path(PathBuf::from(source))
+        Self::local(PathBuf::from(source))
     }
 }
 
@@ -507,7 +530,7 @@ mod tests {
         let parsed = UserParsedInput::try_from_args_and_config(AppConfig::default(), &args);
         match parsed.location() {
             TemplateLocation::Git(git) => git.url().to_owned(),
-            TemplateLocation::Path(p) => panic!("expected Git location, got Path: {p:?}"),
+            other => panic!("expected Git location, got {other:?}"),
         }
     }
 
@@ -590,7 +613,7 @@ mod tests {
                     git.url()
                 );
             }
-            TemplateLocation::Path(p) => panic!("expected Git location, got Path: {p:?}"),
+            other => panic!("expected Git location, got {other:?}"),
         }
     }
 
@@ -617,7 +640,7 @@ mod tests {
                     git.url()
                 );
             }
-            TemplateLocation::Path(p) => panic!("expected Git location, got Path: {p:?}"),
+            other => panic!("expected Git location, got {other:?}"),
         }
     }
 }
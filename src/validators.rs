@@ -0,0 +1,324 @@
+//! Semantic validators of placeholder values, configured with `validate = "..."`
+//!
+//! They cover values that are hard to describe with a regex, like versions or license expressions.
+
+use std::{
+    fmt::{self, Display},
+    path::Path,
+    str::FromStr,
+};
+
+use cargo_util_schemas::manifest::PackageName;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validator {
+    Semver,
+    Url,
+    Email,
+    RustIdent,
+    CrateName,
+    Spdx,
+    RelativePath,
+}
+
+/// Keywords that can't be used as rust identifiers, in any edition
+const RUST_KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+impl Validator {
+    pub const ALL: [Self; 7] = [
+        Self::Semver,
+        Self::Url,
+        Self::Email,
+        Self::RustIdent,
+        Self::CrateName,
+        Self::Spdx,
+        Self::RelativePath,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Semver => "semver",
+            Self::Url => "url",
+            Self::Email => "email",
+            Self::RustIdent => "rust_ident",
+            Self::CrateName => "crate_name",
+            Self::Spdx => "spdx",
+            Self::RelativePath => "relative_path",
+        }
+    }
+
+    /// Check `value`, the error completes a sentence like "`value` ..."
+    pub fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            Self::Semver => semver::Version::parse(value)
+                .map(drop)
+                .map_err(|e| format!("must be a semantic version like 1.2.3 ({e})")),
+            Self::Url => url::Url::parse(value)
+                .map(drop)
+                .map_err(|e| format!("must be an absolute URL ({e})")),
+            Self::Email => check_email(value)
+                .ok_or_else(|| "must be an email address like someone@example.com".to_string()),
+            Self::RustIdent => check_rust_ident(value),
+            Self::CrateName => check_crate_name(value),
+            Self::Spdx => SpdxParser::new(value).parse().map_err(|e| {
+                format!("must be an SPDX license expression like `MIT OR Apache-2.0` ({e})")
+            }),
+            Self::RelativePath => check_relative_path(value),
+        }
+    }
+}
+
+impl Display for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Validator {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|validator| validator.name() == s)
+            .ok_or(())
+    }
+}
+
+fn check_email(value: &str) -> Option<()> {
+    let (local, domain) = value.rsplit_once('@')?;
+    let valid_part =
+        |part: &str| !part.is_empty() && !part.contains(|c: char| c.is_whitespace() || c == '@');
+    let (_, tld) = domain.rsplit_once('.')?;
+    (valid_part(local)
+        && valid_part(domain)
+        && !tld.is_empty()
+        && domain.split('.').all(|label| !label.is_empty()))
+    .then_some(())
+}
+
+fn check_rust_ident(value: &str) -> Result<(), String> {
+    let mut chars = value.chars();
+    let starts_well = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_');
+    if !starts_well || !chars.all(|c| c.is_alphanumeric() || c == '_') || value == "_" {
+        return Err(
+            "must be a rust identifier, made of letters, digits and `_`, not starting with a digit"
+                .into(),
+        );
+    }
+    if RUST_KEYWORDS.contains(&value) {
+        return Err(format!("must not be the rust keyword `{value}`"));
+    }
+    Ok(())
+}
+
+fn check_crate_name(value: &str) -> Result<(), String> {
+    if value.contains(':') {
+        return Err("must be a crate name, `:` is not allowed".into());
+    }
+    PackageName::new(value).map_err(|e| format!("must be a crate name ({e})"))?;
+    if RUST_KEYWORDS.contains(&value) {
+        return Err(format!("must not be the rust keyword `{value}`"));
+    }
+    Ok(())
+}
+
+fn check_relative_path(value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Err("must be a relative path, but is empty".into());
+    }
+    // `Path` only knows about the separators and drive letters of the current platform
+    let has_drive = value.get(1..2) == Some(":");
+    if value.starts_with(['/', '\\']) || has_drive || Path::new(value).is_absolute() {
+        return Err("must be a relative path, not an absolute one".into());
+    }
+    if value.split(['/', '\\']).any(|part| part == "..") {
+        return Err(
+            "must be a relative path that stays inside the project, `..` is not allowed".into(),
+        );
+    }
+    Ok(())
+}
+
+/// Checks the syntax of an SPDX license expression
+///
+/// ```text
+/// expression = and ("OR" and)*
+/// and        = term ("AND" term)*
+/// term       = "(" expression ")" | license ["WITH" exception]
+/// ```
+///
+/// License and exception identifiers are not checked against the SPDX license list.
+struct SpdxParser<'a> {
+    tokens: std::iter::Peekable<std::vec::IntoIter<&'a str>>,
+}
+
+impl<'a> SpdxParser<'a> {
+    fn new(value: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        for word in value.split_whitespace() {
+            let mut rest = word;
+            while !rest.is_empty() {
+                let end = match rest.find(['(', ')']) {
+                    Some(0) => 1,
+                    Some(index) => index,
+                    None => rest.len(),
+                };
+                tokens.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+        Self {
+            tokens: tokens.into_iter().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Result<(), String> {
+        self.expression()?;
+        self.tokens
+            .next()
+            .map_or(Ok(()), |token| Err(format!("unexpected `{token}`")))
+    }
+
+    fn expression(&mut self) -> Result<(), String> {
+        self.and()?;
+        while self.tokens.next_if_eq(&"OR").is_some() {
+            self.and()?;
+        }
+        Ok(())
+    }
+
+    fn and(&mut self) -> Result<(), String> {
+        self.term()?;
+        while self.tokens.next_if_eq(&"AND").is_some() {
+            self.term()?;
+        }
+        Ok(())
+    }
+
+    fn term(&mut self) -> Result<(), String> {
+        match self.tokens.next() {
+            Some("(") => {
+                self.expression()?;
+                match self.tokens.next() {
+                    Some(")") => Ok(()),
+                    Some(token) => Err(format!("expected `)`, found `{token}`")),
+                    None => Err("missing `)`".into()),
+                }
+            }
+            Some(license) => {
+                let id = license.strip_suffix('+').unwrap_or(license);
+                let id = id
+                    .split_once(':')
+                    .filter(|(document, _)| document.starts_with("DocumentRef-"))
+                    .map_or(id, |(_, id)| id);
+                if !is_spdx_id(id) {
+                    return Err(format!("`{license}` is not a license identifier"));
+                }
+                if self.tokens.next_if_eq(&"WITH").is_some() {
+                    match self.tokens.next() {
+                        Some(exception) if is_spdx_id(exception) => {}
+                        Some(token) => {
+                            return Err(format!("`{token}` is not an exception identifier"))
+                        }
+                        None => return Err("missing exception after `WITH`".into()),
+                    }
+                }
+                Ok(())
+            }
+            None => Err("missing license identifier".into()),
+        }
+    }
+}
+
+fn is_spdx_id(id: &str) -> bool {
+    !id.is_empty()
+        && !["AND", "OR", "WITH"].contains(&id)
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(validator: &str, value: &str) -> Result<(), String> {
+        validator.parse::<Validator>().unwrap().check(value)
+    }
+
+    #[test]
+    fn every_validator_parses_from_its_name() {
+        for validator in Validator::ALL {
+            assert_eq!(validator.name().parse(), Ok(validator));
+        }
+        assert_eq!("SemVer".parse::<Validator>(), Err(()));
+    }
+
+    #[test]
+    fn accepts_valid_values() {
+        for (validator, value) in [
+            ("semver", "1.2.3-alpha.1+build"),
+            ("url", "https://github.com/cargo-generate/cargo-generate"),
+            ("email", "someone@example.com"),
+            ("rust_ident", "_my_ident2"),
+            ("crate_name", "my-crate_2"),
+            ("spdx", "MIT"),
+            ("spdx", "MIT OR Apache-2.0"),
+            (
+                "spdx",
+                "(MIT OR Apache-2.0) AND GPL-2.0+ WITH Classpath-exception-2.0",
+            ),
+            ("spdx", "DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2"),
+            ("relative_path", "src/bin/main.rs"),
+            ("relative_path", "./docs"),
+        ] {
+            assert_eq!(check(validator, value), Ok(()), "{validator}: {value}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for (validator, value) in [
+            ("semver", "1.2"),
+            ("url", "github.com/cargo-generate"),
+            ("email", "someone@example"),
+            ("email", "some one@example.com"),
+            ("rust_ident", "2fast"),
+            ("rust_ident", "my-ident"),
+            ("rust_ident", "match"),
+            ("crate_name", "1st-crate"),
+            ("crate_name", "my crate"),
+            ("crate_name", "foo::bar"),
+            ("spdx", ""),
+            ("spdx", "MIT OR"),
+            ("spdx", "MIT/Apache-2.0"),
+            ("spdx", "(MIT OR Apache-2.0"),
+            ("spdx", "MIT WITH"),
+            ("relative_path", ""),
+            ("relative_path", "/etc/passwd"),
+            ("relative_path", "C:\\Windows"),
+            ("relative_path", "src/../../outside"),
+        ] {
+            assert!(check(validator, value).is_err(), "{validator}: {value}");
+        }
+    }
+
+    #[test]
+    fn error_explains_what_is_expected() {
+        assert_eq!(
+            check("rust_ident", "match"),
+            Err("must not be the rust keyword `match`".to_string())
+        );
+        assert_eq!(
+            check("spdx", "MIT AND").unwrap_err(),
+            "must be an SPDX license expression like `MIT OR Apache-2.0` (missing license identifier)"
+        );
+    }
+}
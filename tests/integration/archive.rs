@@ -0,0 +1,113 @@
+use crate::helpers::prelude::*;
+
+/// Pack the files of `template` into `<dir>/<name>`, below a `template-1.0` folder
+fn pack(template: &Project, dir: &Project, name: &str) -> PathBuf {
+    let archive = dir.path().join(name);
+    let file = std::fs::File::create(&archive).unwrap();
+    if name.ends_with(".zip") {
+        let mut zip = zip::ZipWriter::new(file);
+        for entry in walkdir::WalkDir::new(template.path()) {
+            let entry = entry.unwrap();
+            let relative = entry.path().strip_prefix(template.path()).unwrap();
+            if entry.file_type().is_file() && !relative.starts_with(".git") {
+                let name = format!("template-1.0/{}", relative.display());
+                zip.start_file(name, zip::write::SimpleFileOptions::default())
+                    .unwrap();
+                std::io::Write::write_all(&mut zip, &std::fs::read(entry.path()).unwrap()).unwrap();
+            }
+        }
+        zip.finish().unwrap();
+    } else {
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        ));
+        tar.append_dir_all("template-1.0", template.path()).unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+    archive
+}
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                kind = { type = "string", prompt = "Kind?", default = "tarball" }
+            "#},
+        )
+        .file(
+            "Cargo.toml",
+            indoc! {r#"
+                [package]
+                name = "{{project-name}}"
+                description = "from a {{kind}}"
+            "#},
+        )
+        .file(
+            "inner/Cargo.toml",
+            "[package]\nname = \"inner-{{project-name}}\"\n",
+        )
+        .build()
+}
+
+#[test]
+fn it_generates_from_a_tarball() {
+    let template = template();
+    let dir = tempdir().build();
+    let archive = pack(&template, &dir, "template.tar.gz");
+
+    binary()
+        .arg(&archive)
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done!").from_utf8());
+
+    assert!(dir
+        .read("foobar-project/Cargo.toml")
+        .contains(r#"description = "from a tarball""#));
+    assert!(!dir.exists("foobar-project/cargo-generate.toml"));
+}
+
+#[test]
+fn it_generates_a_subfolder_of_a_zip_from_a_file_url() {
+    let template = template();
+    let dir = tempdir().build();
+    let archive = pack(&template, &dir, "template.zip");
+
+    binary()
+        .arg(format!("file://{}", archive.display()))
+        .arg("inner")
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done!").from_utf8());
+
+    assert!(dir
+        .read("foobar-project/Cargo.toml")
+        .contains(r#"name = "inner-foobar-project""#));
+}
+
+#[test]
+fn it_accepts_an_archive_as_path() {
+    let template = template();
+    let dir = tempdir().build();
+    pack(&template, &dir, "template.tar.gz");
+
+    binary()
+        .arg("--path")
+        .arg("template.tar.gz")
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert!(dir.exists("foobar-project/Cargo.toml"));
+}
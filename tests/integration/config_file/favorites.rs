@@ -348,3 +348,82 @@ fn favorite_can_specify_to_be_generated_into_cwd() -> anyhow::Result<()> {
     assert!(!dir.path().join(".git").exists());
     Ok(())
 }
+
+#[test]
+fn favorites_can_be_managed_from_the_command_line() {
+    let template = create_template("managed-template");
+    let config_dir = tempdir()
+        .file(
+            "config.toml",
+            indoc! {r#"
+                # hand written
+                [values]
+                license = "MIT"
+            "#},
+        )
+        .build();
+    let config = config_dir.path().join("config.toml");
+    let dir = tempdir().build();
+
+    binary()
+        .args(["favorite", "add", "mine", "--git"])
+        .arg(template.path())
+        .args(["--branch", "main", "--description", "My template"])
+        .arg("--config")
+        .arg(&config)
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Favorite `mine` added").from_utf8());
+
+    binary()
+        .args(["favorite", "rename", "mine", "ours", "--config"])
+        .arg(&config)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    binary()
+        .args(["favorite", "show", "ours", "--config"])
+        .arg(&config)
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("description: My template").from_utf8())
+        .stdout(predicates::str::contains("branch: main").from_utf8());
+
+    binary()
+        .arg("--config")
+        .arg(&config)
+        .arg_name("from-favorite")
+        .arg("ours")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    assert!(dir
+        .read("from-favorite/Cargo.toml")
+        .contains(r#"description = "managed-template""#));
+
+    binary()
+        .args(["favorite", "remove", "ours", "--config"])
+        .arg(&config)
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        config_dir.read("config.toml"),
+        indoc! {r#"
+            # hand written
+            [values]
+            license = "MIT"
+        "#}
+    );
+
+    binary()
+        .args(["favorite", "remove", "ours", "--config"])
+        .arg(&config)
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Favorite `ours` is not defined").from_utf8());
+}
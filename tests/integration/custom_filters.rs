@@ -0,0 +1,49 @@
+use crate::helpers::prelude::*;
+
+#[test]
+fn declared_filters_work_in_contents_and_file_names() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [filters]
+                semver_major = "filters/semver_major.rhai"
+                wrap = "filters/wrap.rhai"
+
+                [placeholders]
+                version = { type = "string", prompt = "Version?", default = "2.4.1" }
+            "#},
+        )
+        .file("filters/semver_major.rhai", r#"value.split(".")[0]"#)
+        .file("filters/wrap.rhai", r#"`${quote}${value}${quote}`"#)
+        .file(
+            "src/version.rs",
+            indoc! {r#"
+                pub const MAJOR: u32 = {{ version | semver_major }};
+                pub const NAME: &str = {{ project-name | wrap: quote: '"' }};
+            "#},
+        )
+        .file("v{{ version | semver_major }}.md", "")
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/src/version.rs"),
+        indoc! {r#"
+            pub const MAJOR: u32 = 2;
+            pub const NAME: &str = "foobar-project";
+        "#}
+    );
+    assert!(dir.exists("foobar-project/v2.md"));
+    assert!(!dir.exists("foobar-project/filters/semver_major.rhai"));
+    assert!(!dir.exists("foobar-project/filters/wrap.rhai"));
+}
@@ -0,0 +1,124 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                exclude = ["excluded.txt"]
+                ignore = ["ignored.txt"]
+
+                [hooks]
+                pre = ["pre-script.rhai"]
+            "#},
+        )
+        .file("pre-script.rhai", r#"throw "the hook must not run";"#)
+        .file("Cargo.toml", "[package]\nname = \"{{project-name}}\"\n")
+        .file("{{project-name}}.md", "# {{project-name}}")
+        .file("excluded.txt", "{{project-name}}")
+        .file("ignored.txt", "nothing to see")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_reports_the_planned_files_without_writing_them() {
+    let template = template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--dry-run")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Hooks are skipped in a dry run")
+                .and(predicates::str::is_match(r"rendered\s+Cargo.toml").unwrap())
+                .and(predicates::str::is_match(r"rendered\s+foobar-project.md").unwrap())
+                .and(predicates::str::is_match(r"copied\s+excluded.txt").unwrap())
+                .and(predicates::str::is_match(r"ignored\s+ignored.txt").unwrap())
+                .and(predicates::str::is_match(r"ignored\s+pre-script.rhai").unwrap())
+                .and(predicates::str::contains("Dry run done"))
+                .from_utf8(),
+        );
+
+    assert!(!dir.exists("foobar-project"));
+}
+
+#[test]
+fn it_reports_files_that_would_be_overwritten() {
+    let template = template();
+    let dir = tempdir().file("Cargo.toml", "existing").build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--dry-run")
+        .flag_init()
+        .arg("--overwrite")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("These files already exist and would be overwritten:")
+                .and(predicates::str::is_match(r"\n\s+Cargo.toml").unwrap())
+                .from_utf8(),
+        );
+
+    assert_eq!(dir.read("Cargo.toml"), "existing");
+    assert!(!dir.exists("foobar-project.md"));
+}
+
+#[test]
+fn it_fails_a_dry_run_into_an_existing_directory() {
+    let template = template();
+    let dir = tempdir().file("foobar-project/file", "").build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--dry-run")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Target directory already exists").from_utf8());
+}
+
+#[test]
+fn it_reports_liquid_files_that_would_fail_on_existing_files() {
+    // the first `.liquid` suffix is stripped when the template is fetched, the second one on copy
+    let template = tempdir()
+        .file("notes.txt.liquid.liquid", "{{project-name}}")
+        .file("plain.txt", "plain")
+        .init_git()
+        .build();
+    let dir = tempdir()
+        .file("notes.txt", "existing")
+        .file("plain.txt", "existing")
+        .build();
+    let generate = |dry_run: bool| {
+        let mut cmd = binary();
+        cmd.arg_git(template.path())
+            .arg_name("foobar-project")
+            .flag_init();
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        cmd.current_dir(dir.path()).assert()
+    };
+
+    generate(true).success().stdout(
+        predicates::str::is_match(
+            r"These files already exist, and generating would fail without `--overwrite`:\n\s+notes.txt\n",
+        )
+        .unwrap()
+        .and(predicates::str::is_match(r"would be kept, unless `--overwrite` is given:\n\s+plain.txt\n").unwrap())
+        .from_utf8(),
+    );
+    generate(false)
+        .failure()
+        .stderr(predicates::str::contains("File already exists").from_utf8());
+}
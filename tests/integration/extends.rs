@@ -0,0 +1,122 @@
+use crate::helpers::prelude::*;
+use crate::helpers::project_builder::ProjectBuilder;
+
+const BASE_CONFIG: &str = indoc! {r#"
+    [placeholders]
+    license = { type = "string", prompt = "License?", default = "MIT" }
+    ci = { type = "bool", prompt = "CI?", default = true }
+
+    [hooks]
+    pre = ["base.rhai"]
+"#};
+
+fn base_files(builder: ProjectBuilder, dir: &str) -> ProjectBuilder {
+    builder
+        .file(&format!("{dir}cargo-generate.toml"), BASE_CONFIG)
+        .file(
+            &format!("{dir}base.rhai"),
+            r#"variable::set("from_base", "yes");"#,
+        )
+        .file(&format!("{dir}LICENSE"), "{{license}} license")
+        .file(&format!("{dir}README.md"), "base readme")
+        .file(
+            &format!("{dir}ci.txt"),
+            "ci: {{ci}}, base hook: {{from_base}}",
+        )
+}
+
+#[test]
+fn a_template_inherits_files_and_config_from_a_local_parent() {
+    let templates = base_files(tempdir(), "base/")
+        .file(
+            "service/cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                extends = { path = "../base" }
+
+                [placeholders]
+                ci = { type = "bool", prompt = "CI?", default = false }
+                port = { type = "string", prompt = "Port?", default = "8080" }
+            "#},
+        )
+        .file("service/README.md", "{{project-name}} on port {{port}}")
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_path(templates.path().join("service"))
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Extending the template").from_utf8());
+
+    assert_eq!(dir.read("foobar-project/LICENSE"), "MIT license");
+    assert_eq!(
+        dir.read("foobar-project/README.md"),
+        "foobar-project on port 8080"
+    );
+    assert_eq!(
+        dir.read("foobar-project/ci.txt"),
+        "ci: false, base hook: yes"
+    );
+    assert!(!dir.exists("foobar-project/cargo-generate.toml"));
+}
+
+#[test]
+fn a_template_inherits_from_a_git_parent() {
+    let base = base_files(tempdir(), "").init_git().build();
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            format!(
+                indoc! {r#"
+                    [template]
+                    extends = {{ git = "{git}", branch = "main" }}
+                "#},
+                git = base.path().display().to_string().escape_default(),
+            ),
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(dir.read("foobar-project/README.md"), "base readme");
+    assert_eq!(
+        dir.read("foobar-project/ci.txt"),
+        "ci: true, base hook: yes"
+    );
+}
+
+#[test]
+fn templates_extending_each_other_are_rejected() {
+    let templates = tempdir()
+        .file(
+            "a/cargo-generate.toml",
+            "[template]\nextends = { path = \"../b\" }\n",
+        )
+        .file(
+            "b/cargo-generate.toml",
+            "[template]\nextends = { path = \"../a\" }\n",
+        )
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_path(templates.path().join("a"))
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("extend each other in a cycle").from_utf8());
+}
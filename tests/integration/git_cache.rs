@@ -0,0 +1,140 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file("Cargo.toml", "[package]\nname = \"{{project-name}}\"\n")
+        .file("README.md", "first")
+        .init_git()
+        .build()
+}
+
+fn url(template: &Project) -> String {
+    format!("file://{}", template.path().display())
+}
+
+#[test]
+fn it_reuses_the_cached_template_and_works_offline() {
+    let template = template();
+    let cache = tempdir().build();
+    let generate = |dir: &Project, name: &str, offline: bool| {
+        let mut cmd = binary();
+        cmd.arg_git(url(&template)).arg_name(name);
+        if offline {
+            cmd.arg("--offline");
+        }
+        cmd.current_dir(dir.path())
+            .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+            .assert()
+            .success()
+    };
+
+    let dir = tempdir().build();
+    generate(&dir, "first", false);
+    assert_eq!(dir.read("first/README.md"), "first");
+    generate(&dir, "cached", false)
+        .stdout(predicates::str::contains("Using the cached template at commit").from_utf8());
+
+    // offline, the commit the default branch resolved to last time is used
+    template.commit_file("README.md", "second");
+    generate(&dir, "offline", true);
+    assert_eq!(dir.read("offline/README.md"), "first");
+
+    // online, the new commit is fetched
+    generate(&dir, "online", false);
+    assert_eq!(dir.read("online/README.md"), "second");
+}
+
+#[test]
+fn it_warns_when_the_cached_template_can_not_be_checked() {
+    let template = template();
+    let cache = tempdir().build();
+    let dir = tempdir().build();
+    let generate = |name: &str| {
+        binary()
+            .arg_git(url(&template))
+            .arg_name(name)
+            .current_dir(dir.path())
+            .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+            .assert()
+            .success()
+    };
+
+    generate("first");
+    fs::remove_dir_all(template.path().join(".git")).unwrap();
+    generate("unreachable").stdout(
+        predicates::str::contains("a cached template might be outdated")
+            .and(predicates::str::contains(
+                "Using the cached template at commit",
+            ))
+            .from_utf8(),
+    );
+    assert_eq!(dir.read("unreachable/README.md"), "first");
+}
+
+#[test]
+fn it_fails_offline_if_the_template_is_not_cached() {
+    let template = template();
+    let cache = tempdir().build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(url(&template))
+        .arg_name("foobar-project")
+        .arg("--offline")
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("is not in the git cache").from_utf8());
+
+    assert!(!dir.exists("foobar-project"));
+}
+
+#[test]
+fn it_lists_prunes_and_clears_the_cache() {
+    let template = template();
+    let cache = tempdir().build();
+    let dir = tempdir().build();
+    binary()
+        .arg_git(url(&template))
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .success();
+
+    binary()
+        .args(["cache", "list"])
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(url(&template))
+                .and(predicates::str::contains("used today"))
+                .and(predicates::str::contains("(HEAD)"))
+                .from_utf8(),
+        );
+
+    binary()
+        .args(["cache", "prune"])
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Removed 0 cached commit(s)").from_utf8());
+
+    binary()
+        .args(["cache", "clear"])
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .success();
+    binary()
+        .args(["cache", "list"])
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_CACHE_DIR", cache.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("(empty)").from_utf8());
+}
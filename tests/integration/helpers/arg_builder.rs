@@ -12,6 +12,11 @@ impl CargoGenerateArgBuilder {
     pub fn new() -> Self {
         let mut builder = Self(cargo::cargo_bin_cmd!());
         builder.0.arg("generate");
+        // keep the git cache of the tests out of the real $CARGO_HOME
+        builder.0.env(
+            "CARGO_GENERATE_CACHE_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("git-cache"),
+        );
 
         builder
     }
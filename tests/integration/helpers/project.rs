@@ -35,6 +35,34 @@ impl Project {
         self.path().join(path).exists()
     }
 
+    /// Writes `contents` to the file `path` and commits it to the current branch.
+    pub fn commit_file(&self, path: &str, contents: &str) {
+        let file = self.path().join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, contents).unwrap_or_else(|_| panic!("couldn't write {file:?}"));
+
+        for args in [
+            vec!["add", "--all"],
+            vec![
+                "-c",
+                "user.name='Foo Bar'",
+                "-c",
+                "user.email='foo@bar.com'",
+                "commit",
+                "--no-gpg-sign",
+                "--message",
+                path,
+            ],
+        ] {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(self.path())
+                .output()
+                .expect("failed to execute `git`");
+            assert!(output.status.success());
+        }
+    }
+
     /// Returns the commit SHAs of the commits in the current branch.
     pub fn commit_shas(&self) -> Vec<String> {
         std::process::Command::new("git")
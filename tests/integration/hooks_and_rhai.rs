@@ -522,6 +522,45 @@ fn rhai_filter_invokes_rhai_script() {
         .contains("content from RHAI"));
 }
 
+#[test]
+fn rhai_filter_passes_the_value_and_arguments_to_the_script() {
+    let template = tempdir()
+        .file(
+            "scripts/fmt.rhai",
+            indoc! {r#"
+                let padded = "";
+                padded.pad(width - value.len(), ' ');
+                `${padded}${value}${suffix}`
+            "#},
+        )
+        .file(
+            "file_to_expand.txt",
+            indoc! {r#"
+                [{{ "ab" | rhai: "scripts/fmt.rhai", width: 4, suffix: "!" }}]
+                {{ project-name | rhai_expr: "value.to_upper() + sep + n * 2", sep: "-", n: 21 }}
+                {{ "x" | rhai_expr: 'flag && value == "x"', flag: true }}
+            "#},
+        )
+        .init_git()
+        .build();
+
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("filter-project")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done"));
+
+    assert_eq!(
+        dir.read("filter-project/file_to_expand.txt"),
+        "[  ab!]\nFILTER-PROJECT-42\ntrue\n"
+    );
+    assert!(!dir.exists("filter-project/scripts/fmt.rhai"));
+}
+
 #[test]
 fn date_works() {
     let template = tempdir()
@@ -0,0 +1,138 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                license = { type = "string", prompt = "Which license?", choices = ["MIT", "Apache-2.0"], default = "MIT" }
+                ci = { type = "bool", prompt = "Add CI?", default = false }
+
+                [conditional.'ci']
+                ignore = [".github"]
+                placeholders = { runner = { type = "string", prompt = "Which runner?", regex = "^[a-z-]+$" } }
+
+                [hooks]
+                pre = ["pre-script.rhai"]
+            "#},
+        )
+        .file("pre-script.rhai", r#"throw "inspect must not run hooks";"#)
+        .with_default_manifest()
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_describes_a_template_as_tables() {
+    let template = template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("inspect")
+        .arg_git(template.path())
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match(
+                r"license\s+string\s+Which license\?\s+MIT\s+MIT, Apache-2.0",
+            )
+            .unwrap()
+            .and(predicates::str::is_match(r"ci\s+bool\s+Add CI\?\s+false").unwrap())
+            .and(predicates::str::contains("if `ci`"))
+            .and(predicates::str::contains("ignore: .github"))
+            .and(
+                predicates::str::is_match(r"runner\s+string\s+Which runner\?\s+\^\[a-z-\]\+\$")
+                    .unwrap(),
+            )
+            .and(predicates::str::contains("pre: pre-script.rhai"))
+            .from_utf8(),
+        );
+
+    assert!(!dir.exists("Cargo.toml"));
+}
+
+#[test]
+fn it_describes_a_template_as_json() {
+    let template = template();
+    let dir = tempdir().build();
+
+    let output = binary()
+        .arg("inspect")
+        .arg("--format")
+        .arg("json")
+        .arg_git(template.path())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let description: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        description["placeholders"][0],
+        serde_json::json!({
+            "name": "license",
+            "type": "string",
+            "prompt": "Which license?",
+            "default": "MIT",
+            "choices": ["MIT", "Apache-2.0"],
+            "regex": null,
+            "validate": null,
+            "when": null,
+            "computed": null,
+        })
+    );
+    assert_eq!(description["conditionals"][0]["condition"], "ci");
+    assert_eq!(
+        description["conditionals"][0]["placeholders"][0]["name"],
+        "runner"
+    );
+    assert_eq!(
+        description["hooks"]["pre"],
+        serde_json::json!(["pre-script.rhai"])
+    );
+}
+
+#[test]
+fn it_describes_the_default_sub_template() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                sub_templates = ["sub1", "sub2"]
+            "#},
+        )
+        .file(
+            "sub1/cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                flavor = { type = "string", prompt = "Which flavor?", default = "vanilla" }
+            "#},
+        )
+        .file("sub1/Cargo.toml", "")
+        .file(
+            "sub2/cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                color = { type = "string", prompt = "Which color?", default = "red" }
+            "#},
+        )
+        .file("sub2/Cargo.toml", "")
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("inspect")
+        .arg_path(template.path())
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match(r"flavor\s+string\s+Which flavor\?\s+vanilla")
+                .unwrap()
+                .and(predicates::str::contains("color").not())
+                .from_utf8(),
+        );
+}
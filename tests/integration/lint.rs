@@ -0,0 +1,63 @@
+use crate::helpers::prelude::*;
+
+#[test]
+fn it_accepts_a_valid_template() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                greeting = { prompt = "Greeting?", default = "hello" }
+            "#},
+        )
+        .file("README.md", "{{greeting}}, {{project-name}}!")
+        .build();
+
+    binary()
+        .arg("lint")
+        .arg(template.path())
+        .current_dir(template.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Template looks good").from_utf8());
+}
+
+#[test]
+fn it_reports_the_mistakes_of_a_template() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                exlude = ["raw.txt"]
+
+                [placeholders]
+                unused = { prompt = "Unused?" }
+
+                [hooks]
+                pre = ["missing.rhai"]
+            "#},
+        )
+        .file("README.md", "{% if project-name %}")
+        .build();
+
+    binary()
+        .arg("lint")
+        .current_dir(template.path())
+        .assert()
+        .failure()
+        .stdout(
+            predicates::str::contains("unknown key `template.exlude`")
+                .and(predicates::str::contains(
+                    "hook file `missing.rhai` does not exist",
+                ))
+                .and(predicates::str::contains(
+                    "README.md: invalid Liquid syntax",
+                ))
+                .and(predicates::str::contains(
+                    "placeholder `unused` is not used",
+                ))
+                .from_utf8(),
+        )
+        .stderr(predicates::str::contains("Template has 3 error(s) and 1 warning(s)").from_utf8());
+}
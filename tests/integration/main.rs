@@ -1,16 +1,29 @@
 mod helpers;
 
 // test modules go here
+mod archive;
 mod basics;
 mod conditionals;
 mod config_file;
+mod custom_filters;
+mod dry_run;
+mod extends;
 mod filenames;
 mod git;
+mod git_cache;
 mod git_instead_of;
 #[cfg(e2e_tests_with_ssh_key)]
 mod git_over_ssh;
 mod hooks_and_rhai;
+mod inspect;
+mod lint;
+mod message_format;
+mod partials;
 mod public_api;
+mod save_values;
+mod structured_values;
 mod template_config_file;
 mod template_filters;
+mod unknown_keys;
+mod update;
 mod workspace_member;
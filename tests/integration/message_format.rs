@@ -0,0 +1,137 @@
+use crate::helpers::prelude::*;
+
+use serde_json::{json, Value};
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                ignore = ["ignored.txt"]
+                exclude = ["raw.txt"]
+
+                [placeholders]
+                ci = { type = "bool", prompt = "CI?", default = true }
+
+                [hooks]
+                pre = ["pre-script.rhai"]
+            "#},
+        )
+        .file(
+            "pre-script.rhai",
+            indoc! {r#"
+                print("hello from the hook");
+                variable::set("license", "MIT");
+            "#},
+        )
+        .file("Cargo.toml", "[package]\nname = \"{{project-name}}\"\n")
+        .file("raw.txt", "{{project-name}}")
+        .file("ignored.txt", "nothing to see")
+        .init_git()
+        .build()
+}
+
+fn events(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line of stdout is a JSON event"))
+        .collect()
+}
+
+fn find<'a>(events: &'a [Value], event: &str, key: &str, value: &str) -> &'a Value {
+    events
+        .iter()
+        .find(|e| e["event"] == event && e[key] == value)
+        .unwrap_or_else(|| panic!("no `{event}` event with {key} = {value} in {events:#?}"))
+}
+
+#[test]
+fn it_prints_one_json_event_per_line() {
+    let template = template();
+    let dir = tempdir().build();
+
+    let output = binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .arg("--message-format")
+        .arg("json")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events = events(&output.stdout);
+    assert_eq!(events[0]["event"], "source_resolved");
+    assert_eq!(
+        events[1],
+        json!({ "event": "template_dir_selected", "subfolder": null })
+    );
+    assert_eq!(
+        find(&events, "placeholder_value", "name", "project-name")["source"],
+        "provided"
+    );
+    assert_eq!(
+        find(&events, "placeholder_value", "name", "ci"),
+        &json!({ "event": "placeholder_value", "name": "ci", "value": true, "source": "default" })
+    );
+    assert_eq!(
+        find(&events, "placeholder_value", "name", "license")["source"],
+        "hook"
+    );
+    assert_eq!(
+        find(&events, "hook_finished", "script", "pre-script.rhai")["output"],
+        "hello from the hook\n"
+    );
+    assert_eq!(
+        find(&events, "file", "path", "Cargo.toml")["action"],
+        "rendered"
+    );
+    assert_eq!(find(&events, "file", "path", "raw.txt")["action"], "copied");
+    assert_eq!(
+        find(&events, "file", "path", "ignored.txt")["action"],
+        "ignored"
+    );
+    assert_eq!(events.last().unwrap()["event"], "result");
+    assert_eq!(events.last().unwrap()["success"], true);
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Done!"));
+    assert!(dir.exists("foobar-project/Cargo.toml"));
+}
+
+#[test]
+fn it_reports_a_failure_as_the_result() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                license = { prompt = "License?" }
+            "#},
+        )
+        .file("Cargo.toml", "[package]\nname = \"{{project-name}}\"\n")
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    let output = binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .arg("--message-format")
+        .arg("json")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let events = events(&output.stdout);
+    let result = events.last().unwrap();
+    assert_eq!(result["event"], "result");
+    assert_eq!(result["success"], false);
+    assert!(result["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("license"));
+}
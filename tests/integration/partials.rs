@@ -0,0 +1,84 @@
+use crate::helpers::prelude::*;
+
+#[test]
+fn partials_can_be_included_and_rendered_in_contents_and_file_names() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                partials = "_partials"
+            "#},
+        )
+        .file("_partials/header.rs", "// {{project-name}}, licensed MIT")
+        .file("_partials/ci/step.yml", "- run: cargo {{ command }}")
+        .file("_partials/module", "{{ name | snake_case }}")
+        .file(
+            "src/main.rs",
+            indoc! {r#"
+                {% include 'header.rs' %}
+                fn main() {}
+            "#},
+        )
+        .file(
+            "ci.yml",
+            indoc! {r#"
+                {% render 'ci/step.yml', command: "build" %}
+                {% render 'ci/step.yml', command: "test" %}
+            "#},
+        )
+        .file(
+            "src/{% render 'module', name: project-name %}.rs",
+            "{% include 'header.rs' %}",
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/src/main.rs"),
+        "// foobar-project, licensed MIT\nfn main() {}\n"
+    );
+    assert_eq!(
+        dir.read("foobar-project/ci.yml"),
+        "- run: cargo build\n- run: cargo test\n"
+    );
+    assert_eq!(
+        dir.read("foobar-project/src/foobar_project.rs"),
+        "// foobar-project, licensed MIT"
+    );
+    assert!(!dir.exists("foobar-project/_partials"));
+}
+
+#[test]
+fn a_missing_partials_folder_is_an_error() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                partials = "_partials"
+            "#},
+        )
+        .file("README.md", "{{project-name}}")
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("The partials folder does not exist").from_utf8());
+}
@@ -1,6 +1,6 @@
 use crate::helpers::prelude::*;
 
-use cargo_generate::{generate, GenerateArgs, TemplatePath};
+use cargo_generate::{generate, GenerateArgs, MessageFormat, TemplatePath};
 
 #[test]
 fn it_allows_generate_call_with_public_args_and_returns_the_generated_path() {
@@ -11,6 +11,7 @@ fn it_allows_generate_call_with_public_args_and_returns_the_generated_path() {
     let dir = tempdir().build().root.keep();
 
     let args_exposed: GenerateArgs = GenerateArgs {
+        command: None,
         template_path: TemplatePath {
             auto_path: None,
             git: Some(format!("{}", template.path().display())),
@@ -27,6 +28,7 @@ fn it_allows_generate_call_with_public_args_and_returns_the_generated_path() {
         vcs: None,
         verbose: true,
         template_values_file: None,
+        save_values: None,
         silent: false,
         continue_on_error: false,
         quiet: false,
@@ -45,6 +47,11 @@ fn it_allows_generate_call_with_public_args_and_returns_the_generated_path() {
         other_args: None,
         skip_submodules: false,
         no_workspace: false,
+        provenance: false,
+        dry_run: false,
+        strict: false,
+        message_format: MessageFormat::Human,
+        offline: false,
     };
 
     assert_eq!(
@@ -0,0 +1,125 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                description = { type = "string", prompt = "Description?" }
+                ci = { type = "bool", prompt = "CI?", default = false }
+                features = { type = "array", prompt = "Features?", choices = ["serde", "tokio", "clap"] }
+
+                [hooks]
+                pre = ["pre-script.rhai"]
+            "#},
+        )
+        .file(
+            "pre-script.rhai",
+            indoc! {r#"
+                variable::set("license", "MIT");
+            "#},
+        )
+        .file(
+            "README.md",
+            indoc! {r#"
+                {{description}}
+                ci: {{ci}}
+                features: {{features | join: ", "}}
+                license: {{license}}
+            "#},
+        )
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_saves_all_resolved_values() {
+    let template = template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values]
+                features = ["serde", "clap"]
+            "#},
+        )
+        .build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .arg("-d")
+        .arg("description=A demo")
+        .arg("--save-values")
+        .arg("saved.toml")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Values saved to").from_utf8());
+
+    let saved: toml::Value = toml::from_str(&dir.read("saved.toml")).unwrap();
+    assert_eq!(
+        saved,
+        toml::from_str(indoc! {r#"
+            [values]
+            description = "A demo"
+            ci = false
+            features = ["serde", "clap"]
+            license = "MIT"
+        "#})
+        .unwrap()
+    );
+}
+
+#[test]
+fn saved_values_reproduce_the_project() {
+    let template = template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values]
+                description = "A demo"
+                ci = true
+                features = ["tokio"]
+            "#},
+        )
+        .build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("first")
+        .arg("--silent")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .arg("--save-values")
+        .arg("saved.toml")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("second")
+        .arg("--silent")
+        .arg("--values-file")
+        .arg(dir.path().join("saved.toml"))
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("first/README.md"),
+        indoc! {r#"
+            A demo
+            ci: true
+            features: tokio
+            license: MIT
+        "#}
+    );
+    assert_eq!(dir.read("second/README.md"), dir.read("first/README.md"));
+}
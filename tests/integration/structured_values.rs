@@ -0,0 +1,123 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                description = { type = "string", prompt = "Description?", default = "A service" }
+
+                [conditional.'database.port == 5432']
+                placeholders = { schema = { type = "string", prompt = "Schema?", default = "public" } }
+
+                [hooks]
+                pre = ["pre-script.rhai"]
+            "#},
+        )
+        .file(
+            "pre-script.rhai",
+            indoc! {r#"
+                let database = variable::get("database");
+                variable::set("database_url", `postgres://${database.host}:${database.port}`);
+            "#},
+        )
+        .file(
+            "README.md",
+            indoc! {r#"
+                {{description}}
+                {{database_url}}/{{schema}}
+                {% for svc in services %}- {{ svc.name }} on {{ svc.port }}
+                {% endfor %}"#},
+        )
+        .init_git()
+        .build()
+}
+
+#[test]
+fn tables_of_a_values_file_are_liquid_objects() {
+    let template = template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values.database]
+                host = "localhost"
+                port = 5432
+
+                [[values.services]]
+                name = "api"
+                port = 8080
+
+                [[values.services]]
+                name = "worker"
+                port = 8081
+            "#},
+        )
+        .build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .arg("--save-values")
+        .arg("saved.toml")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/README.md"),
+        indoc! {r#"
+            A service
+            postgres://localhost:5432/public
+            - api on 8080
+            - worker on 8081
+        "#}
+    );
+
+    let saved: toml::Value = toml::from_str(&dir.read("saved.toml")).unwrap();
+    assert_eq!(saved["values"]["database"]["port"].as_integer(), Some(5432));
+    assert_eq!(
+        saved["values"]["services"][1]["name"].as_str(),
+        Some("worker")
+    );
+}
+
+#[test]
+fn conditionals_see_the_fields_of_tables() {
+    let template = template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values]
+                services = []
+
+                [values.database]
+                host = "db"
+                port = 5433
+            "#},
+        )
+        .build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--silent")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/README.md"),
+        indoc! {r#"
+            A service
+            postgres://db:5433/
+        "#}
+    );
+}
@@ -139,7 +139,7 @@ fn it_fails_for_arrays_without_defaults_in_silent_mode() {
         .assert()
         .failure()
         .stderr(contains(
-            "variable `formats` is missing default value in config file running in silent mode",
+            "no value for `formats`, and no way to ask while not running interactively",
         ));
 }
 
@@ -259,3 +259,768 @@ fn it_renders_arrays_as_list() {
         r#"["esp32", "esp32c6"]"#
     );
 }
+
+fn number_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                port = { type = "integer", prompt = "Port?", min = 1024, max = 65535, default = 8080 }
+                ratio = { type = "float", prompt = "Ratio?", min = 0.0, max = 1.0, step = 0.25, default = 0.5 }
+
+                [conditional.'port <= 9000']
+                ignore = ["high.txt"]
+            "#},
+        )
+        .file(
+            "config.txt",
+            indoc! {r#"
+                port={{ port }} next={{ port | plus: 1 }}
+                ratio={{ ratio | times: 2 }}
+                {% if port > 9000 %}high{% else %}low{% endif %}
+            "#},
+        )
+        .file("high.txt", "")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_renders_number_placeholders_as_numbers() {
+    let template = number_template();
+    let values = tempdir()
+        .file("values.toml", "[values]\nport = 9443\n")
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--template-values-file")
+        .arg(values.path().join("values.toml"))
+        .args(["--define", "ratio=0.75"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/config.txt"),
+        "port=9443 next=9444\nratio=1.5\nhigh\n"
+    );
+    assert!(dir.exists("foobar-project/high.txt"));
+}
+
+#[test]
+fn it_uses_number_defaults_in_silent_mode() {
+    let template = number_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/config.txt"),
+        "port=8080 next=8081\nratio=1\nlow\n"
+    );
+    assert!(!dir.exists("foobar-project/high.txt"));
+}
+
+#[test]
+fn it_fails_on_numbers_out_of_bounds() {
+    let template = number_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "port=80"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "is not a valid value for port, it must be at least 1024",
+        ));
+}
+
+fn validated_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                version = { prompt = "Version?", validate = "semver", regex = '^0\.', default = "0.1.0" }
+                license = { prompt = "License?", validate = "spdx", default = "MIT OR Apache-2.0" }
+            "#},
+        )
+        .file("Cargo.toml", "version = \"{{ version }}\"\nlicense = \"{{ license }}\"\n")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_accepts_values_passing_their_validator() {
+    let template = validated_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "license=(MIT OR Apache-2.0) AND Unicode-3.0"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/Cargo.toml"),
+        "version = \"0.1.0\"\nlicense = \"(MIT OR Apache-2.0) AND Unicode-3.0\"\n"
+    );
+}
+
+#[test]
+fn it_fails_on_values_failing_their_validator() {
+    let template = validated_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "version=0.1"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "is not a valid value for version, it must be a semantic version like 1.2.3",
+        ));
+}
+
+#[test]
+fn it_checks_the_regex_before_the_validator() {
+    let template = validated_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "version=1.0.0"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            r"is not a valid value for version, it does not match the regex `^0\.`",
+        ));
+}
+
+fn when_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                use_db = { type = "bool", prompt = "Database?", default = false }
+                db_kind = { prompt = "Which database?", choices = ["postgres", "sqlite"], default = "sqlite", when = "use_db" }
+                db_url = { prompt = "Database URL?", when = 'use_db && db_kind == "postgres"' }
+            "#},
+        )
+        .file(
+            "config.txt",
+            indoc! {r#"
+                kind={{ db_kind }}
+                {% if db_kind == "postgres" %}url={{ db_url }}{% endif %}
+            "#},
+        )
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_skips_placeholders_whose_when_is_false() {
+    let template = when_template();
+    let dir = tempdir().build();
+
+    // `db_url` has no default, but isn't needed in silent mode, as it's skipped
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(dir.read("foobar-project/config.txt"), "kind=sqlite\n\n");
+}
+
+#[test]
+fn it_asks_placeholders_whose_when_is_true() {
+    let template = when_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "use_db=true", "--define", "db_kind=postgres"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains("no value for `db_url`"));
+
+    let dir = tempdir().build();
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "use_db=true", "--define", "db_kind=postgres"])
+        .args(["--define", "db_url=postgres://localhost"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/config.txt"),
+        "kind=postgres\nurl=postgres://localhost\n"
+    );
+}
+
+fn computed_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                api-url = { computed = { liquid = "{{ base-url }}/api" } }
+                base-url = { computed = { liquid = "http://{{ address }}" } }
+                address = { computed = { liquid = "{{host}}:{{service_port}}" } }
+                service_port = { type = "integer", computed = { rhai = "8000 + index" } }
+                module_path = { computed = { liquid = '{{ crate_name | replace: "_", "::" }}' } }
+                index = { type = "integer", prompt = "Index?", default = 1 }
+                host = { prompt = "Host?", default = "localhost" }
+            "#},
+        )
+        .file(
+            "config.txt",
+            indoc! {r#"
+                address={{ address }} next={{ service_port | plus: 1 }}
+                module={{ module_path }}
+                api={{ api-url }}
+            "#},
+        )
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_computes_placeholders_from_other_values() {
+    let template = computed_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foo_bar")
+        .args(["--define", "index=2"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(contains("service_port: \"8002\" (computed placeholder)"));
+
+    assert_eq!(
+        dir.read("foo_bar/config.txt"),
+        "address=localhost:8002 next=8003\nmodule=foo::bar\napi=http://localhost:8002/api\n"
+    );
+}
+
+#[test]
+fn it_lets_defined_values_override_computed_placeholders() {
+    let template = computed_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foo_bar")
+        .args(["--define", "service_port=9000"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foo_bar/config.txt"),
+        "address=localhost:9000 next=9001\nmodule=foo::bar\napi=http://localhost:9000/api\n"
+    );
+}
+
+#[test]
+fn it_fails_on_computed_placeholders_depending_on_each_other() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                a = { computed = { rhai = "b + 1" } }
+                b = { computed = { rhai = "a - 1" } }
+            "#},
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "computed placeholders depend on each other: `a` -> `b` -> `a`",
+        ));
+}
+
+#[test]
+fn it_renders_liquid_defaults_with_earlier_values() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                org = { prompt = "Organization?", default = "acme" }
+                docker_image = { prompt = "Image?", default = "ghcr.io/{{ org }}/{{ project-name }}", regex = '^ghcr\.io/' }
+                binary_name = { prompt = "Binary?", default = "{{ crate_name | kebab_case }}", choices = ["foo-bar", "other"] }
+            "#},
+        )
+        .file(
+            "config.txt",
+            "image={{ docker_image }} bin={{ binary_name }}\n",
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foo_bar")
+        .args(["--define", "org=example"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(contains(
+            "docker_image: \"ghcr.io/example/foo_bar\" (default value from template)",
+        ));
+
+    assert_eq!(
+        dir.read("foo_bar/config.txt"),
+        "image=ghcr.io/example/foo_bar bin=foo-bar\n"
+    );
+}
+
+#[test]
+fn it_checks_rendered_liquid_defaults() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                binary_name = { prompt = "Binary?", default = "{{ crate_name }}", validate = "url" }
+            "#},
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foo_bar")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "is not a valid value for binary_name, it must be an absolute URL",
+        ));
+}
+
+fn secret_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                api_token = { type = "secret", prompt = "API token?", regex = '^tok_' }
+                region = { prompt = "Region?", default = "eu" }
+            "#},
+        )
+        .file(".env", "API_TOKEN={{ api_token }}\nREGION={{ region }}\n")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_fills_secrets_without_showing_them() {
+    let template = secret_template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values]
+                api_token = "tok_hunter2"
+            "#},
+        )
+        .build();
+
+    let output = binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .args(["--message-format", "json"])
+        .args(["--save-values", "saved.toml"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stdout.contains("hunter2") && !stderr.contains("hunter2"));
+    assert!(stdout.contains(r#""name":"api_token","value":"********""#));
+
+    assert_eq!(
+        dir.read("foobar-project/.env"),
+        "API_TOKEN=tok_hunter2\nREGION=eu\n"
+    );
+    let saved = dir.read("saved.toml");
+    assert!(saved.contains("region") && !saved.contains("api_token"));
+}
+
+#[test]
+fn it_does_not_show_invalid_secrets() {
+    let template = secret_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_VALUE_API_TOKEN", "hunter2")
+        .assert()
+        .failure()
+        .stderr(
+            contains("\"********\" is not a valid value for api_token")
+                .and(contains("hunter2").not()),
+        );
+}
+
+#[test]
+fn it_rejects_secrets_on_the_command_line() {
+    let template = secret_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "api_token=tok_hunter2"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "The secret `api_token` can't be passed with `--define`",
+        ))
+        .stdout(contains("hunter2").not());
+}
+
+#[test]
+fn it_does_not_show_secrets_of_conditionals_set_by_hooks() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [hooks]
+                init = ["init.rhai"]
+
+                [placeholders]
+                use_api = { type = "bool", prompt = "API?", default = true }
+                region = { prompt = "Region?", default = "eu" }
+
+                [conditional.'use_api'.placeholders]
+                api_token = { type = "secret", prompt = "API token?" }
+            "#},
+        )
+        .file("init.rhai", r#"variable::set("api_token", "tok_hunter2");"#)
+        .file(".env", "API_TOKEN={{ api_token }}\n")
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    let output = binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "region=us"])
+        .args(["--message-format", "json"])
+        .args(["--save-values", "saved.toml"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stdout.contains("hunter2") && !stderr.contains("hunter2"));
+    assert!(stdout.contains(r#""name":"api_token","value":"********""#));
+    assert!(stderr.contains(r#"region: "us" (value from CLI)"#));
+
+    assert_eq!(dir.read("foobar-project/.env"), "API_TOKEN=tok_hunter2\n");
+    let saved = dir.read("saved.toml");
+    assert!(saved.contains("region") && !saved.contains("api_token"));
+}
+
+fn labeled_choices_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                runtime = { prompt = "Which runtime?", default = "tokio-multi-thread", choices = [
+                    { value = "tokio-multi-thread", label = "Tokio (multi-threaded runtime)", help = "recommended" },
+                    { value = "tokio-current-thread", label = "Tokio (current thread)" },
+                    "none",
+                ] }
+                features = { type = "array", prompt = "Features?", default = [], choices = [
+                    { value = "serde", label = "Serialization", help = "with serde" },
+                    "clap",
+                ] }
+            "#},
+        )
+        .file("config.txt", "{{ runtime }} {{ features | join: \",\" }}\n")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_only_puts_the_value_of_labeled_choices_into_the_template() {
+    let template = labeled_choices_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "runtime=tokio-current-thread"])
+        .args(["--define", "features=serde,clap"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("foobar-project/config.txt"),
+        "tokio-current-thread serde,clap\n"
+    );
+}
+
+#[test]
+fn it_does_not_accept_the_label_of_a_choice() {
+    let template = labeled_choices_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "features=Serialization"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "\"Serialization\" is not a valid value for features",
+        ));
+}
+
+fn free_form_list_template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                binaries = { type = "array", prompt = "Which extra binaries?", validate = "rust_ident", min_items = 1, max_items = 3 }
+            "#},
+        )
+        .file(
+            "Cargo.toml",
+            indoc! {r#"
+                {% for bin in binaries %}[[bin]]
+                name = "{{ bin }}"
+                {% endfor %}"#},
+        )
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_takes_free_form_lists() {
+    let template = free_form_list_template();
+    let dir = tempdir()
+        .file(
+            "values.toml",
+            indoc! {r#"
+                [values]
+                binaries = ["server", "worker"]
+            "#},
+        )
+        .build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("from-define")
+        .args(["--define", "binaries=cli, migrate"])
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("from-values-file")
+        .arg("--values-file")
+        .arg(dir.path().join("values.toml"))
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        dir.read("from-define/Cargo.toml"),
+        "[[bin]]\nname = \"cli\"\n[[bin]]\nname = \"migrate\"\n"
+    );
+    assert_eq!(
+        dir.read("from-values-file/Cargo.toml"),
+        "[[bin]]\nname = \"server\"\n[[bin]]\nname = \"worker\"\n"
+    );
+}
+
+#[test]
+fn it_checks_the_items_of_free_form_lists() {
+    let template = free_form_list_template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "binaries=cli,fn"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "is not a valid value for binaries, it has the item `fn`, that must not be the rust keyword `fn`",
+        ));
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("too-many")
+        .args(["--define", "binaries=a,b,c,d"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains("it must have at most 3 items"));
+}
+
+#[test]
+fn it_reports_all_missing_values_at_once() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                use_db = { type = "bool", prompt = "Database?", default = false }
+                license = { prompt = "Which license?", choices = ["MIT", "Apache-2.0"] }
+                port = { type = "integer", prompt = "Which port?" }
+
+                [conditional.'use_db'.placeholders]
+                db_url = { prompt = "Database URL?" }
+            "#},
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    // stdin is not a terminal, so even without `--silent` nothing can be asked
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "use_db=true"])
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "no value for `license`, `port`, `db_url`, and no way to ask while not running interactively",
+        ))
+        .stderr(contains("Which license?"))
+        .stderr(contains("[values]\nlicense = \"\"  # MIT, Apache-2.0\nport = 0\ndb_url = \"\"\n"));
+}
+
+#[test]
+fn it_uses_defaults_and_reports_missing_values_without_a_terminal() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                greeting = { prompt = "Greeting?", default = "hi" }
+                license = { prompt = "Which license?" }
+                port = { type = "integer", prompt = "Which port?" }
+            "#},
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "no value for `license`, `port`, and no way to ask while not running interactively",
+        ))
+        .stderr(contains("not a terminal").not());
+}
+
+#[test]
+fn it_only_logs_defaults_that_are_used() {
+    let template = tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                greeting = { prompt = "Greeting?", default = "hi" }
+                farewell = { prompt = "Farewell?", default = "bye" }
+            "#},
+        )
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg("--silent")
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .args(["--define", "greeting=hello"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(contains("farewell: \"bye\"").and(contains("greeting: \"hi\"").not()));
+}
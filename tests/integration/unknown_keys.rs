@@ -0,0 +1,58 @@
+use crate::helpers::prelude::*;
+
+fn template() -> Project {
+    tempdir()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [template]
+                exlude = ["raw.txt"]
+            "#},
+        )
+        .file("Cargo.toml", "[package]\nname = \"{{project-name}}\"\n")
+        .init_git()
+        .build()
+}
+
+#[test]
+fn it_warns_about_unknown_keys_with_a_suggestion() {
+    let template = template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Unknown key in cargo-generate.toml")
+                .and(predicates::str::contains(
+                    "`template.exlude`, did you mean `exclude`?",
+                ))
+                .from_utf8(),
+        );
+
+    assert!(dir.exists("foobar-project/Cargo.toml"));
+}
+
+#[test]
+fn it_fails_on_unknown_keys_if_strict() {
+    let template = template();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--strict")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(
+            predicates::str::contains("Unknown keys in cargo-generate.toml")
+                .and(predicates::str::contains("`template.exlude`"))
+                .from_utf8(),
+        );
+
+    assert!(!dir.exists("foobar-project"));
+}
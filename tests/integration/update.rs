@@ -0,0 +1,240 @@
+use crate::helpers::prelude::*;
+
+const README: &str = indoc! {r#"
+    # {{project-name}}
+
+    Greeting: {{greeting}}
+
+    Some
+    filler
+    lines
+    to
+    keep
+    the
+    changes
+    apart
+
+    The end
+"#};
+
+fn template_with_readme() -> Project {
+    tempdir()
+        .with_default_manifest()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                greeting = { type = "string", prompt = "Greeting?", default = "hello" }
+            "#},
+        )
+        .file("README.md", README)
+        .init_git()
+        .build()
+}
+
+fn generate_with_provenance(template: &Project, dir: &Project) {
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--provenance")
+        .arg("--silent")
+        .args(["-d", "greeting=howdy"])
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Template provenance recorded in").from_utf8());
+}
+
+#[test]
+fn it_records_the_provenance_of_a_generated_project() {
+    let template = template_with_readme();
+    let dir = tempdir().build();
+
+    generate_with_provenance(&template, &dir);
+
+    let provenance = dir.read("foobar-project/.cargo-generate-provenance.toml");
+    assert!(provenance.contains(&format!("git = \"{}\"", template.path().display())));
+    assert!(provenance.contains("name = \"foobar-project\""));
+    assert!(provenance.contains("greeting = \"howdy\""));
+    let head = template.commit_shas().first().unwrap().clone();
+    assert!(provenance.contains(&format!("commit = \"{head}")));
+}
+
+#[test]
+fn it_does_not_record_the_commit_of_a_template_with_uncommitted_changes() {
+    let template = template_with_readme();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_path(template.path())
+        .arg_name("clean-project")
+        .arg("--provenance")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+    fs::write(template.path().join("NOTES.md"), "not committed\n").unwrap();
+    binary()
+        .arg_path(template.path())
+        .arg_name("dirty-project")
+        .arg("--provenance")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("The template has uncommitted changes").from_utf8());
+
+    let head = template.commit_shas().first().unwrap().clone();
+    assert!(dir
+        .read("clean-project/.cargo-generate-provenance.toml")
+        .contains(&format!("commit = \"{head}")));
+    assert!(!dir
+        .read("dirty-project/.cargo-generate-provenance.toml")
+        .contains("commit ="));
+}
+
+#[test]
+fn it_does_not_record_provenance_by_default() {
+    let template = tempdir().init_default_template().build();
+    let dir = tempdir().build();
+
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .success();
+
+    assert!(!dir.exists("foobar-project/.cargo-generate-provenance.toml"));
+}
+
+#[test]
+fn it_merges_template_changes_into_the_project() {
+    let template = template_with_readme();
+    let dir = tempdir().build();
+    generate_with_provenance(&template, &dir);
+
+    let readme = dir.target_path("foobar-project").join("README.md");
+    let local = dir
+        .read("foobar-project/README.md")
+        .replace("Some", "Several");
+    std::fs::write(&readme, local).unwrap();
+    template.commit_file("README.md", &README.replace("The end", "The very end"));
+    template.commit_file("CHANGELOG.md", "# {{project-name}}\n");
+
+    binary()
+        .arg("update")
+        .arg("foobar-project")
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done! Project updated").from_utf8());
+
+    let readme = dir.read("foobar-project/README.md");
+    assert!(readme.contains("Greeting: howdy"));
+    assert!(readme.contains("Several"));
+    assert!(readme.contains("The very end"));
+    assert_eq!(
+        dir.read("foobar-project/CHANGELOG.md"),
+        "# foobar-project\n"
+    );
+    let head = template.commit_shas().first().unwrap().clone();
+    assert!(dir
+        .read("foobar-project/.cargo-generate-provenance.toml")
+        .contains(&format!("commit = \"{head}")));
+}
+
+#[test]
+fn it_marks_conflicting_changes() {
+    let template = template_with_readme();
+    let dir = tempdir().build();
+    generate_with_provenance(&template, &dir);
+
+    let readme = dir.target_path("foobar-project").join("README.md");
+    let local = dir
+        .read("foobar-project/README.md")
+        .replace("The end", "Local end");
+    std::fs::write(&readme, local).unwrap();
+    template.commit_file("README.md", &README.replace("The end", "Template end"));
+
+    binary()
+        .arg("update")
+        .current_dir(dir.target_path("foobar-project"))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Conflict in").from_utf8());
+
+    let readme = dir.read("foobar-project/README.md");
+    assert!(readme.contains("<<<<<<< project"));
+    assert!(readme.contains("Local end"));
+    assert!(readme.contains("Template end"));
+}
+
+#[test]
+fn it_fails_to_update_a_project_without_provenance() {
+    let dir = tempdir().build();
+
+    binary()
+        .arg("update")
+        .current_dir(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--provenance").from_utf8());
+}
+
+#[test]
+fn it_updates_a_project_of_a_template_with_secrets() {
+    let template = tempdir()
+        .with_default_manifest()
+        .file(
+            "cargo-generate.toml",
+            indoc! {r#"
+                [placeholders]
+                token = { type = "secret", prompt = "Token?" }
+            "#},
+        )
+        .file("README.md", README.replace("{{greeting}}", "{{token}}"))
+        .init_git()
+        .build();
+    let dir = tempdir().build();
+    binary()
+        .arg_git(template.path())
+        .arg_name("foobar-project")
+        .arg("--provenance")
+        .arg("--silent")
+        .current_dir(dir.path())
+        .env("CARGO_GENERATE_VALUE_TOKEN", "hunter2")
+        .assert()
+        .success();
+    let provenance = dir.read("foobar-project/.cargo-generate-provenance.toml");
+    assert!(!provenance.contains("hunter2"));
+    assert!(provenance.contains("secrets = [\"token\"]"));
+    template.commit_file(
+        "README.md",
+        &README
+            .replace("{{greeting}}", "{{token}}")
+            .replace("The end", "The very end"),
+    );
+
+    binary()
+        .arg("update")
+        .current_dir(dir.target_path("foobar-project"))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("CARGO_GENERATE_VALUE_TOKEN").from_utf8())
+        .stderr(predicates::str::contains("--values-file").not().from_utf8());
+    binary()
+        .arg("update")
+        .current_dir(dir.target_path("foobar-project"))
+        .env("CARGO_GENERATE_VALUE_TOKEN", "hunter2")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Done! Project updated").from_utf8());
+
+    let readme = dir.read("foobar-project/README.md");
+    assert!(readme.contains("Greeting: hunter2"));
+    assert!(readme.contains("The very end"));
+    assert!(!dir
+        .read("foobar-project/.cargo-generate-provenance.toml")
+        .contains("hunter2"));
+}